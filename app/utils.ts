@@ -1,14 +1,17 @@
 export const calculateWinnings = (wagerDetail, betState) => {
   try {
 
-    const negThreeAndUnderPool: number = betState.negThreeAndUnderPool.toNumber();
-    const negThreeToNegTwoPool: number = betState.negThreeToNegTwoPool.toNumber();
-    const negTwoToNegOnePool: number = betState.negTwoToNegOnePool.toNumber();
-    const negOneToZeroPool: number = betState.negOneToZeroPool.toNumber();
-    const zeroToPosOnePool: number = betState.zeroToPosOnePool.toNumber();
-    const posOneToPosTwoPool: number = betState.posOneToPosTwoPool.toNumber();
-    const posTwoToPosThreePool: number = betState.posTwoToPosThreePool.toNumber();
-    const posThreeAndOverPool: number = betState.posThreeAndOverPool.toNumber();
+    // Bet states keep one pool per bet range, indexed by bucket
+    const [
+      negThreeAndUnderPool,
+      negThreeToNegTwoPool,
+      negTwoToNegOnePool,
+      negOneToZeroPool,
+      zeroToPosOnePool,
+      posOneToPosTwoPool,
+      posTwoToPosThreePool,
+      posThreeAndOverPool,
+    ]: number[] = betState.pools.map(pool => pool.toNumber());
 
     const totalBetPool = [negThreeAndUnderPool, negThreeToNegTwoPool, negTwoToNegOnePool, negOneToZeroPool, zeroToPosOnePool, posOneToPosTwoPool, posTwoToPosThreePool, posThreeAndOverPool].reduce((partialSum, a) => partialSum + a, 0)
 
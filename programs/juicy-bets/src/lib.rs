@@ -1,7 +1,18 @@
 use anchor_lang::prelude::*;
 use anchor_lang::prelude::program;
 use anchor_lang::prelude::Rent;
+use anchor_lang::AccountsClose;
+use anchor_lang::solana_program::program::invoke;
+use anchor_lang::solana_program::system_instruction;
 use anchor_lang::solana_program::system_program;
+use anchor_lang::solana_program::sysvar;
+
+pub mod migration;
+pub mod oracle;
+pub mod price;
+
+use oracle::{OracleKind, OraclePrice, OracleSource};
+use price::{pow10, Price, Rounding};
 
 declare_id!("CYpwqMgesShNFYrkLzpHC3NmaWqAXkiRZihAgekb535w");
 
@@ -9,115 +20,589 @@ declare_id!("CYpwqMgesShNFYrkLzpHC3NmaWqAXkiRZihAgekb535w");
 // Start thinking about dynamic ranges for each equity
 
 
-const TAKE_RATE: f64  = 1.02;
+// Fees are charged on top of the wager, the default protocol fee keeps the original 2% take rate
+const DEFAULT_PROTOCOL_FEE_BPS: u16 = 200;
+const MAX_TOTAL_FEE_BPS: u16 = 1_000;
+
+const BASIS_POINTS: u64 = 10_000;
+
+// Stored as the first field of every account and bumped whenever a layout changes,
+// accounts written before versioning have no version byte and count as version 0
+const ACCOUNT_VERSION: u8 = 1;
+
+// Symbols are stored zero-padded in a fixed number of bytes, on bet states and asset registry entries alike
+const MAX_SYMBOL_LEN: usize = 16;
+
+// Bet states used to have a fixed 1% between range boundaries, bet states from before bucket layouts still do
+const DEFAULT_BUCKET_WIDTH_BPS: u16 = 100;
+
+// How far in the past a bet state's start can be, so a client clock running a little ahead of the cluster isn't rejected
+const MAX_START_TIME_LAG_MS: u64 = 60_000;
 
 
 #[program]
 pub mod juicy_bets {
     use super::*;
 
-    // *** Betting Functionality *** //
+    // *** Config Functionality *** //
 
-    // TODO: Bet state should be initialized every market close 
-    // Endpoint that will initialize a bet state
-    pub fn initialize_bet_state(
-        ctx: Context<InitializeBetState>,
-        start: u64,
-        duration: u64,
+    // Endpoint that creates the global config holding the admin and the default betting limits for newly registered assets
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        default_limits: WagerLimits,
+        pyth_program: Pubkey,
+        switchboard_program: Pubkey,
+        default_oracle_guards: OracleGuards,
+    ) -> Result<()> {
+
+        let config = &mut ctx.accounts.config;
+
+        default_limits.validate()?;
+        default_oracle_guards.validate()?;
+
+        config.version = ACCOUNT_VERSION;
+        config.admin = ctx.accounts.admin.key();
+        config.default_limits = default_limits;
+        config.pyth_program = pyth_program;
+        config.switchboard_program = switchboard_program;
+        config.signed_feed_publishers = Vec::new();
+        config.default_oracle_guards = default_oracle_guards;
+        // The admin holds every role until it hands them out
+        config.resolver = ctx.accounts.admin.key();
+        config.pauser = ctx.accounts.admin.key();
+        config.paused = false;
+        // Disputes stay off until the admin sets a window and a bond
+        config.arbitration_authority = ctx.accounts.admin.key();
+        config.dispute_bond = 0;
+        config.dispute_window_secs = 0;
+        config.creator_bond = 0;
+        // Creators can't charge a fee until the admin allows one
+        config.treasury = ctx.accounts.admin.key();
+        config.protocol_fee_bps = DEFAULT_PROTOCOL_FEE_BPS;
+        config.max_creator_fee_bps = 0;
+        config.referral_share_bps = 0;
+        // Everyone pays the market's protocol fee until the admin sets up tiers
+        config.fee_tiers = Vec::new();
+        config.fee_season = 0;
+        config.bump = *ctx.bumps.get("config").unwrap();
+
+        Ok(())
+    }
+
+    // Endpoint that allows the admin to change the default limits newly registered assets start with
+    pub fn update_default_limits(ctx: Context<UpdateConfig>, default_limits: WagerLimits) -> Result<()> {
+
+        let config = &mut ctx.accounts.config;
+
+        default_limits.validate()?;
+
+        config.default_limits = default_limits;
+
+        Ok(())
+    }
+
+    // Endpoint that allows the admin to change the oracle programs price feeds must belong to and the default resolution guards
+    pub fn update_oracle_config(
+        ctx: Context<UpdateConfig>,
+        pyth_program: Pubkey,
+        switchboard_program: Pubkey,
+        default_oracle_guards: OracleGuards,
+    ) -> Result<()> {
+
+        let config = &mut ctx.accounts.config;
+
+        default_oracle_guards.validate()?;
+
+        config.pyth_program = pyth_program;
+        config.switchboard_program = switchboard_program;
+        config.default_oracle_guards = default_oracle_guards;
+
+        Ok(())
+    }
+
+    // Endpoint that allows the admin to replace the whitelist of keys trusted to sign off-chain prices
+    pub fn set_signed_feed_publishers(ctx: Context<UpdateConfig>, publishers: Vec<Pubkey>) -> Result<()> {
+
+        let config = &mut ctx.accounts.config;
+
+        if publishers.len() > GlobalConfig::MAX_SIGNED_FEED_PUBLISHERS {
+            return Err(error!(ErrorCode::TooManyPricePublishers));
+        }
+
+        config.signed_feed_publishers = publishers;
+
+        Ok(())
+    }
+
+    // Endpoint that allows the admin to set who rules on disputes, the bond a dispute costs and how long after an
+    // outcome is decided it can be disputed. The window is copied into bet states when they are created.
+    pub fn update_dispute_config(
+        ctx: Context<UpdateConfig>,
+        arbitration_authority: Pubkey,
+        dispute_bond: u64,
+        dispute_window_secs: u64,
+    ) -> Result<()> {
+
+        let config = &mut ctx.accounts.config;
+
+        config.arbitration_authority = arbitration_authority;
+        config.dispute_bond = dispute_bond;
+        config.dispute_window_secs = dispute_window_secs;
+
+        Ok(())
+    }
+
+    // Endpoint that allows the admin to set the bond anyone creating a bet state has to post
+    pub fn update_creator_bond(ctx: Context<UpdateConfig>, creator_bond: u64) -> Result<()> {
+
+        let config = &mut ctx.accounts.config;
+
+        config.creator_bond = creator_bond;
+
+        Ok(())
+    }
+
+    // Endpoint that allows the admin to set the treasury, the protocol fee and the cap on creator fees
+    pub fn update_fee_config(
+        ctx: Context<UpdateConfig>,
+        treasury: Pubkey,
+        protocol_fee_bps: u16,
+        max_creator_fee_bps: u16,
+    ) -> Result<()> {
+
+        let config = &mut ctx.accounts.config;
+
+        if protocol_fee_bps as u32 + max_creator_fee_bps as u32 > MAX_TOTAL_FEE_BPS as u32
+            || config.fee_tiers.iter().any(|tier| tier.protocol_fee_bps as u32 + max_creator_fee_bps as u32 > MAX_TOTAL_FEE_BPS as u32)
+        {
+            return Err(error!(ErrorCode::InvalidFeeConfig));
+        }
+
+        config.treasury = treasury;
+        config.protocol_fee_bps = protocol_fee_bps;
+        config.max_creator_fee_bps = max_creator_fee_bps;
+
+        Ok(())
+    }
+
+    // Endpoint that allows the admin to replace the volume-based protocol fee tiers
+    pub fn update_fee_tiers(ctx: Context<UpdateConfig>, fee_tiers: Vec<FeeTier>) -> Result<()> {
+
+        let config = &mut ctx.accounts.config;
+
+        if fee_tiers.len() > GlobalConfig::MAX_FEE_TIERS {
+            return Err(error!(ErrorCode::InvalidFeeTiers));
+        }
+
+        // Tiers are listed from the lowest volume up, each one has to need more volume than the last
+        for (index, tier) in fee_tiers.iter().enumerate() {
+            if index > 0 && tier.min_volume <= fee_tiers[index - 1].min_volume {
+                return Err(error!(ErrorCode::InvalidFeeTiers));
+            }
+            if tier.protocol_fee_bps as u32 + config.max_creator_fee_bps as u32 > MAX_TOTAL_FEE_BPS as u32 {
+                return Err(error!(ErrorCode::InvalidFeeTiers));
+            }
+        }
+
+        config.fee_tiers = fee_tiers;
+
+        Ok(())
+    }
+
+    // Endpoint that allows the admin to start a new fee season, every user's volume counts from zero again
+    pub fn start_fee_season(ctx: Context<UpdateConfig>) -> Result<()> {
+
+        let config = &mut ctx.accounts.config;
+
+        config.fee_season += 1;
+
+        Ok(())
+    }
+
+    // Endpoint that allows the admin to set the share of the protocol fee paid to a referred bettor's referrer
+    pub fn update_referral_share(ctx: Context<UpdateConfig>, referral_share_bps: u16) -> Result<()> {
+
+        let config = &mut ctx.accounts.config;
+
+        if referral_share_bps as u64 > BASIS_POINTS {
+            return Err(error!(ErrorCode::InvalidFeeConfig));
+        }
+
+        config.referral_share_bps = referral_share_bps;
+
+        Ok(())
+    }
+
+    // Endpoint that allows the admin to hand out the resolver role, which decides outcomes by hand, and the pauser role
+    pub fn update_roles(ctx: Context<UpdateConfig>, resolver: Pubkey, pauser: Pubkey) -> Result<()> {
+
+        let config = &mut ctx.accounts.config;
+
+        config.resolver = resolver;
+        config.pauser = pauser;
+
+        Ok(())
+    }
+
+    // Endpoint that allows the pauser to stop or restart the creation of bet states and the placing of wagers.
+    // Everything else, cancelling wagers and resolving and claiming included, keeps working while paused.
+    pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+
+        let config = &mut ctx.accounts.config;
+
+        config.paused = paused;
+
+        Ok(())
+    }
+
+    // *** Asset Registry Functionality *** //
+
+    // Endpoint for the admin to allow bet states on a symbol, read from the given oracle feed. New bet states on it
+    // take the asset's bucket layout and, unless the creator sets their own, its limits, which start as the config's.
+    pub fn register_asset(
+        ctx: Context<RegisterAsset>,
         symbol: String,
-        snapshot_price: u128,
+        asset_class: AssetClass,
+        oracle_kind: OracleKind,
+        bucket_layout: BucketLayout,
+        default_limits: Option<WagerLimits>,
+        trading_hours: TradingHours,
+    ) -> Result<()> {
+        let config = &ctx.accounts.config;
+        oracle::validate_feed(oracle_kind, &ctx.accounts.oracle_feed, config)?;
+
+        let default_limits = default_limits.unwrap_or(config.default_limits);
+        bucket_layout.validate()?;
+        default_limits.validate()?;
+        trading_hours.validate()?;
+        asset_class.validate_closures(&trading_hours, &[])?;
+
+        let asset = &mut ctx.accounts.asset;
+        asset.version = ACCOUNT_VERSION;
+        asset.symbol = pack_symbol(&symbol)?;
+        asset.asset_class = asset_class;
+        asset.oracle_kind = oracle_kind;
+        asset.oracle_feed = ctx.accounts.oracle_feed.key();
+        asset.bucket_layout = bucket_layout;
+        asset.default_limits = default_limits;
+        asset.trading_hours = trading_hours;
+        asset.holidays = vec![];
+        asset.enabled = true;
+        asset.bump = *ctx.bumps.get("asset").unwrap();
+        Ok(())
+    }
+
+    // Endpoint for the admin to change the defaults new bet states on an asset are created with
+    pub fn update_asset(
+        ctx: Context<UpdateAsset>,
+        asset_class: AssetClass,
+        bucket_layout: BucketLayout,
+        default_limits: WagerLimits,
+        trading_hours: TradingHours,
+    ) -> Result<()> {
+        bucket_layout.validate()?;
+        default_limits.validate()?;
+        trading_hours.validate()?;
+
+        let asset = &mut ctx.accounts.asset;
+        asset_class.validate_closures(&trading_hours, &asset.holidays)?;
+        asset.asset_class = asset_class;
+        asset.bucket_layout = bucket_layout;
+        asset.default_limits = default_limits;
+        asset.trading_hours = trading_hours;
+        Ok(())
+    }
+
+    // Endpoint for the admin to move an asset to another oracle feed, bet states already created keep theirs
+    pub fn set_asset_oracle(ctx: Context<SetAssetOracle>, oracle_kind: OracleKind) -> Result<()> {
+        oracle::validate_feed(oracle_kind, &ctx.accounts.oracle_feed, &ctx.accounts.config)?;
+
+        let asset = &mut ctx.accounts.asset;
+        asset.oracle_kind = oracle_kind;
+        asset.oracle_feed = ctx.accounts.oracle_feed.key();
+        Ok(())
+    }
+
+    // Endpoint for the admin to replace the days an asset's market is closed on top of its weekly hours,
+    // given as days since the unix epoch in UTC
+    pub fn set_asset_holidays(ctx: Context<UpdateAsset>, holidays: Vec<u32>) -> Result<()> {
+        if holidays.len() > AssetEntry::MAX_HOLIDAYS {
+            return Err(error!(ErrorCode::TooManyHolidays));
+        }
+
+        let asset = &mut ctx.accounts.asset;
+        asset.asset_class.validate_closures(&asset.trading_hours, &holidays)?;
+        asset.holidays = holidays;
+        Ok(())
+    }
+
+    // Endpoint for the admin to stop or resume new bet states on an asset without losing its entry
+    pub fn set_asset_enabled(ctx: Context<UpdateAsset>, enabled: bool) -> Result<()> {
+        ctx.accounts.asset.enabled = enabled;
+        Ok(())
+    }
+
+    // Endpoint for the admin to take a symbol off the registry, bet states already created on it are unaffected
+    pub fn deregister_asset(_ctx: Context<DeregisterAsset>) -> Result<()> {
+        Ok(())
+    }
+
+    // *** Betting Functionality *** //
+
+    // Endpoint that will initialize a bet state, anyone can create one by posting the config's creator bond.
+    // The bond is held in escrow until the bet state settles and is slashed to its bettors if the creator is at fault.
+    // The snapshot price every outcome is measured against is read from the bet's oracle sources here, with the same
+    // guards and quorum used at resolution. It has to be fresh as of the start time, and a signed feed's has to be the
+    // publisher's first message at or after it, the same rule its closing price is held to.
+    pub fn initialize_bet_state<'info>(
+        ctx: Context<'_, '_, '_, 'info, InitializeBetState<'info>>,
+        params: BetStateParams,
     ) -> Result<()> {
-        let bet_state = &mut ctx.accounts.bet_state;
+        let BetStateParams {
+            start,
+            duration,
+            symbol,
+            limits,
+            betting_cutoff,
+            oracle_guards,
+            oracle_kind,
+            secondary_oracles,
+            oracle_quorum,
+            boundary_rule,
+            straddle_policy,
+            resolver_committee,
+            creator_fee_bps,
+            market_type,
+        } = params;
         let bet_creator = &mut ctx.accounts.bet_creator;
+        let config = &ctx.accounts.config;
+        let now = current_time_millis()?;
+
+        if config.paused {
+            return Err(error!(ErrorCode::BettingPaused));
+        }
+
+        // Bet states can only be created on symbols the admin has registered and not disabled
+        let symbol = pack_symbol(&symbol)?;
+        let asset = registered_asset(&ctx.accounts.asset, &symbol)?;
+        if !asset.enabled {
+            return Err(error!(ErrorCode::AssetDisabled));
+        }
 
-        bet_state.symbol = symbol;
-        bet_state.creator = bet_creator.key();
-        bet_state.running_total_pool = 0;
-        bet_state.static_total_pool = 0;
+        if duration == 0 {
+            return Err(error!(ErrorCode::ZeroDuration));
+        }
+        if start.saturating_add(MAX_START_TIME_LAG_MS) < now {
+            return Err(error!(ErrorCode::StartTimeInPast));
+        }
+        let end_time = start.checked_add(duration).ok_or(ErrorCode::EndTimeOverflow)?;
+
+        // A window the market is closed for part of would be decided by a price that stopped moving at the close
+        if !asset.is_open_throughout(start, end_time) {
+            return Err(error!(ErrorCode::MarketClosedDuringBet));
+        }
+
+        // Markets fall back to the asset's defaults unless the creator sets their own limits
+        let limits = limits.unwrap_or(asset.default_limits);
+        limits.validate()?;
+
+        // The lockout can't be longer than the bet itself
+        if betting_cutoff > duration {
+            return Err(error!(ErrorCode::InvalidBettingCutoff));
+        }
+
+        if creator_fee_bps > config.max_creator_fee_bps {
+            return Err(error!(ErrorCode::InvalidCreatorFee));
+        }
+
+        market_type.validate(straddle_policy)?;
 
-        bet_state.neg_three_and_under_pool = 0;
-        bet_state.neg_three_to_neg_two_pool = 0;
-        bet_state.neg_two_to_neg_one_pool = 0;
-        bet_state.neg_one_to_zero_pool = 0;
-        bet_state.zero_to_pos_one_pool = 0;
-        bet_state.pos_one_to_pos_two_pool = 0;
-        bet_state.pos_two_to_pos_three_pool = 0;
-        bet_state.pos_three_and_over_pool = 0;
+        let oracle_guards = oracle_guards.unwrap_or(config.default_oracle_guards);
+        oracle_guards.validate()?;
+
+        // The asset's feed decides the bet, secondary oracles can only back it up
+        if oracle_kind != asset.oracle_kind || ctx.accounts.oracle_feed.key() != asset.oracle_feed {
+            return Err(error!(ErrorCode::AssetOracleMismatch));
+        }
+        oracle::validate_feed(oracle_kind, &ctx.accounts.oracle_feed, config)?;
+        let mut feeds = vec![(oracle_kind, ctx.accounts.oracle_feed.clone())];
+
+        // Secondary oracles switch the bet to median resolution, their feeds are passed as remaining accounts in the same order.
+        // A relative-performance market's benchmark asset entry and feed come after them.
+        if secondary_oracles.len() > BetState::MAX_SECONDARY_ORACLES
+            || ctx.remaining_accounts.len() != secondary_oracles.len() + market_type.benchmark_account_count()
+            || oracle_quorum == 0
+            || oracle_quorum as usize > secondary_oracles.len() + 1
+        {
+            return Err(error!(ErrorCode::InvalidOracleSources));
+        }
 
-        bet_state.status = BetStateStatus::Open;
-        bet_state.start_time = start;
-        bet_state.end_time = start + duration;
+        for (index, (source, feed)) in secondary_oracles.iter().zip(ctx.remaining_accounts.iter()).enumerate() {
+            // The same feed counted twice would skew both the quorum and the median
+            if feed.key() != source.feed
+                || source.feed == ctx.accounts.oracle_feed.key()
+                || secondary_oracles[..index].iter().any(|other| other.feed == source.feed)
+            {
+                return Err(error!(ErrorCode::InvalidOracleSources));
+            }
+            oracle::validate_feed(source.kind, feed, config)?;
+            feeds.push((source.kind, feed.clone()));
+        }
 
-        bet_state.snapshot_price = snapshot_price;
-        bet_state.winning_bet_range = BetRange::NotAvailable;
+        // The benchmark is held to the same rules as the bet's own asset, and is snapshotted at the same time
+        let benchmark_snapshot = match &market_type {
+            MarketType::RelativePerformance { benchmark_symbol, .. } => {
+                let benchmark_accounts = &ctx.remaining_accounts[secondary_oracles.len()..];
+                let benchmark = registered_asset(&benchmark_accounts[0], &pack_symbol(benchmark_symbol)?)?;
+                if benchmark.symbol == symbol {
+                    return Err(error!(ErrorCode::InvalidMarketType));
+                }
+                if !benchmark.enabled {
+                    return Err(error!(ErrorCode::AssetDisabled));
+                }
+                if !benchmark.is_open_throughout(start, end_time) {
+                    return Err(error!(ErrorCode::MarketClosedDuringBet));
+                }
+                let benchmark_feed = &benchmark_accounts[1];
+                if benchmark_feed.key() != benchmark.oracle_feed {
+                    return Err(error!(ErrorCode::AssetOracleMismatch));
+                }
+                oracle::validate_feed(benchmark.oracle_kind, benchmark_feed, config)?;
+
+                let (benchmark_price, _) = aggregate_oracle_price(&[(benchmark.oracle_kind, benchmark_feed.clone())], &oracle_guards, 1, start, true, config, &ctx.accounts.instructions, now)
+                    .map_err(snapshot_price_error)?;
+                Some((benchmark.oracle_kind, benchmark.oracle_feed, benchmark_price.price))
+            }
+            _ => None,
+        };
+
+        // Every bucket is a percent change from the snapshot, which means nothing from a price of zero
+        let (snapshot, _) = aggregate_oracle_price(&feeds, &oracle_guards, oracle_quorum, start, true, config, &ctx.accounts.instructions, now)
+            .map_err(snapshot_price_error)?;
+
+        let bet_state_key = ctx.accounts.bet_state.key();
+        let mut bet_state = ctx.accounts.bet_state.load_init()?;
+
+        bet_state.open(symbol, bet_creator.key(), start, end_time, &snapshot.price, config);
+        bet_state.boundary_rule = boundary_rule as u8;
+        bet_state.set_market_type(market_type, &asset.bucket_layout)?;
+        if let Some((benchmark_oracle_kind, benchmark_oracle_feed, benchmark_price)) = benchmark_snapshot {
+            bet_state.benchmark_oracle_kind = benchmark_oracle_kind as u8;
+            bet_state.benchmark_oracle_feed = benchmark_oracle_feed;
+            bet_state.set_benchmark_snapshot_price(&benchmark_price);
+        }
+        bet_state.straddle_policy = straddle_policy as u8;
+        bet_state.resolver_committee = resolver_committee.unwrap_or_default();
+
+        let creator_bond = &mut ctx.accounts.creator_bond;
+        invoke(
+            &system_instruction::transfer(&bet_creator.key(), &creator_bond.key(), config.creator_bond),
+            &[
+                bet_creator.to_account_info(),
+                creator_bond.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+        )?;
+        creator_bond.version = ACCOUNT_VERSION;
+        creator_bond.bet_state = bet_state_key;
+        creator_bond.creator = bet_creator.key();
+        creator_bond.amount = config.creator_bond;
+        creator_bond.bump = *ctx.bumps.get("creator_bond").unwrap();
+
+        bet_state.creator_fee_bps = creator_fee_bps;
+        bet_state.creator_bond = config.creator_bond;
+        bet_state.set_limits(&limits);
+        bet_state.betting_cutoff = betting_cutoff;
+        bet_state.oracle_kind = oracle_kind as u8;
+        bet_state.oracle_feed = ctx.accounts.oracle_feed.key();
+        bet_state.set_oracle_guards(&oracle_guards);
+        bet_state.set_secondary_oracles(&secondary_oracles);
+        bet_state.oracle_quorum = oracle_quorum;
 
         Ok(())
     }
 
-    // Endpoint that allows a user to place a wager on a bet state
-    pub fn place_wager(
-        ctx: Context<PlaceWager>,
+    // Endpoint that allows a user to place a wager on a bet state, a referred bettor passes their referrer's user account
+    // as the only remaining account
+    pub fn place_wager<'info>(
+        ctx: Context<'_, '_, '_, 'info, PlaceWager<'info>>,
         bet_range: u8,
         lamports: u64,
     ) -> Result<()> {
 
-        let bet_state = &mut ctx.accounts.bet_state;
+        let bet_state_key = ctx.accounts.bet_state.key();
+        let mut bet_state = ctx.accounts.bet_state.load_mut()?;
         let wager_detail = &mut ctx.accounts.wager_detail;
         let user_account = &mut ctx.accounts.user_account;
+        let bettor_position = &mut ctx.accounts.bettor_position;
         let bettor = &mut ctx.accounts.bettor_account;
-        // let bet_creator = &mut ctx.accounts.bet_creator;
+        let treasury = &mut ctx.accounts.treasury;
+
+        if ctx.accounts.config.paused {
+            return Err(error!(ErrorCode::BettingPaused));
+        }
+
+        if !bet_state.is_accepting_wagers(current_time_millis()?) {
+            return Err(error!(ErrorCode::BettingCutoffReached));
+        }
+
+        // separate the protocol and creator fees from the actual wager, the protocol fee depends on the bettor's volume this season
+        let config = &ctx.accounts.config;
+        let protocol_fee_bps = config.effective_protocol_fee_bps(&bet_state, user_account);
+        let (wager_amount_int, protocol_fee, creator_fee) = bet_state.split_wager(lamports, protocol_fee_bps)?;
+        user_account.add_season_volume(config.fee_season, wager_amount_int);
+
+        // A referred bettor's wagers pay part of the protocol fee into their referrer's balance
+        let mut referral_fee = 0;
+        if user_account.referrer != Pubkey::default() {
+            let referrer_info = ctx.remaining_accounts.first().ok_or(ErrorCode::ReferrerAccountMismatch)?;
+            if referrer_info.key() != user_account.referrer {
+                return Err(error!(ErrorCode::ReferrerAccountMismatch));
+            }
+
+            // A closed referrer account leaves its share with the treasury
+            if !referrer_info.data_is_empty() {
+                let mut referrer = Account::<UserAccount>::try_from(referrer_info)?;
 
-        // separate take rate from actual wager
-        let wager_amount_float = lamports as f64 / TAKE_RATE;
-        let wager_amount_int = wager_amount_float as u64;
+                referral_fee = (protocol_fee as u128 * config.referral_share_bps as u128 / BASIS_POINTS as u128) as u64;
+                referrer.current_balance += referral_fee;
+                referrer.referral_earnings += referral_fee;
+                referrer.referral_volume += wager_amount_int;
+                referrer.exit(&crate::ID)?;
 
-        // grab the take rate from actual wager
-        let take_rate_amount_int = lamports - wager_amount_int;
+                **user_account.to_account_info().try_borrow_mut_lamports()? = user_account.to_account_info().lamports().checked_sub(referral_fee).ok_or(ProgramError::InvalidArgument)?;
+                **referrer_info.try_borrow_mut_lamports()? = referrer_info.lamports().checked_add(referral_fee).ok_or(ProgramError::InvalidArgument)?;
+            }
+        }
+        let protocol_fee = protocol_fee - referral_fee;
+
+        bet_state.protocol_fees += protocol_fee;
+        bet_state.creator_fees += creator_fee;
         
         // add lamport amount to the static total pool and running_total_pool
         bet_state.static_total_pool += wager_amount_int;
         bet_state.running_total_pool += wager_amount_int;
+        bet_state.open_wagers += 1;
         
         // update the BetterDetail obj
+        wager_detail.version = ACCOUNT_VERSION;
         wager_detail.bet_value = wager_amount_int;
-        wager_detail.bet_state = bet_state.key();
+        wager_detail.bet_state = bet_state_key;
         wager_detail.bettor = bettor.key();
-
-        match bet_range {
-            0 => { 
-                wager_detail.range_status = BetRange::NegThreeAndUnder;
-                bet_state.neg_three_and_under_pool += wager_amount_int;
-             },
-            1 => { 
-                wager_detail.range_status = BetRange::NegThreeToNegTwo;
-                bet_state.neg_three_to_neg_two_pool += wager_amount_int;
-            },
-            2 => { 
-                wager_detail.range_status = BetRange::NegTwoToNegOne;
-                bet_state.neg_two_to_neg_one_pool += wager_amount_int;
-            },
-            3 => { 
-                wager_detail.range_status = BetRange::NegOneToZero;
-                bet_state.neg_one_to_zero_pool += wager_amount_int;
-            },
-            4 => { 
-                wager_detail.range_status = BetRange::ZeroToPosOne;
-                bet_state.zero_to_pos_one_pool += wager_amount_int;
-            },
-            5 => { 
-                wager_detail.range_status = BetRange::PosOneToPosTwo;
-                bet_state.pos_one_to_pos_two_pool += wager_amount_int;
-            },
-            6 => { 
-                wager_detail.range_status = BetRange::PosTwoToPosThree;
-                bet_state.pos_two_to_pos_three_pool += wager_amount_int;
-            },
-            7 => { 
-                wager_detail.range_status = BetRange::PosThreeAndOver;
-                bet_state.pos_three_and_over_pool += wager_amount_int;
-            },
-            _ => { return Err(error!(ErrorCode::InvalidBetRange)); }
+        wager_detail.range_status = bet_state.outcome_range(bet_range).map_err(|_| error!(ErrorCode::InvalidBetRange))?;
+        bet_state.pools[bet_range as usize] += wager_amount_int;
+
+        // track the bettor's stake on this bet state so the per-user and per-bucket limits can be enforced
+        if bettor_position.bet_state == Pubkey::default() {
+            bettor_position.version = ACCOUNT_VERSION;
+            bettor_position.bet_state = bet_state_key;
+            bettor_position.user_account = user_account.key();
+            bettor_position.bump = *ctx.bumps.get("bettor_position").unwrap();
         }
+        bettor_position.total_stake += wager_amount_int;
+        bettor_position.bucket_stakes[bet_range as usize] += wager_amount_int;
+
+        enforce_wager_limits(&bet_state, bettor_position, bet_range as usize, wager_amount_int)?;
 
         // add the bet to the user account
         user_account.active_wagers.push(wager_detail.key());
@@ -125,13 +610,16 @@ pub mod juicy_bets {
         // remove the lamports from the current user's account balance
         user_account.current_balance -= lamports;
 
-        // Transfer actual wager lamport amount from user account into bet state wallet
-        **user_account.to_account_info().try_borrow_mut_lamports()? = user_account.to_account_info().lamports().checked_sub(wager_amount_int).ok_or(ProgramError::InvalidArgument)?;
-        **bet_state.to_account_info().try_borrow_mut_lamports()? = bet_state.to_account_info().lamports().checked_add(wager_amount_int).ok_or(ProgramError::InvalidArgument)?;
+        // Transfer actual wager lamport amount from user account into bet state wallet, the creator fee is held there
+        // with it until the creator claims it
+        let bet_state_amount = wager_amount_int + creator_fee;
+        **user_account.to_account_info().try_borrow_mut_lamports()? = user_account.to_account_info().lamports().checked_sub(bet_state_amount).ok_or(ProgramError::InvalidArgument)?;
+        let bet_state_info = ctx.accounts.bet_state.to_account_info();
+        **bet_state_info.try_borrow_mut_lamports()? = bet_state_info.lamports().checked_add(bet_state_amount).ok_or(ProgramError::InvalidArgument)?;
 
-        // Transfer take fee lamport amount from user account into bet creator wallet
-        // **user_account.to_account_info().try_borrow_mut_lamports()? = user_account.to_account_info().lamports().checked_sub(take_rate_amount_int).ok_or(ProgramError::InvalidArgument)?;
-        // **bet_creator.to_account_info().try_borrow_mut_lamports()? = bet_creator.to_account_info().lamports().checked_add(take_rate_amount_int).ok_or(ProgramError::InvalidArgument)?;
+        // Transfer protocol fee lamport amount from user account into the treasury
+        **user_account.to_account_info().try_borrow_mut_lamports()? = user_account.to_account_info().lamports().checked_sub(protocol_fee).ok_or(ProgramError::InvalidArgument)?;
+        **treasury.to_account_info().try_borrow_mut_lamports()? = treasury.to_account_info().lamports().checked_add(protocol_fee).ok_or(ProgramError::InvalidArgument)?;
 
         Ok(())
     }
@@ -140,9 +628,15 @@ pub mod juicy_bets {
     pub fn cancel_wager(ctx:Context<CancelWager>) -> Result<()> { 
 
         // Pull the respective accounts involved in this instruction
-        let bet_state = &mut ctx.accounts.bet_state;
+        let mut bet_state = ctx.accounts.bet_state.load_mut()?;
         let wager_detail = &mut ctx.accounts.wager_detail;
         let user_account = &mut ctx.accounts.user_account;
+        let bettor_position = &mut ctx.accounts.bettor_position;
+
+        // Wagers are locked in for the last stretch of the bet, same as new wagers
+        if !bet_state.is_accepting_wagers(current_time_millis()?) {
+            return Err(error!(ErrorCode::BettingCutoffReached));
+        }
 
         // Grab bettor details bet value
         let bet_value_from_wager_detail = wager_detail.bet_value;
@@ -150,20 +644,14 @@ pub mod juicy_bets {
         // Subtract the user's bet amount from the total pool
         bet_state.static_total_pool -= bet_value_from_wager_detail;
         bet_state.running_total_pool -= bet_value_from_wager_detail;
+        bet_state.open_wagers -= 1;
         
         // Subtract the user's bet amount from the respective party's pool
+        let bucket_index = wager_detail.range_status.bucket_index().ok_or(ErrorCode::InvalidBetRange)?;
+        bet_state.pools[bucket_index] -= bet_value_from_wager_detail;
 
-        match wager_detail.range_status {
-            BetRange::NegThreeAndUnder => bet_state.neg_three_and_under_pool -= bet_value_from_wager_detail,
-            BetRange::NegThreeToNegTwo => bet_state.neg_three_to_neg_two_pool -= bet_value_from_wager_detail,
-            BetRange::NegTwoToNegOne => bet_state.neg_two_to_neg_one_pool -= bet_value_from_wager_detail,
-            BetRange::NegOneToZero => bet_state.neg_one_to_zero_pool -= bet_value_from_wager_detail,
-            BetRange::ZeroToPosOne => bet_state.zero_to_pos_one_pool -= bet_value_from_wager_detail,
-            BetRange::PosOneToPosTwo => bet_state.pos_one_to_pos_two_pool -= bet_value_from_wager_detail,
-            BetRange::PosTwoToPosThree => bet_state.pos_two_to_pos_three_pool -= bet_value_from_wager_detail,
-            BetRange::PosThreeAndOver => bet_state.pos_three_and_over_pool -= bet_value_from_wager_detail,
-            _ => return Err(error!(ErrorCode::InvalidBetRange))
-        }
+        // Release the stake from the bettor's position so it no longer counts against their limits
+        release_position_stake(bettor_position, bucket_index, bet_value_from_wager_detail, &ctx.accounts.bettor)?;
 
         // iterate through the user accounts bets and find the matching bet
         if let Some(keypos) = user_account.active_wagers.iter().position(|x| *x == wager_detail.key()) {
@@ -173,18 +661,38 @@ pub mod juicy_bets {
         }
 
         // Add back to user account balance
-        user_account.current_balance += bet_value_from_wager_detail as u64;
+        user_account.current_balance += bet_value_from_wager_detail;
 
-        **bet_state.to_account_info().try_borrow_mut_lamports()? = bet_state.to_account_info().lamports().checked_sub(bet_value_from_wager_detail as u64).ok_or(ProgramError::InvalidArgument)?;
-        **user_account.to_account_info().try_borrow_mut_lamports()? = user_account.to_account_info().lamports().checked_add(bet_value_from_wager_detail as u64).ok_or(ProgramError::InvalidArgument)?;
+        let bet_state_info = ctx.accounts.bet_state.to_account_info();
+        **bet_state_info.try_borrow_mut_lamports()? = bet_state_info.lamports().checked_sub(bet_value_from_wager_detail).ok_or(ProgramError::InvalidArgument)?;
+        **user_account.to_account_info().try_borrow_mut_lamports()? = user_account.to_account_info().lamports().checked_add(bet_value_from_wager_detail).ok_or(ProgramError::InvalidArgument)?;
 
         Ok(()) 
     }
 
+    // Endpoint that allows a user to close a losing wager once claims open. If nobody backed the winning range there's
+    // nobody to pay the pool to, so the first losing wager closed voids the bet state and is refunded, the rest are
+    // refunded through refund_voided_wager. Series rounds sweep such a pool into their jackpot instead.
     pub fn close_losing_wager(ctx:Context<CloseLosingWager>) -> Result<()> {
 
+        let mut bet_state = ctx.accounts.bet_state.load_mut()?;
         let wager_detail = &mut ctx. accounts.wager_detail;
         let user_account = &mut ctx.accounts.user_account;
+        let now = current_time_millis()?;
+
+        // A losing wager could still win if the outcome is overturned
+        if !bet_state.claims_unlocked(now)? {
+            return Err(error!(ErrorCode::ClaimsLocked));
+        }
+
+        let nobody_won = bet_state.series == Pubkey::default() && bet_state.winning_pool()? == 0;
+        if nobody_won {
+            bet_state.status = BetStateStatus::Voided as u8;
+            emit!(BetStateVoided {
+                bet_state: ctx.accounts.bet_state.key(),
+                voided_at: now,
+            });
+        }
 
         // iterate through the user accounts bets and find the matching bet
         if let Some(keypos) = user_account.active_wagers.iter().position(|x| *x == wager_detail.key()) {
@@ -193,13 +701,28 @@ pub mod juicy_bets {
             return Err(error!(ErrorCode::ActiveWagerNotFound));
         }
 
+        // Losing bettors still get their share of a slashed creator bond
+        let bond_share = bet_state.slashed_bond_share(wager_detail.bet_value)?;
+        let payout = if nobody_won {
+            wager_detail.bet_value.checked_add(bond_share).ok_or(ErrorCode::MathOverflow)?
+        } else {
+            bond_share
+        };
+        user_account.current_balance += payout;
+        transfer_winnings(&ctx.accounts.bet_state.to_account_info(), &mut bet_state, user_account, payout)?;
+        bet_state.open_wagers -= 1;
+
+        let bucket_index = wager_detail.range_status.bucket_index().ok_or(ErrorCode::InvalidBetRange)?;
+        release_position_stake(&mut ctx.accounts.bettor_position, bucket_index, wager_detail.bet_value, &ctx.accounts.bettor)?;
+
         Ok(())
     }
 
-    // Endpoint that allows a user to claim their winnings from a bet state given that the bet is closed and a winner is decided
-    pub fn claim_winnings(ctx:Context<ClaimWinnings>, winnings_amount: u64) -> Result<()> { 
+    // Endpoint that allows a user to claim their winnings from a bet state given that the bet is closed and a winner is decided.
+    // The payout is worked out from the bet state's pools, see BetState::payout_for
+    pub fn claim_winnings(ctx:Context<ClaimWinnings>) -> Result<()> { 
 
-        let bet_state = &mut ctx.accounts.bet_state;
+        let mut bet_state = ctx.accounts.bet_state.load_mut()?;
         let wager_detail = &mut ctx. accounts.wager_detail;
         let user_account = &mut ctx.accounts.user_account;
 
@@ -210,32 +733,37 @@ pub mod juicy_bets {
             return Err(error!(ErrorCode::ActiveWagerNotFound));
         }
 
+        if bet_state.winning_bet_range == BetRange::NotAvailable as u8 {
+            return Err(error!(ErrorCode::BetStillUndecided))
+        }
+
+        if !bet_state.claims_unlocked(current_time_millis()?)? {
+            return Err(error!(ErrorCode::ClaimsLocked));
+        }
+
+        let winnings_amount = bet_state.payout_for(wager_detail)?
+            .checked_add(bet_state.slashed_bond_share(wager_detail.bet_value)?)
+            .ok_or(ErrorCode::MathOverflow)?;
+
         // Add the winning lamports to the user's account balance
         user_account.current_balance += winnings_amount;
 
-        if bet_state.winning_bet_range != BetRange::NotAvailable {
-            if bet_state.winning_bet_range == wager_detail.range_status {
-                transfer_winnings(bet_state, user_account, winnings_amount)?
-            } else {
-                return Err(error!(ErrorCode::NonWinningParty));
-            }
-        } else {
-            return Err(error!(ErrorCode::BetStillUndecided))
-        }
+        transfer_winnings(&ctx.accounts.bet_state.to_account_info(), &mut bet_state, user_account, winnings_amount)?;
+        bet_state.open_wagers -= 1;
+
+        let bucket_index = wager_detail.range_status.bucket_index().ok_or(ErrorCode::InvalidBetRange)?;
+        release_position_stake(&mut ctx.accounts.bettor_position, bucket_index, wager_detail.bet_value, &ctx.accounts.bettor)?;
 
         Ok(())
     }
 
-    // Endpoint that allows the app to close a bet once the betting time (end date - start date) has elapsed
-    pub fn close_bet_state(
-        ctx:Context<CloseBetState>, 
-        end: u64
-    ) -> Result<()> { 
+    // Endpoint that allows the resolver to close a bet to new wagers ahead of deciding it by hand. The end time set when the bet
+    // was created is left alone, it's the instant the bet is resolved against.
+    pub fn close_bet_state(ctx:Context<CloseBetState>) -> Result<()> { 
 
-        let bet_state = &mut ctx.accounts.bet_state;
+        let mut bet_state = ctx.accounts.bet_state.load_mut()?;
 
-        bet_state.end_time = end;
-        bet_state.status = BetStateStatus::Closed;
+        bet_state.status = BetStateStatus::Closed as u8;
 
         Ok(()) 
     }
@@ -243,9 +771,9 @@ pub mod juicy_bets {
     // Endpoint that allows a user to cancel a bet state they created
     pub fn cancel_bet_state(ctx:Context<CancelBetState>) -> Result<()> { 
 
-        let bet_state = &mut ctx.accounts.bet_state;
+        let bet_state = ctx.accounts.bet_state.load()?;
 
-        if bet_state.running_total_pool != 0 {
+        if bet_state.running_total_pool != 0 || bet_state.unmigrated_stake != 0 {
             return Err(error!(ErrorCode::FundsStillInPlay))
         }
 
@@ -253,24 +781,26 @@ pub mod juicy_bets {
         Ok(()) 
     }
 
-    // Endpoint that will determine who won the bet, either Party 1 or Party 2, mutating the bet_outcome field
+    // Endpoint for the config's resolver to decide a closed bet state's winning range by hand, for bets without a
+    // resolver committee or a series to resolve them
     pub fn decide_bet_state_outcome(ctx:Context<DecideBetState>, outcome: u8) -> Result<()> { 
 
-        let bet_state = &mut ctx.accounts.bet_state;
+        let mut bet_state = ctx.accounts.bet_state.load_mut()?;
 
         
-        if bet_state.winning_bet_range == BetRange::NotAvailable && bet_state.status == BetStateStatus::Closed {
-            match outcome {
-                0 => { bet_state.winning_bet_range = BetRange::NegThreeAndUnder },
-                1 => { bet_state.winning_bet_range = BetRange::NegThreeToNegTwo },
-                2 => { bet_state.winning_bet_range = BetRange::NegTwoToNegOne },
-                3 => { bet_state.winning_bet_range = BetRange::NegOneToZero },
-                4 => { bet_state.winning_bet_range = BetRange::ZeroToPosOne },
-                5 => { bet_state.winning_bet_range = BetRange::PosOneToPosTwo },
-                6 => { bet_state.winning_bet_range = BetRange::PosTwoToPosThree },
-                7 => { bet_state.winning_bet_range = BetRange::PosThreeAndOver },
-                _ => { return Err(error!(ErrorCode::InvalidBetOutcome)); }
-            };
+        // Bets with a resolver committee can only be decided by its vote
+        if bet_state.resolver_committee != Pubkey::default() {
+            return Err(error!(ErrorCode::ResolverCommitteeRequired));
+        }
+
+        // Series rounds are resolved from their feed through the series, which pays them its jackpot
+        if bet_state.series != Pubkey::default() {
+            return Err(error!(ErrorCode::ResolvedThroughSeries));
+        }
+
+        if bet_state.winning_bet_range == BetRange::NotAvailable as u8 && bet_state.status == BetStateStatus::Closed as u8 {
+            bet_state.winning_bet_range = bet_state.outcome_range(outcome)? as u8;
+            bet_state.open_dispute_window(current_time_millis()?);
         } else {
             return Err(error!(ErrorCode::BetStillOpen))
         }
@@ -278,346 +808,3085 @@ pub mod juicy_bets {
         Ok(()) 
     }
 
-    // Endpoint that will put the bet state into a "settled" state, all winnings have been claimed and the bet state account is ready to be closed
-    pub fn settle_bet_state(ctx: Context<SettleBetState>) -> Result<()> {
+    // Endpoint that allows the admin to create a committee of resolvers, bet states naming it are decided once
+    // `threshold` of its members vote for the same outcome
+    pub fn initialize_resolver_committee(ctx: Context<InitializeResolverCommittee>, members: Vec<Pubkey>, threshold: u8) -> Result<()> {
 
-        let bet_state = &mut ctx.accounts.bet_state;
+        let committee = &mut ctx.accounts.resolver_committee;
 
-        if bet_state.running_total_pool != 0 {
-            return Err(error!(ErrorCode::FundsStillInPlay));
+        if members.is_empty()
+            || members.len() > ResolverCommittee::MAX_MEMBERS
+            || threshold == 0
+            || threshold as usize > members.len()
+            || members.iter().enumerate().any(|(index, member)| members[..index].contains(member))
+        {
+            return Err(error!(ErrorCode::InvalidResolverCommittee));
         }
 
+        committee.version = ACCOUNT_VERSION;
+        committee.members = members;
+        committee.threshold = threshold;
+
         Ok(())
     }
 
+    // Endpoint for a committee member to vote on the outcome of a bet state that has ended. Votes are collected on a
+    // pending resolution account and the outcome is decided as soon as one of them reaches the committee's threshold.
+    pub fn vote_on_outcome(ctx: Context<VoteOnOutcome>, outcome: u8) -> Result<()> {
 
-    // *** User Account Functionality *** //
-    pub fn initialize_user_account(ctx: Context<InitializeUserAccount>) -> Result<()> {
+        let bet_state_key = ctx.accounts.bet_state.key();
+        let mut bet_state = ctx.accounts.bet_state.load_mut()?;
+        let committee = &ctx.accounts.resolver_committee;
+        let pending_resolution = &mut ctx.accounts.pending_resolution;
+        let member = ctx.accounts.member.key();
+        let now = current_time_millis()?;
 
-        let user_account = &mut ctx.accounts.user_account;
-        let account_owner = &mut ctx.accounts.account_owner;
+        if now < bet_state.end_time {
+            return Err(error!(ErrorCode::BetNotYetEnded));
+        }
 
-        user_account.account_owner = account_owner.key();
-        user_account.wins = 0;
-        user_account.losses = 0;
-        user_account.current_balance = 0;
+        if !committee.members.contains(&member) {
+            return Err(error!(ErrorCode::NotCommitteeMember));
+        }
+
+        if pending_resolution.votes.iter().any(|vote| vote.member == member) {
+            return Err(error!(ErrorCode::AlreadyVoted));
+        }
+
+        let bet_range = bet_state.outcome_range(outcome)?;
+
+        if pending_resolution.bet_state == Pubkey::default() {
+            pending_resolution.version = ACCOUNT_VERSION;
+            pending_resolution.bet_state = bet_state_key;
+            pending_resolution.resolver_committee = committee.key();
+            pending_resolution.bump = *ctx.bumps.get("pending_resolution").unwrap();
+        }
+        pending_resolution.votes.push(ResolverVote { member, bet_range });
+
+        let matching_votes = pending_resolution.votes.iter().filter(|vote| vote.bet_range == bet_range).count();
+        if matching_votes >= committee.threshold as usize {
+            bet_state.status = BetStateStatus::Closed as u8;
+            bet_state.winning_bet_range = bet_range as u8;
+            bet_state.open_dispute_window(now);
+        }
 
         Ok(())
     }
 
-    pub fn close_user_account(_ctx: Context<CloseUserAccount>) -> Result<()> {
+    // Endpoint that reports where a bet state is in its lifecycle, clients read the emitted event by simulating this instruction
+    pub fn view_bet_state_status(ctx: Context<ViewBetState>) -> Result<()> {
+
+        let bet_state = ctx.accounts.bet_state.load()?;
+        let now = current_time_millis()?;
+
+        emit!(BetStateStatusView {
+            bet_state: ctx.accounts.bet_state.key(),
+            status: bet_state.status()?,
+            winning_bet_range: bet_state.winning_bet_range()?,
+            start_time: bet_state.start_time,
+            end_time: bet_state.end_time,
+            betting_cutoff_time: bet_state.betting_cutoff_time(),
+            accepting_wagers: bet_state.is_accepting_wagers(now),
+            dispute_status: bet_state.dispute_status()?,
+            dispute_deadline: bet_state.dispute_deadline,
+            claims_unlocked: bet_state.claims_unlocked(now)?,
+            current_time: now,
+        });
+
         Ok(())
     }
 
-    pub fn deposit_into_account(ctx: Context<DepositIntoAccount>, lamports: u64) -> Result<()> {
+    // Endpoint that quotes how a wager of `lamports` would be split for a user at their current fee tier, clients read the
+    // emitted event by simulating this instruction
+    pub fn quote_wager(ctx: Context<QuoteWager>, lamports: u64) -> Result<()> {
+
+        let bet_state = ctx.accounts.bet_state.load()?;
+        let user_account = &ctx.accounts.user_account;
+        let config = &ctx.accounts.config;
+
+        let protocol_fee_bps = config.effective_protocol_fee_bps(&bet_state, user_account);
+        let (wager, protocol_fee, creator_fee) = bet_state.split_wager(lamports, protocol_fee_bps)?;
+
+        emit!(WagerQuote {
+            bet_state: ctx.accounts.bet_state.key(),
+            user_account: user_account.key(),
+            lamports,
+            wager,
+            protocol_fee,
+            creator_fee,
+            protocol_fee_bps,
+            creator_fee_bps: bet_state.creator_fee_bps,
+            season_volume: user_account.season_volume(config.fee_season),
+        });
 
-        let user_account = &mut ctx.accounts.user_account;
+        Ok(())
+    }
 
-        user_account.current_balance += lamports;
+    // Endpoint that decides the bet's outcome from its oracle feed once the end time has passed, anyone can call it.
+    // Bets with secondary oracles take the median of every source that passes the guards, those feeds are passed as
+    // remaining accounts in the order they were set up.
+    // If the price fails the bet's oracle guards the call errors so it can be retried, once the retry window has run out
+    // the bet is voided instead and every wager can be refunded.
+    pub fn resolve_bet_state_outcome<'info>(ctx: Context<'_, '_, '_, 'info, ResolveBetState<'info>>) -> Result<()> {
+
+        let bet_state_key = ctx.accounts.bet_state.key();
+        let mut bet_state = ctx.accounts.bet_state.load_mut()?;
+        let now = current_time_millis()?;
+
+        // Series rounds go through resolve_series_round so they can be paid the series' jackpot
+        if bet_state.series != Pubkey::default() {
+            return Err(error!(ErrorCode::ResolvedThroughSeries));
+        }
+
+        if now < bet_state.end_time {
+            return Err(error!(ErrorCode::BetNotYetEnded));
+        }
+
+        resolve_with_oracle(
+            &mut bet_state,
+            bet_state_key,
+            &ctx.accounts.oracle_feed,
+            ctx.remaining_accounts,
+            &ctx.accounts.asset,
+            &ctx.accounts.config,
+            &ctx.accounts.instructions,
+            now,
+        )
+    }
+
+    // Endpoint that allows a bettor to dispute a decided outcome before its dispute deadline by posting the config's bond.
+    // Claims stay frozen until the arbitration authority rules on it.
+    pub fn dispute_outcome(ctx: Context<DisputeOutcome>, disputed_bet_range: u8) -> Result<()> {
+
+        let bet_state_key = ctx.accounts.bet_state.key();
+        let mut bet_state = ctx.accounts.bet_state.load_mut()?;
+        let dispute = &mut ctx.accounts.dispute;
+        let disputer = &ctx.accounts.disputer;
+        let now = current_time_millis()?;
+
+        if bet_state.dispute_status != DisputeStatus::None as u8 {
+            return Err(error!(ErrorCode::OutcomeAlreadyDisputed));
+        }
+
+        if now >= bet_state.dispute_deadline {
+            return Err(error!(ErrorCode::DisputeWindowClosed));
+        }
+
+        let disputed_bet_range = bet_state.outcome_range(disputed_bet_range)?;
+        if disputed_bet_range == bet_state.winning_bet_range()? {
+            return Err(error!(ErrorCode::InvalidDisputeRuling));
+        }
+
+        // The bond is held in the dispute account until the ruling
+        let bond = ctx.accounts.config.dispute_bond;
+        invoke(
+            &system_instruction::transfer(&disputer.key(), &dispute.key(), bond),
+            &[
+                disputer.to_account_info(),
+                dispute.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+        )?;
+
+        dispute.version = ACCOUNT_VERSION;
+        dispute.bet_state = bet_state_key;
+        dispute.disputer = disputer.key();
+        dispute.bond = bond;
+        dispute.disputed_bet_range = disputed_bet_range;
+        dispute.raised_at = now;
+        dispute.bump = *ctx.bumps.get("dispute").unwrap();
+
+        bet_state.dispute_status = DisputeStatus::Pending as u8;
 
         Ok(())
     }
 
-    pub fn withdraw_from_account(ctx: Context<WithdrawFromAccount>, amount: u64) -> Result<()> {
+    // Endpoint that allows the arbitration authority to rule on a pending dispute. Passing no range upholds the outcome and
+    // slashes the bond to the authority, passing a range overturns the outcome to it and returns the bond to the disputer.
+    // Either way claims unlock straight away.
+    pub fn rule_on_dispute(ctx: Context<RuleOnDispute>, corrected_bet_range: Option<u8>) -> Result<()> {
 
-        let user_account = &mut ctx.accounts.user_account;
-        let account_owner = &mut ctx.accounts.account_owner;
+        let mut bet_state = ctx.accounts.bet_state.load_mut()?;
+        let dispute = &mut ctx.accounts.dispute;
 
-        user_account.current_balance.checked_sub(amount).ok_or(ErrorCode::InvalidWithdrawalAmount)?;
+        match corrected_bet_range {
+            None => {
+                let authority = &ctx.accounts.arbitration_authority;
+                **dispute.to_account_info().try_borrow_mut_lamports()? = dispute.to_account_info().lamports().checked_sub(dispute.bond).ok_or(ProgramError::InvalidArgument)?;
+                **authority.to_account_info().try_borrow_mut_lamports()? = authority.to_account_info().lamports().checked_add(dispute.bond).ok_or(ProgramError::InvalidArgument)?;
 
-        user_account.current_balance -= amount;
+                bet_state.dispute_status = DisputeStatus::Upheld as u8;
+            },
+            Some(corrected_bet_range) => {
+                let corrected_bet_range = bet_state.outcome_range(corrected_bet_range)?;
+                if corrected_bet_range == bet_state.winning_bet_range()? {
+                    return Err(error!(ErrorCode::InvalidDisputeRuling));
+                }
+
+                bet_state.winning_bet_range = corrected_bet_range as u8;
+                bet_state.split_bet_range = BetRange::NotAvailable as u8;
+                bet_state.dispute_status = DisputeStatus::Overturned as u8;
+
+                // The creator answers for the outcome their market produced
+                slash_creator_bond(&ctx.accounts.bet_state.to_account_info(), &mut bet_state, &mut ctx.accounts.creator_bond)?;
+            },
+        }
 
-        **user_account.to_account_info().try_borrow_mut_lamports()? = user_account.to_account_info().lamports().checked_sub(amount).ok_or(ProgramError::InvalidArgument)?;
-        **account_owner.to_account_info().try_borrow_mut_lamports()? = account_owner.to_account_info().lamports().checked_add(amount).ok_or(ProgramError::InvalidArgument)?;
+        // The dispute account itself, and the bond when it wasn't slashed, goes back to the disputer as it closes
+        Ok(())
+    }
+
+    // Endpoint that allows the arbitration authority to void a bet state the creator is at fault for, for example a market
+    // set up on the wrong feed or asset. The creator bond is slashed to the bet's bettors along with their refunds.
+    pub fn void_for_creator_fault(ctx: Context<VoidForCreatorFault>) -> Result<()> {
+
+        let mut bet_state = ctx.accounts.bet_state.load_mut()?;
+
+        bet_state.status = BetStateStatus::Voided as u8;
+        slash_creator_bond(&ctx.accounts.bet_state.to_account_info(), &mut bet_state, &mut ctx.accounts.creator_bond)?;
+
+        emit!(BetStateVoided {
+            bet_state: ctx.accounts.bet_state.key(),
+            voided_at: current_time_millis()?,
+        });
 
         Ok(())
     }
 
+    // Endpoint that gives a bettor their stake back once their bet state has been voided
+    pub fn refund_voided_wager(ctx: Context<RefundVoidedWager>) -> Result<()> {
+
+        let mut bet_state = ctx.accounts.bet_state.load_mut()?;
+        let wager_detail = &mut ctx.accounts.wager_detail;
+        let user_account = &mut ctx.accounts.user_account;
+
+        let refund = wager_detail.bet_value
+            .checked_add(bet_state.slashed_bond_share(wager_detail.bet_value)?)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        // iterate through the user accounts bets and find the matching bet
+        if let Some(keypos) = user_account.active_wagers.iter().position(|x| *x == wager_detail.key()) {
+            user_account.active_wagers.remove(keypos);
+        } else {
+            return Err(error!(ErrorCode::ActiveWagerNotFound));
+        }
+
+        user_account.current_balance += refund;
+
+        transfer_winnings(&ctx.accounts.bet_state.to_account_info(), &mut bet_state, user_account, refund)?;
+        bet_state.open_wagers -= 1;
+
+        let bucket_index = wager_detail.range_status.bucket_index().ok_or(ErrorCode::InvalidBetRange)?;
+        release_position_stake(&mut ctx.accounts.bettor_position, bucket_index, wager_detail.bet_value, &ctx.accounts.bettor)?;
+
+        Ok(())
+    }
+
+    // Endpoint that pays the creator the fees accrued on their bet state once every wager on it has been paid out
+    pub fn claim_creator_fees(ctx: Context<ClaimCreatorFees>) -> Result<()> {
+
+        let mut bet_state = ctx.accounts.bet_state.load_mut()?;
+        let bet_creator = &mut ctx.accounts.bet_creator;
+
+        let creator_fees = bet_state.creator_fees;
+        bet_state.creator_fees = 0;
+
+        let bet_state_info = ctx.accounts.bet_state.to_account_info();
+        **bet_state_info.try_borrow_mut_lamports()? = bet_state_info.lamports().checked_sub(creator_fees).ok_or(ProgramError::InvalidArgument)?;
+        **bet_creator.to_account_info().try_borrow_mut_lamports()? = bet_creator.to_account_info().lamports().checked_add(creator_fees).ok_or(ProgramError::InvalidArgument)?;
+
+        Ok(())
+    }
+
+    // Endpoint that will put the bet state into a "settled" state, all winnings have been claimed and the bet state account is ready to be closed.
+    // A bet state nobody won is voided as its wagers are closed, so once none are open all that can be left of the pool is
+    // rounding dust, which goes to the creator along with their bond, unless it was slashed.
+    pub fn settle_bet_state(ctx: Context<SettleBetState>) -> Result<()> {
+
+        let bet_state = ctx.accounts.bet_state.load()?;
+
+        // Wagers still on the version 0 layout haven't been counted as open yet
+        if bet_state.open_wagers != 0 || bet_state.unmigrated_stake != 0 {
+            return Err(error!(ErrorCode::FundsStillInPlay));
+        }
+
+        Ok(())
+    }
+
+
+    // *** Market Series Functionality *** //
+
+    // Endpoint for the admin to start a series of recurring bet states on a registered asset. The first round can be
+    // opened from `first_round_start` and every round after it `cadence` milliseconds later, each running `duration`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_market_series(
+        ctx: Context<CreateMarketSeries>,
+        symbol: String,
+        cadence: u64,
+        duration: u64,
+        betting_cutoff: u64,
+        bucket_layout: BucketLayout,
+        first_round_start: u64,
+        jackpot_fee_bps: u16,
+        jackpot_trigger: JackpotTrigger,
+    ) -> Result<()> {
+        if duration == 0 {
+            return Err(error!(ErrorCode::ZeroDuration));
+        }
+        // Rounds can't overlap, so a series only ever has one current round
+        if cadence < duration {
+            return Err(error!(ErrorCode::InvalidSeriesSchedule));
+        }
+        if betting_cutoff > duration {
+            return Err(error!(ErrorCode::InvalidBettingCutoff));
+        }
+        bucket_layout.validate()?;
+        // The jackpot fee is charged the way a creator fee is, so it's held to the same cap
+        if jackpot_fee_bps > ctx.accounts.config.max_creator_fee_bps {
+            return Err(error!(ErrorCode::InvalidCreatorFee));
+        }
+        jackpot_trigger.validate()?;
+
+        let series = &mut ctx.accounts.series;
+        series.version = ACCOUNT_VERSION;
+        series.symbol = pack_symbol(&symbol)?;
+        series.asset = ctx.accounts.asset.key();
+        series.authority = ctx.accounts.admin.key();
+        series.cadence = cadence;
+        series.duration = duration;
+        series.betting_cutoff = betting_cutoff;
+        series.bucket_layout = bucket_layout;
+        series.round = 0;
+        series.next_round_start = first_round_start;
+        series.jackpot_fee_bps = jackpot_fee_bps;
+        series.jackpot_trigger = jackpot_trigger;
+        series.bump = *ctx.bumps.get("series").unwrap();
+
+        let jackpot = &mut ctx.accounts.jackpot;
+        jackpot.version = ACCOUNT_VERSION;
+        jackpot.series = series.key();
+        jackpot.balance = 0;
+        jackpot.total_awarded = 0;
+        jackpot.last_awarded_round = 0;
+        jackpot.bump = *ctx.bumps.get("jackpot").unwrap();
+        Ok(())
+    }
+
+    // Endpoint anyone can call once a series' next round is due. The current round is resolved here if it is still
+    // undecided, see resolve_series_round, then the next round opens at the PDA of ["round", series, round number] on
+    // the asset's feed and defaults. Whoever advances the series pays its rent and gets it back when the round is swept,
+    // see sweep_round_to_jackpot.
+    pub fn advance_series(ctx: Context<AdvanceSeries>) -> Result<()> {
+        let config = &ctx.accounts.config;
+        let asset = &ctx.accounts.asset;
+        let series = &mut ctx.accounts.series;
+        let jackpot = &mut ctx.accounts.jackpot;
+        let now = current_time_millis()?;
+
+        if config.paused {
+            return Err(error!(ErrorCode::BettingPaused));
+        }
+        if !asset.enabled {
+            return Err(error!(ErrorCode::AssetDisabled));
+        }
+        if now < series.next_round_start {
+            return Err(error!(ErrorCode::RoundNotDue));
+        }
+
+        // Rounds whose slot has already started are skipped, the new round keeps the schedule's end time
+        let missed_rounds = (now - series.next_round_start) / series.cadence;
+        let round_start = missed_rounds
+            .checked_mul(series.cadence)
+            .and_then(|offset| series.next_round_start.checked_add(offset))
+            .ok_or(ErrorCode::EndTimeOverflow)?;
+        let end_time = round_start.checked_add(series.duration).ok_or(ErrorCode::EndTimeOverflow)?;
+        if now >= end_time {
+            return Err(error!(ErrorCode::RoundNotDue));
+        }
+        if !asset.is_open_throughout(now, end_time) {
+            return Err(error!(ErrorCode::MarketClosedDuringBet));
+        }
+
+        if ctx.accounts.current_round.key() != round_address(&series.key(), series.round) {
+            return Err(error!(ErrorCode::InvalidSeriesRound));
+        }
+        if series.round > 0 {
+            let current_round_loader = AccountLoader::<BetState>::try_from(&ctx.accounts.current_round)?;
+            let mut current_round = current_round_loader.load_mut()?;
+
+            if current_round.status == BetStateStatus::Open as u8 && current_round.winning_bet_range == BetRange::NotAvailable as u8 {
+                // A round opened before the asset moved feeds has to be resolved on its own feed first
+                if current_round.oracle_feed != ctx.accounts.oracle_feed.key() {
+                    return Err(error!(ErrorCode::InvalidOracleFeed));
+                }
+                resolve_round_with_jackpot(
+                    series,
+                    jackpot,
+                    &mut current_round,
+                    &ctx.accounts.current_round,
+                    &ctx.accounts.oracle_feed,
+                    &asset.to_account_info(),
+                    config,
+                    &ctx.accounts.instructions,
+                    now,
+                )?;
+            }
+        }
+
+        let oracle_guards = config.default_oracle_guards;
+        let feeds = vec![(asset.oracle_kind, ctx.accounts.oracle_feed.clone())];
+        // The next round opens whenever the series is advanced rather than at a time set ahead, so there is no start
+        // for a signed message to bracket and the latest fresh one is taken
+        let (snapshot, _) = aggregate_oracle_price(&feeds, &oracle_guards, 1, now, false, config, &ctx.accounts.instructions, now)
+            .map_err(snapshot_price_error)?;
+
+        let next_round_key = ctx.accounts.next_round.key();
+        let mut next_round = ctx.accounts.next_round.load_init()?;
+        // The jackpot stands in as the round's creator, so the creator fee is the jackpot fee and only a sweep can
+        // collect it
+        next_round.open(series.symbol, jackpot.key(), now, end_time, &snapshot.price, config);
+        next_round.series = series.key();
+        next_round.boundary_rule = BoundaryRule::LowerInclusive as u8;
+        next_round.bucket_width_bps = series.bucket_layout.bucket_width_bps;
+        next_round.straddle_policy = StraddlePolicy::Reject as u8;
+        next_round.creator_fee_bps = series.jackpot_fee_bps;
+        next_round.set_limits(&asset.default_limits);
+        next_round.betting_cutoff = series.betting_cutoff;
+        next_round.oracle_kind = asset.oracle_kind as u8;
+        next_round.oracle_feed = asset.oracle_feed;
+        next_round.set_oracle_guards(&oracle_guards);
+        next_round.oracle_quorum = 1;
+
+        // Nobody chose to create the round, so there's no bond to post. The keeper is recorded in its place to get
+        // the rent back.
+        let creator_bond = &mut ctx.accounts.next_creator_bond;
+        creator_bond.version = ACCOUNT_VERSION;
+        creator_bond.bet_state = next_round_key;
+        creator_bond.creator = ctx.accounts.keeper.key();
+        creator_bond.amount = 0;
+        creator_bond.bump = *ctx.bumps.get("next_creator_bond").unwrap();
+
+        series.round += 1;
+        series.next_round_start = round_start.saturating_add(series.cadence);
+        Ok(())
+    }
+
+    // Endpoint anyone can call to resolve a series' current round once it has ended without opening the next one, for
+    // when the next round can't open yet or the round has to be resolved on a feed the asset has since moved off.
+    // Rounds are only ever resolved through their series, so the jackpot is paid into them whichever way they go.
+    pub fn resolve_series_round(ctx: Context<ResolveSeriesRound>) -> Result<()> {
+        let series = &ctx.accounts.series;
+        let round_info = ctx.accounts.round.to_account_info();
+        let mut round = ctx.accounts.round.load_mut()?;
+
+        if round_info.key() != round_address(&series.key(), series.round) {
+            return Err(error!(ErrorCode::InvalidSeriesRound));
+        }
+
+        resolve_round_with_jackpot(
+            series,
+            &mut ctx.accounts.jackpot,
+            &mut round,
+            &round_info,
+            &ctx.accounts.oracle_feed,
+            &ctx.accounts.asset,
+            &ctx.accounts.config,
+            &ctx.accounts.instructions,
+            current_time_millis()?,
+        )
+    }
+
+    // Endpoint anyone can call to move what a decided series round can no longer pay out into the series' jackpot:
+    // its jackpot fees, its whole pool if nobody won, and the rounding dust once every wager has been paid. A round
+    // with no wagers left is closed and its rent refunded to the keeper who opened it.
+    pub fn sweep_round_to_jackpot(ctx: Context<SweepRoundToJackpot>) -> Result<()> {
+        let jackpot = &mut ctx.accounts.jackpot;
+        let round_info = ctx.accounts.round.to_account_info();
+        let round_settled;
+
+        {
+            let mut round = ctx.accounts.round.load_mut()?;
+
+            let nobody_won = match round.status()? {
+                BetStateStatus::Closed => {
+                    if round.winning_bet_range == BetRange::NotAvailable as u8 {
+                        return Err(error!(ErrorCode::BetStillUndecided));
+                    }
+                    // The outcome could still be overturned
+                    if !round.claims_unlocked(current_time_millis()?)? {
+                        return Err(error!(ErrorCode::ClaimsLocked));
+                    }
+                    round.winning_pool()? == 0
+                }
+                BetStateStatus::Voided => false,
+                _ => return Err(error!(ErrorCode::BetStillOpen)),
+            };
+
+            let unclaimable = if nobody_won || round.open_wagers == 0 { round.running_total_pool } else { 0 };
+            let amount = round.creator_fees.checked_add(unclaimable).ok_or(ErrorCode::MathOverflow)?;
+            round.creator_fees = 0;
+            round.running_total_pool -= unclaimable;
+
+            **round_info.try_borrow_mut_lamports()? = round_info.lamports().checked_sub(amount).ok_or(ProgramError::InvalidArgument)?;
+            **jackpot.to_account_info().try_borrow_mut_lamports()? = jackpot.to_account_info().lamports().checked_add(amount).ok_or(ProgramError::InvalidArgument)?;
+            jackpot.balance = jackpot.balance.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+
+            round_settled = round.open_wagers == 0;
+        }
+
+        if round_settled {
+            let keeper = ctx.accounts.keeper.to_account_info();
+            ctx.accounts.round.close(keeper.clone())?;
+            ctx.accounts.creator_bond.close(keeper)?;
+        }
+
+        Ok(())
+    }
+
+    // *** User Account Functionality *** //
+    pub fn initialize_user_account(ctx: Context<InitializeUserAccount>) -> Result<()> {
+
+        let user_account = &mut ctx.accounts.user_account;
+        let account_owner = &mut ctx.accounts.account_owner;
+
+        user_account.version = ACCOUNT_VERSION;
+        user_account.account_owner = account_owner.key();
+        user_account.wins = 0;
+        user_account.losses = 0;
+        user_account.current_balance = 0;
+        user_account.referrer = Pubkey::default();
+        user_account.referral_count = 0;
+        user_account.referral_volume = 0;
+        user_account.referral_earnings = 0;
+        user_account.season_volume = 0;
+        user_account.volume_season = 0;
+
+        Ok(())
+    }
+
+    // Endpoint that records who referred a user, it can only be set once.
+    // Someone who has already referred others can't take a referrer, which keeps every referral chain free of loops:
+    // the last link of a loop would always have to come from a user who has already referred someone.
+    pub fn set_referrer(ctx: Context<SetReferrer>) -> Result<()> {
+
+        let user_account = &mut ctx.accounts.user_account;
+        let referrer_account = &mut ctx.accounts.referrer_account;
+
+        if referrer_account.key() == user_account.key() || referrer_account.account_owner == user_account.account_owner {
+            return Err(error!(ErrorCode::SelfReferral));
+        }
+
+        if user_account.referral_count > 0 {
+            return Err(error!(ErrorCode::ReferralLoop));
+        }
+
+        user_account.referrer = referrer_account.key();
+        referrer_account.referral_count += 1;
+
+        Ok(())
+    }
+
+    pub fn close_user_account(_ctx: Context<CloseUserAccount>) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn deposit_into_account(ctx: Context<DepositIntoAccount>, lamports: u64) -> Result<()> {
+
+        let user_account = &mut ctx.accounts.user_account;
+
+        user_account.current_balance += lamports;
+
+        Ok(())
+    }
+
+    pub fn withdraw_from_account(ctx: Context<WithdrawFromAccount>, amount: u64) -> Result<()> {
+
+        let user_account = &mut ctx.accounts.user_account;
+        let account_owner = &mut ctx.accounts.account_owner;
+
+        user_account.current_balance.checked_sub(amount).ok_or(ErrorCode::InvalidWithdrawalAmount)?;
+
+        user_account.current_balance -= amount;
+
+        **user_account.to_account_info().try_borrow_mut_lamports()? = user_account.to_account_info().lamports().checked_sub(amount).ok_or(ProgramError::InvalidArgument)?;
+        **account_owner.to_account_info().try_borrow_mut_lamports()? = account_owner.to_account_info().lamports().checked_add(amount).ok_or(ProgramError::InvalidArgument)?;
+
+        Ok(())
+    }
+
+    // *** Migration Functionality *** //
+
+    // Endpoint that upgrades a version 0 bet state to the current zero-copy layout, anyone can call it and pays for
+    // any extra rent. Version 0 bet states predate creator bonds, so they get an empty escrow to settle against, and
+    // can't be settled or cancelled until the stake on their wagers has been migrated along with them.
+    pub fn migrate_bet_state(ctx: Context<MigrateBetState>) -> Result<()> {
+
+        let bet_state = migration::migrate_bet_state(&ctx.accounts.account, &ctx.accounts.payer, &ctx.accounts.system_program)?;
+
+        let creator_bond = &mut ctx.accounts.creator_bond;
+        creator_bond.version = ACCOUNT_VERSION;
+        creator_bond.bet_state = ctx.accounts.account.key();
+        creator_bond.creator = bet_state.creator;
+        creator_bond.amount = 0;
+        creator_bond.bump = *ctx.bumps.get("creator_bond").unwrap();
+
+        Ok(())
+    }
+
+    // Endpoint that upgrades a version 0 wager detail to the current layout.
+    // Its bet state and user account have to be migrated first, the wager is counted toward the bet state's open wagers and
+    // the bettor's position as it comes across.
+    pub fn migrate_wager_detail(ctx: Context<MigrateWagerDetail>) -> Result<()> {
+
+        let mut bet_state = ctx.accounts.bet_state.load_mut()?;
+
+        let wager_detail = migration::migrate_wager_detail(&ctx.accounts.account, &ctx.accounts.payer, &ctx.accounts.system_program)?;
+
+        if wager_detail.bet_state != ctx.accounts.bet_state.key() || wager_detail.bettor != ctx.accounts.user_account.account_owner {
+            return Err(error!(ErrorCode::InvalidMigrationAccount));
+        }
+
+        bet_state.open_wagers += 1;
+        bet_state.unmigrated_stake = bet_state.unmigrated_stake.checked_sub(wager_detail.bet_value).ok_or(ErrorCode::InvalidMigrationAccount)?;
+
+        // Count the stake on the bettor's position so it can be released when the wager is paid out
+        let bettor_position = &mut ctx.accounts.bettor_position;
+        if bettor_position.bet_state == Pubkey::default() {
+            bettor_position.version = ACCOUNT_VERSION;
+            bettor_position.bet_state = wager_detail.bet_state;
+            bettor_position.user_account = ctx.accounts.user_account.key();
+            bettor_position.bump = *ctx.bumps.get("bettor_position").unwrap();
+        }
+        let bucket_index = wager_detail.range_status.bucket_index().ok_or(ErrorCode::InvalidBetRange)?;
+        bettor_position.total_stake += wager_detail.bet_value;
+        bettor_position.bucket_stakes[bucket_index] += wager_detail.bet_value;
+
+        Ok(())
+    }
+
+    // Endpoint that upgrades a version 0 user account to the current layout
+    pub fn migrate_user_account(ctx: Context<MigrateAccount>) -> Result<()> {
+
+        migration::migrate_user_account(&ctx.accounts.account, &ctx.accounts.payer, &ctx.accounts.system_program)?;
+
+        Ok(())
+    }
+
+}
+
+// ***** UTILITY FUNCTIONS ***** //
+
+// Address of the registry entry for a packed symbol, whether or not it exists
+fn asset_address(symbol: &[u8; MAX_SYMBOL_LEN]) -> Pubkey {
+    let len = symbol.iter().position(|&byte| byte == 0).unwrap_or(MAX_SYMBOL_LEN);
+    Pubkey::find_program_address(&[b"asset", &symbol[..len]], &crate::ID).0
+}
+
+// Address of a series' round, the current one is at the series' round counter
+fn round_address(series: &Pubkey, round: u64) -> Pubkey {
+    Pubkey::find_program_address(&[b"round", series.as_ref(), &round.to_le_bytes()], &crate::ID).0
+}
+
+// Pays a series' whole jackpot into a round's pool. Winners are paid their share of the whole pool, so the jackpot
+// is split between them pro-rata to their stake like the rest of their payout.
+fn award_jackpot(jackpot: &mut Account<SeriesJackpot>, round: &mut BetState, round_info: &AccountInfo, round_number: u64) -> Result<()> {
+    let amount = jackpot.balance;
+    if amount == 0 {
+        return Ok(());
+    }
+
+    round.static_total_pool = round.static_total_pool.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+    round.running_total_pool = round.running_total_pool.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+
+    **jackpot.to_account_info().try_borrow_mut_lamports()? = jackpot.to_account_info().lamports().checked_sub(amount).ok_or(ProgramError::InvalidArgument)?;
+    **round_info.try_borrow_mut_lamports()? = round_info.lamports().checked_add(amount).ok_or(ProgramError::InvalidArgument)?;
+
+    jackpot.balance = 0;
+    jackpot.total_awarded = jackpot.total_awarded.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+    jackpot.last_awarded_round = round_number;
+
+    emit!(JackpotAwarded {
+        series: jackpot.series,
+        round: round_info.key(),
+        round_number,
+        amount,
+    });
+    Ok(())
+}
+
+// Resolves a series' current round the same way resolve_bet_state_outcome resolves any other bet state, then pays the
+// series' jackpot into it if it's decided with winners and the jackpot's trigger is hit
+#[allow(clippy::too_many_arguments)]
+fn resolve_round_with_jackpot<'info>(
+    series: &Account<'info, MarketSeries>,
+    jackpot: &mut Account<'info, SeriesJackpot>,
+    round: &mut BetState,
+    round_info: &AccountInfo<'info>,
+    oracle_feed: &AccountInfo<'info>,
+    asset_info: &AccountInfo<'info>,
+    config: &GlobalConfig,
+    instructions: &AccountInfo<'info>,
+    now: u64,
+) -> Result<()> {
+    if now < round.end_time {
+        return Err(error!(ErrorCode::BetNotYetEnded));
+    }
+
+    resolve_with_oracle(round, round_info.key(), oracle_feed, &[], asset_info, config, instructions, now)?;
+
+    if round.status == BetStateStatus::Closed as u8 && round.winning_pool()? > 0 && series.jackpot_trigger.is_hit(jackpot.balance, series.round) {
+        award_jackpot(jackpot, round, round_info, series.round)?;
+    }
+
+    Ok(())
+}
+
+// The registry entry for a bet state's symbol, or None if the symbol is no longer registered
+fn asset_for_symbol<'info>(asset_info: &AccountInfo<'info>, symbol: &[u8; MAX_SYMBOL_LEN]) -> Result<Option<Account<'info, AssetEntry>>> {
+    if asset_info.key() != asset_address(symbol) {
+        return Err(error!(ErrorCode::InvalidAssetAccount));
+    }
+    if asset_info.data_is_empty() {
+        return Ok(None);
+    }
+    registered_asset(asset_info, symbol).map(Some)
+}
+
+// Zero-pads a symbol into the fixed-size form accounts store it in
+fn pack_symbol(symbol: &str) -> Result<[u8; MAX_SYMBOL_LEN]> {
+    if symbol.is_empty() {
+        return Err(error!(ErrorCode::EmptySymbol));
+    }
+    if symbol.len() > MAX_SYMBOL_LEN {
+        return Err(error!(ErrorCode::SymbolTooLong));
+    }
+    let mut packed = [0; MAX_SYMBOL_LEN];
+    packed[..symbol.len()].copy_from_slice(symbol.as_bytes());
+    Ok(packed)
+}
+
+// The registry entry for a symbol. An unregistered symbol's entry doesn't exist, that and an entry for another symbol
+// both get the same error rather than Anchor's generic account errors.
+fn registered_asset<'info>(asset_info: &AccountInfo<'info>, symbol: &[u8; MAX_SYMBOL_LEN]) -> Result<Account<'info, AssetEntry>> {
+    if asset_info.data_is_empty() || *asset_info.owner != crate::ID {
+        return Err(error!(ErrorCode::AssetNotRegistered));
+    }
+    let asset = Account::<AssetEntry>::try_from(asset_info)?;
+    if asset.symbol != *symbol {
+        return Err(error!(ErrorCode::AssetNotRegistered));
+    }
+    Ok(asset)
+}
+
+fn transfer_winnings(bet_state_info: &AccountInfo, bet_state: &mut BetState, user_account: &Account<UserAccount>, winnings_amount: u64) -> Result<()> {
+    **bet_state_info.try_borrow_mut_lamports()? = bet_state_info.lamports().checked_sub(winnings_amount).ok_or(ProgramError::InvalidArgument)?;
+    **user_account.to_account_info().try_borrow_mut_lamports()? = user_account.to_account_info().lamports().checked_add(winnings_amount).ok_or(ProgramError::InvalidArgument)?;
+    bet_state.running_total_pool -= winnings_amount;
+    Ok(())
+}
+
+// Takes a wager's stake off the bettor's position, which is closed to the bettor once none of their stake is left on the bet state
+fn release_position_stake<'info>(bettor_position: &mut Account<'info, BettorPosition>, bucket_index: usize, stake: u64, bettor: &AccountInfo<'info>) -> Result<()> {
+    bettor_position.total_stake = bettor_position.total_stake.checked_sub(stake).ok_or(ErrorCode::MathOverflow)?;
+    bettor_position.bucket_stakes[bucket_index] = bettor_position.bucket_stakes[bucket_index].checked_sub(stake).ok_or(ErrorCode::MathOverflow)?;
+
+    if bettor_position.total_stake == 0 {
+        bettor_position.close(bettor.clone())?;
+    }
+
+    Ok(())
+}
+
+// Moves the creator's bond from escrow into the bet state, where it is paid out to every bettor pro-rata to their stake
+// along with the creator fees the bet state accrued. With no bettors there is nobody to compensate and both stay put.
+fn slash_creator_bond(bet_state_info: &AccountInfo, bet_state: &mut BetState, creator_bond: &mut Account<CreatorBond>) -> Result<()> {
+    bet_state.creator_at_fault = 1;
+
+    if bet_state.static_total_pool == 0 {
+        return Ok(());
+    }
+
+    let amount = creator_bond.amount;
+    **creator_bond.to_account_info().try_borrow_mut_lamports()? = creator_bond.to_account_info().lamports().checked_sub(amount).ok_or(ProgramError::InvalidArgument)?;
+    **bet_state_info.try_borrow_mut_lamports()? = bet_state_info.lamports().checked_add(amount).ok_or(ProgramError::InvalidArgument)?;
+
+    // The creator fees are already held by the bet state
+    let slashed = amount + bet_state.creator_fees;
+    creator_bond.amount = 0;
+    bet_state.creator_fees = 0;
+    bet_state.slashed_bond = slashed;
+    bet_state.running_total_pool += slashed;
+
+    Ok(())
+}
+
+// Bet state times are stored in milliseconds, so the cluster clock is converted to match
+fn current_time_millis() -> Result<u64> {
+    let clock = Clock::get()?;
+    Ok((clock.unix_timestamp as u64).saturating_mul(1000))
+}
+
+// Decides an ended bet state's outcome from its oracle sources, voiding it if the asset's calendar closed the market
+// during it or if the price fails its guards past the retry window
+#[allow(clippy::too_many_arguments)]
+fn resolve_with_oracle<'info>(
+    bet_state: &mut BetState,
+    bet_state_key: Pubkey,
+    oracle_feed: &AccountInfo<'info>,
+    secondary_feeds: &[AccountInfo<'info>],
+    asset_info: &AccountInfo<'info>,
+    config: &GlobalConfig,
+    instructions: &AccountInfo<'info>,
+    now: u64,
+) -> Result<()> {
+    // The calendar can change after a bet state is created, a holiday added since then voids the bet instead of
+    // letting a stale price decide it. Bet states on an asset that has been deregistered resolve as before.
+    if let Some(asset) = asset_for_symbol(asset_info, &bet_state.symbol)? {
+        if !asset.is_open_throughout(bet_state.start_time, bet_state.end_time) {
+            bet_state.status = BetStateStatus::Voided as u8;
+            emit!(BetStateVoided {
+                bet_state: bet_state_key,
+                voided_at: now,
+            });
+            return Ok(());
+        }
+    }
+
+    // A relative-performance market's benchmark feed is passed after the secondary feeds
+    let secondary_oracles = bet_state.secondary_oracles()?;
+    let market_type = bet_state.market_type()?;
+    if secondary_feeds.len() != secondary_oracles.len() + market_type.benchmark_feed_count() {
+        return Err(error!(ErrorCode::InvalidOracleSources));
+    }
+    let (secondary_feeds, benchmark_feeds) = secondary_feeds.split_at(secondary_oracles.len());
+
+    let mut feeds = vec![(bet_state.oracle_kind()?, oracle_feed.clone())];
+    for (source, feed) in secondary_oracles.iter().zip(secondary_feeds.iter()) {
+        if feed.key() != source.feed {
+            return Err(error!(ErrorCode::InvalidOracleFeed));
+        }
+        feeds.push((source.kind, feed.clone()));
+    }
+    if let Some(benchmark_feed) = benchmark_feeds.first() {
+        if benchmark_feed.key() != bet_state.benchmark_oracle_feed {
+            return Err(error!(ErrorCode::InvalidOracleFeed));
+        }
+    }
+
+    let oracle_guards = bet_state.oracle_guards();
+    let outcome = aggregate_oracle_price(
+        &feeds,
+        &oracle_guards,
+        bet_state.oracle_quorum,
+        bet_state.end_time,
+        true,
+        config,
+        instructions,
+        now,
+    )
+    .and_then(|(price, sources)| {
+        // The benchmark has to pass the same guards at the same time, on its one feed
+        let benchmark_price = match benchmark_feeds.first() {
+            Some(benchmark_feed) => {
+                let benchmark_oracle = [(bet_state.benchmark_oracle_kind()?, benchmark_feed.clone())];
+                let (benchmark_price, _) = aggregate_oracle_price(&benchmark_oracle, &oracle_guards, 1, bet_state.end_time, true, config, instructions, now)?;
+                Some(benchmark_price.price)
+            }
+            None => None,
+        };
+
+        winning_ranges_for_price(bet_state, &price.price, benchmark_price.as_ref())
+            .map(|(winning_bet_range, split_bet_range)| (winning_bet_range, split_bet_range, price, sources, benchmark_price))
+    });
+
+    match outcome {
+        // A close inside an up/down market's dead zone refunds every wager
+        Ok((BetRange::NotAvailable, _, price, sources, _)) => {
+            bet_state.status = BetStateStatus::Voided as u8;
+            bet_state.set_closing_price(&price.price);
+            bet_state.set_resolution_sources(&sources);
+            emit!(BetStateVoided {
+                bet_state: bet_state_key,
+                voided_at: now,
+            });
+        },
+        Ok((winning_bet_range, split_bet_range, price, sources, benchmark_price)) => {
+            bet_state.status = BetStateStatus::Closed as u8;
+            bet_state.winning_bet_range = winning_bet_range as u8;
+            bet_state.split_bet_range = split_bet_range as u8;
+            bet_state.open_dispute_window(now);
+            bet_state.set_closing_price(&price.price);
+            bet_state.set_resolution_sources(&sources);
+            if let Some(benchmark_price) = benchmark_price {
+                bet_state.set_benchmark_closing_price(&benchmark_price);
+            }
+        },
+        // Only the feed failing its guards counts toward voiding, mistakes in the accounts or instructions the caller
+        // passed fail as they are so they can't be used to void a market
+        Err(guard_error) => {
+            let retry_deadline = bet_state.end_time.saturating_add(oracle_guards.retry_window_secs.saturating_mul(1000));
+            if now < retry_deadline || !is_guard_failure(&guard_error) {
+                return Err(guard_error);
+            }
+
+            bet_state.status = BetStateStatus::Voided as u8;
+            emit!(BetStateVoided {
+                bet_state: bet_state_key,
+                voided_at: now,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+// Reads every oracle source of a bet state and aggregates the usable ones into a single price as of `reference_time`,
+// returning it along with the feeds that went into it. A single source fails with its own error, with several
+// sources the unusable ones are dropped and the rest have to reach the bet's quorum. When `bracketed`, signed feeds
+// only take the publisher's first message at or after the reference time, which is how a bet's snapshot and closing
+// prices are both picked.
+#[allow(clippy::too_many_arguments)]
+fn aggregate_oracle_price(
+    feeds: &[(OracleKind, AccountInfo)],
+    guards: &OracleGuards,
+    quorum: u8,
+    reference_time: u64,
+    bracketed: bool,
+    config: &GlobalConfig,
+    instructions: &AccountInfo,
+    now: u64,
+) -> Result<(OraclePrice, Vec<Pubkey>)> {
+    let priced_at = if bracketed { Some(reference_time) } else { None };
+
+    if feeds.len() == 1 {
+        let (kind, feed) = &feeds[0];
+        let price = oracle::load_price(*kind, feed, config, instructions, priced_at)?;
+        check_oracle_price(guards, &price, reference_time, now)?;
+        return Ok((price, vec![feed.key()]));
+    }
+
+    let mut prices = Vec::with_capacity(feeds.len());
+    let mut sources = Vec::with_capacity(feeds.len());
+    for (kind, feed) in feeds {
+        let usable_price = oracle::load_price(*kind, feed, config, instructions, priced_at)
+            .and_then(|price| check_oracle_price(guards, &price, reference_time, now).map(|_| price));
+
+        if let Ok(price) = usable_price {
+            prices.push(price);
+            sources.push(feed.key());
+        }
+    }
+
+    if prices.len() < quorum as usize {
+        return Err(error!(ErrorCode::OracleQuorumNotReached));
+    }
+
+    Ok((oracle::median_price(&prices)?, sources))
+}
+
+// Whether an error is an oracle price failing a bet's guards, as opposed to anything the caller got wrong
+fn is_guard_failure(error: &Error) -> bool {
+    const GUARD_FAILURES: [ErrorCode; 6] = [
+        ErrorCode::OraclePriceStale,
+        ErrorCode::OraclePriceNotTrading,
+        ErrorCode::InvalidOraclePrice,
+        ErrorCode::OracleConfidenceTooWide,
+        ErrorCode::OracleConfidenceStraddlesBoundary,
+        ErrorCode::OracleQuorumNotReached,
+    ];
+    match error {
+        Error::AnchorError(anchor_error) => GUARD_FAILURES.iter().any(|code| anchor_error.error_code_number == u32::from(*code)),
+        _ => false,
+    }
+}
+
+// A snapshot is what every bucket is measured from, so a price that can't be used reports it as the snapshot's fault
+fn snapshot_price_error(error: Error) -> Error {
+    match &error {
+        Error::AnchorError(anchor_error) if anchor_error.error_code_number == u32::from(ErrorCode::InvalidOraclePrice) => {
+            error!(ErrorCode::InvalidSnapshotPrice)
+        },
+        _ => error,
+    }
+}
+
+// Runs a bet's oracle guards against a single price meant to stand for the price at `reference_time`
+fn check_oracle_price(guards: &OracleGuards, oracle_price: &OraclePrice, reference_time: u64, now: u64) -> Result<()> {
+    let price = &oracle_price.price;
+    let now_secs = (now / 1000) as i64;
+    let reference_secs = (reference_time / 1000) as i64;
+    let max_staleness = guards.max_staleness_secs as i64;
+
+    if !oracle_price.trading {
+        return Err(error!(ErrorCode::OraclePriceNotTrading));
+    }
+
+    // The price has to be recent and can't have been published too long before or after the time it stands for,
+    // otherwise resolution could be held back until the feed moves somewhere favourable
+    if now_secs.saturating_sub(price.publish_time) > max_staleness
+        || price.publish_time.saturating_add(max_staleness) < reference_secs
+        || price.publish_time > reference_secs.saturating_add(max_staleness)
+    {
+        return Err(error!(ErrorCode::OraclePriceStale));
+    }
+
+    if price.mantissa <= 0 {
+        return Err(error!(ErrorCode::InvalidOraclePrice));
+    }
+
+    if guards.max_confidence_bps != 0
+        && price.conf as u128 * BASIS_POINTS as u128 > price.mantissa as u128 * guards.max_confidence_bps as u128
+    {
+        return Err(error!(ErrorCode::OracleConfidenceTooWide));
+    }
+
+    Ok(())
+}
+
+// Returns the range the closing price lands in under the bet's boundary rule, along with the adjacent range that
+// shares the pool when the bet splits on a close call (NotAvailable otherwise). Ranges are percent moves from the
+// snapshot price, the spans between strikes on over/under markets, or the spread over the benchmark's percent move
+// on relative-performance markets. Up/down markets have their own rules, see up_down_range_for_price.
+fn winning_ranges_for_price(bet_state: &BetState, closing_price: &Price, benchmark_closing_price: Option<&Price>) -> Result<(BetRange, BetRange)> {
+    let market_type = bet_state.market_type()?;
+    if let MarketType::UpDown { dead_zone_bps } = market_type {
+        return Ok((up_down_range_for_price(bet_state, closing_price, dead_zone_bps)?, BetRange::NotAvailable));
+    }
+
+    let rule = BoundaryRule::from_u8(bet_state.boundary_rule)?;
+    let snapshot_price = &bet_state.snapshot_price();
+    let bucket_width_bps = bet_state.bucket_width_bps();
+    let benchmark_snapshot_price = &bet_state.benchmark_snapshot_price();
+    let range_for = |mantissa: i128, benchmark_mantissa: i128| -> Result<BetRange> {
+        match &market_type {
+            MarketType::OverUnder { strikes, expo } => rule.strike_range(strikes, *expo, mantissa, closing_price.expo),
+            MarketType::RelativePerformance { .. } => {
+                let benchmark_expo = benchmark_closing_price.ok_or(ErrorCode::InvalidOracleSources)?.expo;
+                let spread_bps = snapshot_price.spread_bps_to(
+                    mantissa,
+                    closing_price.expo,
+                    benchmark_snapshot_price,
+                    benchmark_mantissa,
+                    benchmark_expo,
+                    rule.rounding(),
+                )?;
+                Ok(rule.range_for_change_bps(spread_bps, bucket_width_bps))
+            }
+            _ => rule.bet_range(snapshot_price, mantissa, closing_price.expo, bucket_width_bps),
+        }
+    };
+
+    // The spread is lowest with the bet's asset at the bottom of its interval and the benchmark at the top
+    let (benchmark_mantissa, (benchmark_low, benchmark_high)) = match benchmark_closing_price {
+        Some(price) => (price.mantissa as i128, price.confidence_interval()),
+        None => (0, (0, 0)),
+    };
+    let winning_bet_range = range_for(closing_price.mantissa as i128, benchmark_mantissa)?;
+
+    let (low, high) = closing_price.confidence_interval();
+    let low_range = range_for(low, benchmark_high)?;
+    let high_range = range_for(high, benchmark_low)?;
+    if low_range == winning_bet_range && high_range == winning_bet_range {
+        return Ok((winning_bet_range, BetRange::NotAvailable));
+    }
+
+    // A confidence interval crossing a single boundary can be split between the two ranges either side of it,
+    // anything wider, or a bet that doesn't split, leaves the outcome a coin flip
+    let crosses_one_boundary = match (low_range.bucket_index(), high_range.bucket_index()) {
+        (Some(low_index), Some(high_index)) => low_index + 1 == high_index,
+        _ => false,
+    };
+    if StraddlePolicy::from_u8(bet_state.straddle_policy)? == StraddlePolicy::Split && crosses_one_boundary {
+        let split_bet_range = if low_range == winning_bet_range { high_range } else { low_range };
+        return Ok((winning_bet_range, split_bet_range));
+    }
+
+    Err(error!(ErrorCode::OracleConfidenceStraddlesBoundary))
+}
+
+// Returns the side of the snapshot price an up/down market closes on, or NotAvailable when the close is within
+// `dead_zone_bps` of the snapshot, exactly on it included. The whole confidence interval has to land on the same
+// side, an up/down market never splits.
+fn up_down_range_for_price(bet_state: &BetState, closing_price: &Price, dead_zone_bps: u16) -> Result<BetRange> {
+    let snapshot_price = bet_state.snapshot_price();
+    let dead_zone_bps = dead_zone_bps as i64;
+
+    let range_for = |mantissa: i128| -> Result<BetRange> {
+        if snapshot_price.change_bps_to(mantissa, closing_price.expo, Rounding::Up)? > dead_zone_bps {
+            bet_state.outcome_range(MarketType::UP_BUCKET)
+        } else if snapshot_price.change_bps_to(mantissa, closing_price.expo, Rounding::Down)? < -dead_zone_bps {
+            bet_state.outcome_range(MarketType::DOWN_BUCKET)
+        } else {
+            Ok(BetRange::NotAvailable)
+        }
+    };
+
+    let winning_bet_range = range_for(closing_price.mantissa as i128)?;
+    let (low, high) = closing_price.confidence_interval();
+    if range_for(low)? != winning_bet_range || range_for(high)? != winning_bet_range {
+        return Err(error!(ErrorCode::OracleConfidenceStraddlesBoundary));
+    }
+
+    Ok(winning_bet_range)
+}
+
+// Checks a freshly applied wager against the bet state's limits. A limit of 0 means that limit is not enforced.
+fn enforce_wager_limits(bet_state: &BetState, bettor_position: &BettorPosition, bucket_index: usize, wager_amount: u64) -> Result<()> {
+    let limits = bet_state.limits();
+
+    if wager_amount < limits.min_wager {
+        return Err(error!(ErrorCode::WagerBelowMinimum));
+    }
+
+    if limits.max_wager != 0 && wager_amount > limits.max_wager {
+        return Err(error!(ErrorCode::WagerAboveMaximum));
+    }
+
+    if limits.max_user_stake != 0 && bettor_position.total_stake > limits.max_user_stake {
+        return Err(error!(ErrorCode::UserStakeLimitExceeded));
+    }
+
+    if limits.max_total_pool != 0 && bet_state.static_total_pool > limits.max_total_pool {
+        return Err(error!(ErrorCode::TotalPoolLimitExceeded));
+    }
+
+    // A single bettor can always seed a bucket with one max-size wager, past that they can only hold their share of it
+    let bucket_pool = bet_state.pool_for_bucket(bucket_index);
+    if limits.max_bucket_share_bps != 0 && bucket_pool > limits.max_wager {
+        let bettor_bucket_stake = bettor_position.bucket_stakes[bucket_index] as u128;
+        let max_bettor_bucket_stake = bucket_pool as u128 * limits.max_bucket_share_bps as u128 / BASIS_POINTS as u128;
+        if bettor_bucket_stake > max_bettor_bucket_stake {
+            return Err(error!(ErrorCode::BucketShareLimitExceeded));
+        }
+    }
+
+    Ok(())
+}
+
+
+// ***** CONFIG CONTEXT AND STRUCTS ***** //
+
+// Context to create the global config, whoever creates it becomes the admin
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(init, payer = admin, space = GlobalConfig::MAX_SIZE + 8, seeds = [b"config"], bump)]
+    config: Account<'info, GlobalConfig>,
+
+    #[account(mut)]
+    admin: Signer<'info>,
+
+    /// CHECK: Not read from or written to
+    #[account(address = system_program::ID)]
+    pub system_program: AccountInfo<'info>,
+}
+
+// Context for the pauser to pause or unpause betting
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = pauser @ ErrorCode::NotPauser,
+    )]
+    config: Account<'info, GlobalConfig>,
+
+    pauser: Signer<'info>,
+}
+
+// Context for the admin to update the global config
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ ErrorCode::NotConfigAdmin,
+    )]
+    config: Account<'info, GlobalConfig>,
+
+    admin: Signer<'info>,
+}
+
+#[account]
+pub struct GlobalConfig {
+    pub version: u8, // 1
+    pub admin: Pubkey, // 32
+    pub default_limits: WagerLimits, // 34
+    pub pyth_program: Pubkey, // 32
+    pub switchboard_program: Pubkey, // 32
+    pub signed_feed_publishers: Vec<Pubkey>, // 4 + (32 * 5)
+    pub default_oracle_guards: OracleGuards, // 18
+    pub arbitration_authority: Pubkey, // 32
+    pub dispute_bond: u64, // 8, lamports
+    pub dispute_window_secs: u64, // 8, 0 means outcomes can't be disputed
+    pub resolver: Pubkey, // 32
+    pub pauser: Pubkey, // 32
+    pub paused: bool, // 1
+    pub creator_bond: u64, // 8, lamports
+    pub treasury: Pubkey, // 32
+    pub protocol_fee_bps: u16, // 2
+    pub max_creator_fee_bps: u16, // 2
+    pub referral_share_bps: u16, // 2, share of the protocol fee
+    pub fee_tiers: Vec<FeeTier>, // 4 + (10 * 5)
+    pub fee_season: u32, // 4
+    pub bump: u8, // 1
+}
+
+impl GlobalConfig {
+    const MAX_SIZE: usize = 531;
+    const MAX_SIGNED_FEED_PUBLISHERS: usize = 5;
+    const MAX_FEE_TIERS: usize = 5;
+
+    // Protocol fee for a user's next wager: the highest tier their volume this season reaches, but never more than the
+    // fee the bet state was created with
+    fn effective_protocol_fee_bps(&self, bet_state: &BetState, user_account: &UserAccount) -> u16 {
+        let season_volume = user_account.season_volume(self.fee_season);
+
+        self.fee_tiers
+            .iter()
+            .rev()
+            .find(|tier| season_volume >= tier.min_volume)
+            .map_or(bet_state.protocol_fee_bps, |tier| tier.protocol_fee_bps.min(bet_state.protocol_fee_bps))
+    }
+}
+
+// Protocol fee charged once a user has wagered at least `min_volume` lamports in the current fee season
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Copy, Debug, Default)]
+pub struct FeeTier {
+    pub min_volume: u64, // 8
+    pub protocol_fee_bps: u16, // 2
+}
+
+// Terms a bet state is created with. Limits and oracle guards left unset fall back to the asset's and the config's
+// defaults, and a resolver committee left unset leaves the bet to its oracle sources and the resolver.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct BetStateParams {
+    pub start: u64,
+    pub duration: u64,
+    pub symbol: String,
+    pub limits: Option<WagerLimits>,
+    pub betting_cutoff: u64,
+    pub oracle_guards: Option<OracleGuards>,
+    pub oracle_kind: OracleKind,
+    pub secondary_oracles: Vec<OracleSource>,
+    pub oracle_quorum: u8,
+    pub boundary_rule: BoundaryRule,
+    pub straddle_policy: StraddlePolicy,
+    pub resolver_committee: Option<Pubkey>,
+    pub creator_fee_bps: u16,
+    pub market_type: MarketType,
+}
+
+// Betting limits for a single bet state, all amounts are in lamports after the take rate is removed.
+// A value of 0 disables that limit.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Copy, Debug, Default)]
+pub struct WagerLimits {
+    pub min_wager: u64, // 8
+    pub max_wager: u64, // 8
+    pub max_user_stake: u64, // 8
+    pub max_total_pool: u64, // 8
+    pub max_bucket_share_bps: u16, // 2
+}
+
+impl WagerLimits {
+    fn validate(&self) -> Result<()> {
+        if self.max_wager != 0 && self.min_wager > self.max_wager {
+            return Err(error!(ErrorCode::InvalidWagerLimits));
+        }
+        if self.max_user_stake != 0 && self.min_wager > self.max_user_stake {
+            return Err(error!(ErrorCode::InvalidWagerLimits));
+        }
+        if self.max_total_pool != 0 && self.min_wager > self.max_total_pool {
+            return Err(error!(ErrorCode::InvalidWagerLimits));
+        }
+        if self.max_bucket_share_bps as u64 > BASIS_POINTS {
+            return Err(error!(ErrorCode::InvalidWagerLimits));
+        }
+        // The bucket share is only enforced once a bucket outgrows a single max-size wager
+        if self.max_bucket_share_bps != 0 && self.max_wager == 0 {
+            return Err(error!(ErrorCode::InvalidWagerLimits));
+        }
+        Ok(())
+    }
+}
+
+
+// ***** ASSET REGISTRY CONTEXT AND STRUCTS ***** //
+
+// Context for the admin to add a symbol to the asset registry
+#[derive(Accounts)]
+#[instruction(symbol: String)]
+pub struct RegisterAsset<'info> {
+    #[account(seeds = [b"config"], bump = config.bump, has_one = admin @ ErrorCode::NotConfigAdmin)]
+    config: Account<'info, GlobalConfig>,
+
+    #[account(init, payer = admin, space = AssetEntry::MAX_SIZE + 8, seeds = [b"asset", symbol.as_bytes()], bump)]
+    asset: Account<'info, AssetEntry>,
+
+    /// CHECK: Feed bet states on the asset are resolved from, validated by the adapter for its oracle kind
+    oracle_feed: AccountInfo<'info>,
+
+    #[account(mut)]
+    admin: Signer<'info>,
+
+    /// CHECK: Not read from or written to
+    #[account(address = system_program::ID)]
+    pub system_program: AccountInfo<'info>,
+}
+
+// Context for the admin to change an asset's entry
+#[derive(Accounts)]
+pub struct UpdateAsset<'info> {
+    #[account(seeds = [b"config"], bump = config.bump, has_one = admin @ ErrorCode::NotConfigAdmin)]
+    config: Account<'info, GlobalConfig>,
+
+    #[account(mut)]
+    asset: Account<'info, AssetEntry>,
+
+    admin: Signer<'info>,
+}
+
+// Context for the admin to point an asset at another oracle feed
+#[derive(Accounts)]
+pub struct SetAssetOracle<'info> {
+    #[account(seeds = [b"config"], bump = config.bump, has_one = admin @ ErrorCode::NotConfigAdmin)]
+    config: Account<'info, GlobalConfig>,
+
+    #[account(mut)]
+    asset: Account<'info, AssetEntry>,
+
+    /// CHECK: Validated by the adapter for the asset's new oracle kind
+    oracle_feed: AccountInfo<'info>,
+
+    admin: Signer<'info>,
+}
+
+// Context for the admin to remove a symbol from the asset registry
+#[derive(Accounts)]
+pub struct DeregisterAsset<'info> {
+    #[account(seeds = [b"config"], bump = config.bump, has_one = admin @ ErrorCode::NotConfigAdmin)]
+    config: Account<'info, GlobalConfig>,
+
+    #[account(mut, close = admin)]
+    asset: Account<'info, AssetEntry>,
+
+    #[account(mut)]
+    admin: Signer<'info>,
+}
+
+// An asset bet states can be created on, one entry per symbol at the PDA of ["asset", symbol]
+#[account]
+pub struct AssetEntry {
+    pub version: u8, // 1
+    pub symbol: [u8; 16], // 16, zero-padded
+    pub asset_class: AssetClass, // 1
+    pub oracle_kind: OracleKind, // 1
+    pub oracle_feed: Pubkey, // 32
+    pub bucket_layout: BucketLayout, // 2
+    pub default_limits: WagerLimits, // 34
+    pub trading_hours: TradingHours, // 5
+    pub holidays: Vec<u32>, // 4 + (4 * 32), days since the unix epoch in UTC
+    pub enabled: bool, // 1
+    pub bump: u8, // 1
+}
+
+impl AssetEntry {
+    const MAX_SIZE: usize = 226;
+    const MAX_HOLIDAYS: usize = 32;
+
+    // Whether the market is open from `start` to `end`, both unix milliseconds. No day the window touches can be a
+    // holiday, and the part of the window on each day has to fall inside that day's trading hours.
+    fn is_open_throughout(&self, start: u64, end: u64) -> bool {
+        let first_day = start / TradingHours::MILLIS_PER_DAY;
+        let last_day = end.saturating_sub(1).max(start) / TradingHours::MILLIS_PER_DAY;
+
+        if self.holidays.iter().any(|&holiday| (first_day..=last_day).contains(&(holiday as u64))) {
+            return false;
+        }
+
+        // Any window longer than a week runs into a closed day unless the market never closes, so the loop is short
+        self.trading_hours.is_always_open()
+            || (first_day..=last_day).all(|day| self.trading_hours.is_open_throughout(day, start, end))
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AssetClass {
+    Equity,
+    Etf,
+    Index,
+    Crypto,
+    Forex,
+    Commodity,
+}
+
+impl AssetClass {
+    // Crypto trades around the clock, so closed hours or holidays on one would only block bets the market is open for
+    fn validate_closures(&self, trading_hours: &TradingHours, holidays: &[u32]) -> Result<()> {
+        if *self == AssetClass::Crypto && (!trading_hours.is_always_open() || !holidays.is_empty()) {
+            return Err(error!(ErrorCode::AssetNeverCloses));
+        }
+        Ok(())
+    }
+}
+
+// Where the boundaries between a bet state's ranges sit, as percent moves from the snapshot price
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Copy, Debug, Default)]
+pub struct BucketLayout {
+    pub bucket_width_bps: u16, // 2, size of every range but the two open-ended ones
+}
+
+impl BucketLayout {
+    fn validate(&self) -> Result<()> {
+        // The lowest boundary is three widths below the snapshot and has to stay above a 100% fall
+        if self.bucket_width_bps == 0 || self.bucket_width_bps as u64 * 3 >= BASIS_POINTS {
+            return Err(error!(ErrorCode::InvalidBucketLayout));
+        }
+        Ok(())
+    }
+}
+
+// When an asset's market is open: from `open_minute` to `close_minute` after midnight UTC on each day in `trading_days`.
+// An asset that never closes trades every day from minute 0 to 1440. Hours are in UTC, so an exchange that observes
+// daylight saving needs them updated when its clocks change.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Copy, Debug, Default)]
+pub struct TradingHours {
+    pub trading_days: u8, // 1, bit 0 is Monday through bit 6 for Sunday
+    pub open_minute: u16, // 2
+    pub close_minute: u16, // 2
+}
+
+impl TradingHours {
+    const MINUTES_PER_DAY: u16 = 1_440;
+    const MILLIS_PER_MINUTE: u64 = 60_000;
+    const MILLIS_PER_DAY: u64 = 86_400_000;
+    const ALL_DAYS: u8 = 0b0111_1111;
+
+    fn validate(&self) -> Result<()> {
+        if self.trading_days == 0
+            || self.trading_days & !TradingHours::ALL_DAYS != 0
+            || self.open_minute >= self.close_minute
+            || self.close_minute > TradingHours::MINUTES_PER_DAY
+        {
+            return Err(error!(ErrorCode::InvalidTradingHours));
+        }
+        Ok(())
+    }
+
+    fn is_always_open(&self) -> bool {
+        self.trading_days == TradingHours::ALL_DAYS && self.open_minute == 0 && self.close_minute == TradingHours::MINUTES_PER_DAY
+    }
+
+    // Whether the part of [start, end) on `day`, counted in days since the unix epoch, is all within trading hours
+    fn is_open_throughout(&self, day: u64, start: u64, end: u64) -> bool {
+        // The epoch fell on a Thursday, bit 3 counting from Monday
+        let weekday = (day + 3) % 7;
+        let day_start = day * TradingHours::MILLIS_PER_DAY;
+        let open = day_start.saturating_add(self.open_minute as u64 * TradingHours::MILLIS_PER_MINUTE);
+        let close = day_start.saturating_add(self.close_minute as u64 * TradingHours::MILLIS_PER_MINUTE);
+
+        self.trading_days & (1 << weekday) != 0
+            && start.max(day_start) >= open
+            && end.min(day_start.saturating_add(TradingHours::MILLIS_PER_DAY)) <= close
+    }
+}
+
+
+// ***** BETTING FUNCTIONALITY CONTEXT AND STRUCTS ***** //
+// Adding an account on a context simply means its public key should be provided when sending the instruction
+
+// Context to initialize the bet state
+#[derive(Accounts)]
+pub struct InitializeBetState<'info> {
+
+    #[account(init, payer = bet_creator, space = BetState::MAX_SIZE + 8)]
+    bet_state: AccountLoader<'info, BetState>,
+
+    /// CHECK: Used to pay for the bet state account
+    #[account(signer, mut)]
+    bet_creator: AccountInfo<'info>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    config: Account<'info, GlobalConfig>,
+
+    /// CHECK: Registry entry for the bet's symbol, checked in the endpoint so an unregistered symbol gets its own error
+    asset: AccountInfo<'info>,
+
+    /// CHECK: Feed the outcome is resolved from, validated by the adapter for the bet's oracle kind
+    oracle_feed: AccountInfo<'info>,
+
+    /// CHECK: Instructions sysvar, signed feeds read their opening price message from a preceding ed25519 instruction
+    #[account(address = sysvar::instructions::ID)]
+    instructions: AccountInfo<'info>,
+
+    #[account(
+        init,
+        payer = bet_creator,
+        space = CreatorBond::MAX_SIZE + 8,
+        seeds = [b"creator_bond", bet_state.key().as_ref()],
+        bump
+    )]
+    creator_bond: Account<'info, CreatorBond>,
+
+    /// CHECK: Not read from or written to
+    #[account(address = system_program::ID)]
+    pub system_program: AccountInfo<'info>,
+
+}
+
+// Context to place a wager on a created bet 
+#[derive(Accounts)]
+pub struct PlaceWager<'info> {
+
+    #[account(
+        constraint = bet_state.load()?.status == BetStateStatus::Open as u8 @ ErrorCode::BetIsClosedOrSettled,
+        constraint = bet_state.load()?.winning_bet_range == BetRange::NotAvailable as u8 @ ErrorCode::BetAlreadyDecided,
+        mut
+    )]
+    bet_state: AccountLoader<'info, BetState>,
+
+    #[account(init, payer = bettor_account, space = WagerDetail::MAX_SIZE + 8)]
+    wager_detail: Account<'info, WagerDetail>,
+
+    #[account(
+        mut,
+        constraint = user_account.account_owner == bettor_account.key() @ ErrorCode::NotAccountOwnerToPlaceWager,
+    )]
+    user_account: Account<'info, UserAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = bettor_account,
+        space = BettorPosition::MAX_SIZE + 8,
+        seeds = [b"position", bet_state.key().as_ref(), user_account.key().as_ref()],
+        bump,
+    )]
+    bettor_position: Account<'info, BettorPosition>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    config: Account<'info, GlobalConfig>,
+
+    /// CHECK: Credited with the protocol fee, checked against the config
+    #[account(mut, address = config.treasury @ ErrorCode::InvalidTreasury)]
+    treasury: AccountInfo<'info>,
+
+    /// CHECK: Used to pay for the wager detail account
+    #[account(signer, mut)]
+    bettor_account: AccountInfo<'info>,
+
+    /// CHECK: Not read from or written to
+    #[account(address = system_program::ID)]
+    system_program: AccountInfo<'info>,
+}
+
+// Context to cancel a wager on a created bet
+#[derive(Accounts)]
+pub struct CancelWager<'info> {
+
+    #[account(
+        constraint = bet_state.load()?.status == BetStateStatus::Open as u8 @ ErrorCode::BetIsClosedOrSettled,
+        constraint = bet_state.load()?.winning_bet_range == BetRange::NotAvailable as u8 @ ErrorCode::BetAlreadyDecided,
+        mut,
+    )]
+    bet_state: AccountLoader<'info, BetState>,
+
+    #[account(
+        has_one = bet_state,
+        constraint = wager_detail.bettor == bettor.key(),
+        mut, 
+        close = bettor,
+    )]
+    wager_detail: Account<'info, WagerDetail>,
+
+    #[account(
+        mut,
+        constraint = user_account.account_owner == bettor.key() @ ErrorCode::NotAccountOwnerToPlaceWager,
+        constraint = !user_account.active_wagers.is_empty() @ ErrorCode::ActiveWagersEmpty,
+    )]
+    user_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"position", bet_state.key().as_ref(), user_account.key().as_ref()],
+        bump = bettor_position.bump,
+    )]
+    bettor_position: Account<'info, BettorPosition>,
+
+    /// CHECK: Used to cancel the wager it owns
+    #[account(signer, mut)]
+    bettor: AccountInfo<'info>
+}
+
+// Context to close a wager once the bet state it's attached to has already been decided and the wager is a losing one
+#[derive(Accounts)]
+pub struct CloseLosingWager<'info> {
+    #[account(
+        constraint = bet_state.load()?.status == BetStateStatus::Closed as u8 @ ErrorCode::BetStillOpen,
+        constraint = bet_state.load()?.winning_bet_range != BetRange::NotAvailable as u8 @ ErrorCode::BetStillUndecided,
+        mut
+    )]
+    bet_state: AccountLoader<'info, BetState>,
+
+    #[account(
+        has_one = bet_state,
+        constraint = wager_detail.bettor == bettor.key(),
+        constraint = wager_detail.range_status as u8 != bet_state.load()?.winning_bet_range @ ErrorCode::PleaseClaimYourWinnings,
+        mut, 
+        close = bettor,
+    )]
+    wager_detail: Account<'info, WagerDetail>,
+
+    #[account(
+        mut,
+        constraint = user_account.account_owner == bettor.key() @ ErrorCode::NotAccountOwnerToPlaceWager,
+        constraint = !user_account.active_wagers.is_empty() @ ErrorCode::ActiveWagersEmpty,
+    )]
+    user_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"position", bet_state.key().as_ref(), user_account.key().as_ref()],
+        bump = bettor_position.bump,
+    )]
+    bettor_position: Account<'info, BettorPosition>,
+
+    /// CHECK: Used to cancel the wager it owns
+    #[account(signer, mut)]
+    bettor: AccountInfo<'info>
+}
+
+// Context to claim winnings from a closed bet with a decided outcome
+#[derive(Accounts)]
+pub struct ClaimWinnings<'info> {
+
+    #[account(
+        constraint = bet_state.load()?.status == BetStateStatus::Closed as u8 @ ErrorCode::BetStillOpen,
+        constraint = bet_state.load()?.winning_bet_range != BetRange::NotAvailable as u8 @ ErrorCode::BetStillUndecided,
+        mut
+    )]
+    bet_state: AccountLoader<'info, BetState>,
+
+    #[account(
+        has_one = bet_state, 
+        has_one = bettor,
+        constraint = wager_detail.bettor == bettor.key() @ ErrorCode::NotAccountOwnerToClaimWinnings,
+        mut,
+        close = bettor
+    )]
+    wager_detail: Account<'info, WagerDetail>,
+
+    #[account(
+        mut,
+        constraint = user_account.account_owner == bettor.key() @ ErrorCode::NotAccountOwnerToClaimWinnings,
+        constraint = !user_account.active_wagers.is_empty() @ ErrorCode::ActiveWagersEmpty,
+    )]
+    user_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"position", bet_state.key().as_ref(), user_account.key().as_ref()],
+        bump = bettor_position.bump,
+    )]
+    bettor_position: Account<'info, BettorPosition>,
+
+    /// CHECK: Used to pay for the bet state account
+    #[account(signer, mut)]
+    bettor: AccountInfo<'info>,
+}
+
+// Context for bet creator to cancel a bet when it's empty
+#[derive(Accounts)]
+pub struct CancelBetState<'info>{
+    #[account(
+        constraint = bet_state.load()?.status == BetStateStatus::Open as u8 @ ErrorCode::BetIsClosedOrSettled,
+        constraint = bet_state.load()?.winning_bet_range == BetRange::NotAvailable as u8 @ ErrorCode::BetAlreadyDecided,
+        constraint = bet_state.load()?.creator == bettor.key() @ ErrorCode::NotBetCreator,
+        constraint = bet_state.load()?.running_total_pool == 0 @ ErrorCode::FundsStillInPlay,
+        // A zero-value wager leaves the pool empty but would be orphaned by closing the bet state under it
+        constraint = bet_state.load()?.open_wagers == 0 @ ErrorCode::FundsStillInPlay,
+        mut,
+        close = bettor
+    )]
+    bet_state: AccountLoader<'info, BetState>,
+
+    // Nothing was staked, so the bond goes back to the creator
+    #[account(
+        mut,
+        seeds = [b"creator_bond", bet_state.key().as_ref()],
+        bump = creator_bond.bump,
+        has_one = bet_state,
+        close = bettor
+    )]
+    creator_bond: Account<'info, CreatorBond>,
+
+    // The bet's creator
+    #[account(mut)]
+    bettor: Signer<'info>
+
+}
+
+// Context for the resolver to close a bet to new wagers before the actual event takes place
+#[derive(Accounts)]
+pub struct CloseBetState<'info> {
+    #[account(
+        constraint = bet_state.load()?.status == BetStateStatus::Open as u8 @ ErrorCode::BetIsClosedOrSettled,
+        constraint = bet_state.load()?.winning_bet_range == BetRange::NotAvailable as u8 @ ErrorCode::BetAlreadyDecided,
+        mut
+    )]
+    bet_state: AccountLoader<'info, BetState>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    config: Account<'info, GlobalConfig>,
+
+    #[account(constraint = resolver.key() == config.resolver @ ErrorCode::NotResolver)]
+    resolver: Signer<'info>
+}
+
+// Context for the resolver to decide a closed bet state by hand
+#[derive(Accounts)]
+pub struct DecideBetState<'info> {
+    #[account(
+        constraint = bet_state.load()?.status == BetStateStatus::Closed as u8 @ ErrorCode::BetStillOpen,
+        constraint = bet_state.load()?.winning_bet_range == BetRange::NotAvailable as u8 @ ErrorCode::BetAlreadyDecided,
+        mut
+    )]
+    bet_state: AccountLoader<'info, BetState>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    config: Account<'info, GlobalConfig>,
+
+    #[account(constraint = resolver.key() == config.resolver @ ErrorCode::NotResolver)]
+    resolver: Signer<'info>
+}
+
+// Context for the admin to create a resolver committee
+#[derive(Accounts)]
+pub struct InitializeResolverCommittee<'info> {
+    #[account(init, payer = admin, space = ResolverCommittee::MAX_SIZE + 8)]
+    resolver_committee: Account<'info, ResolverCommittee>,
+
+    #[account(seeds = [b"config"], bump = config.bump, has_one = admin @ ErrorCode::NotConfigAdmin)]
+    config: Account<'info, GlobalConfig>,
+
+    #[account(mut)]
+    admin: Signer<'info>,
+
+    /// CHECK: Not read from or written to
+    #[account(address = system_program::ID)]
+    pub system_program: AccountInfo<'info>,
+}
+
+// Context for a committee member to vote on a bet state's outcome
+#[derive(Accounts)]
+pub struct VoteOnOutcome<'info> {
+    #[account(
+        constraint = bet_state.load()?.status == BetStateStatus::Open as u8 || bet_state.load()?.status == BetStateStatus::Closed as u8 @ ErrorCode::BetIsClosedOrSettled,
+        constraint = bet_state.load()?.winning_bet_range == BetRange::NotAvailable as u8 @ ErrorCode::BetAlreadyDecided,
+        mut
+    )]
+    bet_state: AccountLoader<'info, BetState>,
+
+    #[account(constraint = resolver_committee.key() == bet_state.load()?.resolver_committee @ ErrorCode::InvalidResolverCommittee)]
+    resolver_committee: Account<'info, ResolverCommittee>,
+
+    #[account(
+        init_if_needed,
+        payer = member,
+        space = PendingResolution::MAX_SIZE + 8,
+        seeds = [b"resolution", bet_state.key().as_ref()],
+        bump
+    )]
+    pending_resolution: Account<'info, PendingResolution>,
+
+    #[account(mut)]
+    member: Signer<'info>,
+
+    /// CHECK: Not read from or written to
+    #[account(address = system_program::ID)]
+    pub system_program: AccountInfo<'info>,
+}
+
+// Context to resolve a bet state's outcome from its oracle feed
+#[derive(Accounts)]
+pub struct ResolveBetState<'info> {
+    #[account(
+        constraint = bet_state.load()?.status == BetStateStatus::Open as u8 || bet_state.load()?.status == BetStateStatus::Closed as u8 @ ErrorCode::BetIsClosedOrSettled,
+        constraint = bet_state.load()?.winning_bet_range == BetRange::NotAvailable as u8 @ ErrorCode::BetAlreadyDecided,
+        mut
+    )]
+    bet_state: AccountLoader<'info, BetState>,
+
+    /// CHECK: Has to be the feed the bet state was created with, read through the adapter for its oracle kind
+    #[account(constraint = oracle_feed.key() == bet_state.load()?.oracle_feed @ ErrorCode::InvalidOracleFeed)]
+    oracle_feed: AccountInfo<'info>,
+
+    /// CHECK: Registry entry for the bet's symbol, checked in the endpoint since the asset may have been deregistered
+    asset: AccountInfo<'info>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    config: Account<'info, GlobalConfig>,
+
+    /// CHECK: Instructions sysvar, signed feeds read their price message from the preceding ed25519 instruction
+    #[account(address = sysvar::instructions::ID)]
+    instructions: AccountInfo<'info>,
+}
+
+// Context for a bettor to dispute a decided outcome
+#[derive(Accounts)]
+pub struct DisputeOutcome<'info> {
+    #[account(
+        constraint = bet_state.load()?.status == BetStateStatus::Closed as u8 @ ErrorCode::BetStillOpen,
+        constraint = bet_state.load()?.winning_bet_range != BetRange::NotAvailable as u8 @ ErrorCode::BetStillUndecided,
+        mut
+    )]
+    bet_state: AccountLoader<'info, BetState>,
+
+    #[account(
+        init,
+        payer = disputer,
+        space = OutcomeDispute::MAX_SIZE + 8,
+        seeds = [b"dispute", bet_state.key().as_ref()],
+        bump
+    )]
+    dispute: Account<'info, OutcomeDispute>,
+
+    // Only bettors on this bet state can dispute it
+    #[account(
+        has_one = bet_state,
+        constraint = wager_detail.bettor == disputer.key() @ ErrorCode::NotBettorOnBetState,
+    )]
+    wager_detail: Account<'info, WagerDetail>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    config: Account<'info, GlobalConfig>,
+
+    #[account(mut)]
+    disputer: Signer<'info>,
+
+    /// CHECK: Not read from or written to
+    #[account(address = system_program::ID)]
+    pub system_program: AccountInfo<'info>,
+}
+
+// Context for the arbitration authority to rule on a dispute
+#[derive(Accounts)]
+pub struct RuleOnDispute<'info> {
+    #[account(
+        constraint = bet_state.load()?.dispute_status == DisputeStatus::Pending as u8 @ ErrorCode::NoPendingDispute,
+        mut
+    )]
+    bet_state: AccountLoader<'info, BetState>,
+
+    #[account(
+        mut,
+        seeds = [b"dispute", bet_state.key().as_ref()],
+        bump = dispute.bump,
+        has_one = bet_state,
+        has_one = disputer,
+        close = disputer
+    )]
+    dispute: Account<'info, OutcomeDispute>,
+
+    /// CHECK: Receives the dispute account's lamports, checked against the dispute
+    #[account(mut)]
+    disputer: AccountInfo<'info>,
+
+    // Slashed when the outcome is overturned
+    #[account(
+        mut,
+        seeds = [b"creator_bond", bet_state.key().as_ref()],
+        bump = creator_bond.bump,
+        has_one = bet_state
+    )]
+    creator_bond: Account<'info, CreatorBond>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    config: Account<'info, GlobalConfig>,
+
+    #[account(
+        mut,
+        constraint = arbitration_authority.key() == config.arbitration_authority @ ErrorCode::NotArbitrationAuthority
+    )]
+    arbitration_authority: Signer<'info>,
+}
+
+// Context for the creator to claim the fees accrued on their bet state
+#[derive(Accounts)]
+pub struct ClaimCreatorFees<'info> {
+    #[account(
+        constraint = bet_state.load()?.status == BetStateStatus::Closed as u8 || bet_state.load()?.status == BetStateStatus::Voided as u8 @ ErrorCode::BetStillOpen,
+        constraint = bet_state.load()?.winning_bet_range != BetRange::NotAvailable as u8 || bet_state.load()?.status == BetStateStatus::Voided as u8 @ ErrorCode::BetStillUndecided,
+        constraint = bet_state.load()?.creator == bet_creator.key() @ ErrorCode::NotBetCreator,
+        constraint = bet_state.load()?.open_wagers == 0 @ ErrorCode::FundsStillInPlay,
+        mut
+    )]
+    bet_state: AccountLoader<'info, BetState>,
+
+    #[account(mut)]
+    bet_creator: Signer<'info>
+}
+
+// Context to void a bet state the creator is at fault for
+#[derive(Accounts)]
+pub struct VoidForCreatorFault<'info> {
+    #[account(
+        constraint = bet_state.load()?.status != BetStateStatus::Voided as u8 @ ErrorCode::BetIsClosedOrSettled,
+        constraint = bet_state.load()?.creator_at_fault == 0 @ ErrorCode::BetIsClosedOrSettled,
+        constraint = bet_state.load()?.running_total_pool == bet_state.load()?.static_total_pool @ ErrorCode::PayoutsAlreadyStarted,
+        mut
+    )]
+    bet_state: AccountLoader<'info, BetState>,
+
+    #[account(
+        mut,
+        seeds = [b"creator_bond", bet_state.key().as_ref()],
+        bump = creator_bond.bump,
+        has_one = bet_state
+    )]
+    creator_bond: Account<'info, CreatorBond>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    config: Account<'info, GlobalConfig>,
+
+    #[account(
+        constraint = arbitration_authority.key() == config.arbitration_authority @ ErrorCode::NotArbitrationAuthority
+    )]
+    arbitration_authority: Signer<'info>,
+}
+
+// Context to refund a wager on a voided bet state
+#[derive(Accounts)]
+pub struct RefundVoidedWager<'info> {
+    #[account(
+        constraint = bet_state.load()?.status == BetStateStatus::Voided as u8 @ ErrorCode::BetNotVoided,
+        mut
+    )]
+    bet_state: AccountLoader<'info, BetState>,
+
+    #[account(
+        has_one = bet_state,
+        has_one = bettor,
+        mut,
+        close = bettor
+    )]
+    wager_detail: Account<'info, WagerDetail>,
+
+    #[account(
+        mut,
+        constraint = user_account.account_owner == bettor.key() @ ErrorCode::InvalidAccountOwner,
+        constraint = !user_account.active_wagers.is_empty() @ ErrorCode::ActiveWagersEmpty,
+    )]
+    user_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"position", bet_state.key().as_ref(), user_account.key().as_ref()],
+        bump = bettor_position.bump,
+    )]
+    bettor_position: Account<'info, BettorPosition>,
+
+    /// CHECK: Receives the rent of the closed wager detail
+    #[account(signer, mut)]
+    bettor: AccountInfo<'info>,
+}
+
+// Read-only context for the status view
+#[derive(Accounts)]
+pub struct ViewBetState<'info> {
+    bet_state: AccountLoader<'info, BetState>,
+}
+
+// Context to quote a wager for a user
+#[derive(Accounts)]
+pub struct QuoteWager<'info> {
+    bet_state: AccountLoader<'info, BetState>,
+
+    user_account: Account<'info, UserAccount>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    config: Account<'info, GlobalConfig>,
+}
+
+// General context for a closed and settled bet state
+#[derive(Accounts)]
+pub struct SettleBetState<'info> {
+    #[account(
+        constraint = bet_state.load()?.status == BetStateStatus::Closed as u8 || bet_state.load()?.status == BetStateStatus::Voided as u8 @ ErrorCode::BetStillOpen,
+        constraint = bet_state.load()?.winning_bet_range != BetRange::NotAvailable as u8 || bet_state.load()?.status == BetStateStatus::Voided as u8 @ ErrorCode::BetStillUndecided,
+        constraint = bet_state.load()?.creator == bet_creator.key() @ ErrorCode::NotBetCreator,
+        mut,
+        close = bet_creator
+    )]
+    bet_state: AccountLoader<'info, BetState>,
+
+    // Returns whatever is left of the bond, nothing if it was slashed
+    #[account(
+        mut,
+        seeds = [b"creator_bond", bet_state.key().as_ref()],
+        bump = creator_bond.bump,
+        has_one = bet_state,
+        close = bet_creator
+    )]
+    creator_bond: Account<'info, CreatorBond>,
+
+    #[account(mut)]
+    bet_creator: Signer<'info>
+}
+
+
+// ** Betting Related Types ** //
+// Zero-copy so instructions read and write fields in place instead of deserializing and reserializing the whole
+// account. Enums are stored as their variant index and read back through the accessors below. Byte arrays and pubkeys
+// only need 1 byte alignment, so the single-byte fields up front and the pubkeys after them add up to a multiple of 8
+// before the first u64, the narrower integers follow the u64s, and _padding rounds the end up to 8 bytes. That way no
+// field needs implicit padding, which the zero-copy derive wouldn't allow, and the size is the sum of the field sizes.
+#[account(zero_copy)]
+pub struct BetState {
+    pub version: u8, // 1
+    pub status: u8, // 1, BetStateStatus
+    pub winning_bet_range: u8, // 1, BetRange
+    pub split_bet_range: u8, // 1, BetRange sharing the pool with winning_bet_range on a split outcome
+    pub boundary_rule: u8, // 1, BoundaryRule
+    pub straddle_policy: u8, // 1, StraddlePolicy
+    pub dispute_status: u8, // 1, DisputeStatus
+    pub creator_at_fault: u8, // 1, bool
+    pub symbol: [u8; 16], // 16, utf-8 padded with zeroes
+    pub benchmark_symbol: [u8; 16], // 16, the asset a relative-performance market is measured against
+
+    pub creator: Pubkey, //32
+    pub resolver_committee: Pubkey, // 32, default when the bet is decided by the config's resolver or its oracle
+    pub oracle_feed: Pubkey, // 32
+    pub benchmark_oracle_feed: Pubkey, // 32
+    pub series: Pubkey, // 32, default unless the bet is a round of a market series
+    pub secondary_oracle_feeds: [Pubkey; 4], // 32 * 4
+    pub resolution_sources: [Pubkey; 5], // 32 * 5
+
+    pub running_total_pool: u64, // 8
+    pub static_total_pool: u64, // 8
+    pub pools: [u64; 8], // 8 * 8, indexed by bucket
+    pub unmigrated_stake: u64, // 8, stake on version 0 wagers that haven't been migrated yet
+
+    pub start_time: u64, // 8
+    pub end_time: u64, // 8
+    pub betting_cutoff: u64, // 8
+    pub dispute_window_secs: u64, // 8
+    pub dispute_deadline: u64, // 8, set once the outcome is decided
+
+    pub creator_bond: u64, // 8
+    pub slashed_bond: u64, // 8, bond paid out to bettors on top of their payouts and refunds
+    pub protocol_fees: u64, // 8, sent to the treasury
+    pub creator_fees: u64, // 8, held until the creator claims them
+
+    pub min_wager: u64, // 8
+    pub max_wager: u64, // 8
+    pub max_user_stake: u64, // 8
+    pub max_total_pool: u64, // 8
+    pub max_staleness_secs: u64, // 8
+    pub retry_window_secs: u64, // 8
+
+    pub snapshot_mantissa: i64, // 8
+    pub snapshot_conf: u64, // 8
+    pub snapshot_publish_time: i64, // 8
+    pub closing_mantissa: i64, // 8
+    pub closing_conf: u64, // 8
+    pub closing_publish_time: i64, // 8
+    pub benchmark_snapshot_mantissa: i64, // 8
+    pub benchmark_snapshot_conf: u64, // 8
+    pub benchmark_snapshot_publish_time: i64, // 8
+    pub benchmark_closing_mantissa: i64, // 8
+    pub benchmark_closing_conf: u64, // 8
+    pub benchmark_closing_publish_time: i64, // 8
+    pub strikes: [i64; 7], // 8 * 7, ascending mantissas at the snapshot's exponent on over/under markets
+    pub snapshot_expo: i32, // 4
+    pub closing_expo: i32, // 4
+    pub benchmark_snapshot_expo: i32, // 4
+    pub benchmark_closing_expo: i32, // 4
+
+    pub open_wagers: u32, // 4
+    pub protocol_fee_bps: u16, // 2
+    pub creator_fee_bps: u16, // 2
+    pub max_bucket_share_bps: u16, // 2
+    pub max_confidence_bps: u16, // 2
+
+    pub secondary_oracle_kinds: [u8; 4], // 4, OracleKind of each secondary feed
+    pub secondary_oracle_count: u8, // 1
+    pub resolution_source_count: u8, // 1
+    pub oracle_kind: u8, // 1, OracleKind
+    pub oracle_quorum: u8, // 1
+    pub bucket_width_bps: u16, // 2, 0 on bet states created before bucket layouts
+    pub dead_zone_bps: u16, // 2, the band around the snapshot that refunds every wager on up/down markets
+    pub market_type: u8, // 1, MarketType
+    pub strike_count: u8, // 1
+    pub benchmark_oracle_kind: u8, // 1, OracleKind
+    pub _padding: [u8; 5], // 5, rounds the size up to the 8 byte alignment
+}
+
+// The account is allocated from MAX_SIZE, so it has to match the layout exactly
+const _: () = assert!(std::mem::size_of::<BetState>() == BetState::MAX_SIZE);
+
+impl BetState {
+    const MAX_SIZE: usize = 896;
+    const MAX_SECONDARY_ORACLES: usize = 4;
+    const MAX_RESOLUTION_SOURCES: usize = 5;
+
+    // Starts a freshly created bet state with empty pools and no outcome, measured from `snapshot` and charging the
+    // config's current protocol fee for its whole life. The caller sets the bet's own terms on top.
+    fn open(&mut self, symbol: [u8; MAX_SYMBOL_LEN], creator: Pubkey, start: u64, end_time: u64, snapshot: &Price, config: &GlobalConfig) {
+        self.version = ACCOUNT_VERSION;
+        self.symbol = symbol;
+        self.creator = creator;
+        self.running_total_pool = 0;
+        self.static_total_pool = 0;
+        self.pools = [0; 8];
+
+        self.status = BetStateStatus::Open as u8;
+        self.start_time = start;
+        self.end_time = end_time;
+
+        self.set_snapshot_price(snapshot);
+        self.winning_bet_range = BetRange::NotAvailable as u8;
+        self.split_bet_range = BetRange::NotAvailable as u8;
+        self.dispute_window_secs = config.dispute_window_secs;
+        self.dispute_deadline = 0;
+        self.dispute_status = DisputeStatus::None as u8;
+
+        self.protocol_fee_bps = config.protocol_fee_bps;
+        self.protocol_fees = 0;
+        self.creator_fees = 0;
+        self.creator_at_fault = 0;
+        self.slashed_bond = 0;
+        self.open_wagers = 0;
+        self.unmigrated_stake = 0;
+        self.set_closing_price(&Price::default());
+        self.set_resolution_sources(&[]);
+    }
+
+    fn status(&self) -> Result<BetStateStatus> {
+        BetStateStatus::from_u8(self.status)
+    }
+
+    fn winning_bet_range(&self) -> Result<BetRange> {
+        BetRange::from_u8(self.winning_bet_range)
+    }
+
+    fn split_bet_range(&self) -> Result<BetRange> {
+        BetRange::from_u8(self.split_bet_range)
+    }
+
+    fn dispute_status(&self) -> Result<DisputeStatus> {
+        DisputeStatus::from_u8(self.dispute_status)
+    }
+
+    fn oracle_kind(&self) -> Result<OracleKind> {
+        OracleKind::from_u8(self.oracle_kind)
+    }
+
+    fn set_symbol(&mut self, symbol: &str) -> Result<()> {
+        if symbol.len() > MAX_SYMBOL_LEN {
+            return Err(error!(ErrorCode::SymbolTooLong));
+        }
+        self.symbol = [0; 16];
+        self.symbol[..symbol.len()].copy_from_slice(symbol.as_bytes());
+        Ok(())
+    }
+
+    fn snapshot_price(&self) -> Price {
+        Price {
+            mantissa: self.snapshot_mantissa,
+            expo: self.snapshot_expo,
+            conf: self.snapshot_conf,
+            publish_time: self.snapshot_publish_time,
+        }
+    }
+
+    fn set_snapshot_price(&mut self, price: &Price) {
+        self.snapshot_mantissa = price.mantissa;
+        self.snapshot_expo = price.expo;
+        self.snapshot_conf = price.conf;
+        self.snapshot_publish_time = price.publish_time;
+    }
+
+    fn benchmark_oracle_kind(&self) -> Result<OracleKind> {
+        OracleKind::from_u8(self.benchmark_oracle_kind)
+    }
+
+    fn benchmark_snapshot_price(&self) -> Price {
+        Price {
+            mantissa: self.benchmark_snapshot_mantissa,
+            expo: self.benchmark_snapshot_expo,
+            conf: self.benchmark_snapshot_conf,
+            publish_time: self.benchmark_snapshot_publish_time,
+        }
+    }
+
+    fn set_benchmark_snapshot_price(&mut self, price: &Price) {
+        self.benchmark_snapshot_mantissa = price.mantissa;
+        self.benchmark_snapshot_expo = price.expo;
+        self.benchmark_snapshot_conf = price.conf;
+        self.benchmark_snapshot_publish_time = price.publish_time;
+    }
+
+    fn set_benchmark_closing_price(&mut self, price: &Price) {
+        self.benchmark_closing_mantissa = price.mantissa;
+        self.benchmark_closing_expo = price.expo;
+        self.benchmark_closing_conf = price.conf;
+        self.benchmark_closing_publish_time = price.publish_time;
+    }
+
+    fn set_closing_price(&mut self, price: &Price) {
+        self.closing_mantissa = price.mantissa;
+        self.closing_expo = price.expo;
+        self.closing_conf = price.conf;
+        self.closing_publish_time = price.publish_time;
+    }
+
+    fn limits(&self) -> WagerLimits {
+        WagerLimits {
+            min_wager: self.min_wager,
+            max_wager: self.max_wager,
+            max_user_stake: self.max_user_stake,
+            max_total_pool: self.max_total_pool,
+            max_bucket_share_bps: self.max_bucket_share_bps,
+        }
+    }
+
+    fn set_limits(&mut self, limits: &WagerLimits) {
+        self.min_wager = limits.min_wager;
+        self.max_wager = limits.max_wager;
+        self.max_user_stake = limits.max_user_stake;
+        self.max_total_pool = limits.max_total_pool;
+        self.max_bucket_share_bps = limits.max_bucket_share_bps;
+    }
+
+    fn oracle_guards(&self) -> OracleGuards {
+        OracleGuards {
+            max_staleness_secs: self.max_staleness_secs,
+            max_confidence_bps: self.max_confidence_bps,
+            retry_window_secs: self.retry_window_secs,
+        }
+    }
+
+    fn set_oracle_guards(&mut self, oracle_guards: &OracleGuards) {
+        self.max_staleness_secs = oracle_guards.max_staleness_secs;
+        self.max_confidence_bps = oracle_guards.max_confidence_bps;
+        self.retry_window_secs = oracle_guards.retry_window_secs;
+    }
+
+    fn secondary_oracles(&self) -> Result<Vec<OracleSource>> {
+        let (feeds, kinds) = (self.secondary_oracle_feeds, self.secondary_oracle_kinds);
+        (0..self.secondary_oracle_count as usize)
+            .map(|index| Ok(OracleSource { kind: OracleKind::from_u8(kinds[index])?, feed: feeds[index] }))
+            .collect()
+    }
+
+    fn set_secondary_oracles(&mut self, secondary_oracles: &[OracleSource]) {
+        self.secondary_oracle_feeds = [Pubkey::default(); 4];
+        self.secondary_oracle_kinds = [0; 4];
+        for (index, source) in secondary_oracles.iter().enumerate() {
+            self.secondary_oracle_feeds[index] = source.feed;
+            self.secondary_oracle_kinds[index] = source.kind as u8;
+        }
+        self.secondary_oracle_count = secondary_oracles.len() as u8;
+    }
+
+    fn set_resolution_sources(&mut self, sources: &[Pubkey]) {
+        self.resolution_sources = [Pubkey::default(); 5];
+        let count = sources.len().min(BetState::MAX_RESOLUTION_SOURCES);
+        self.resolution_sources[..count].copy_from_slice(&sources[..count]);
+        self.resolution_source_count = count as u8;
+    }
+
+    // No wagers can be placed or cancelled from this time onwards
+    fn betting_cutoff_time(&self) -> u64 {
+        self.end_time.saturating_sub(self.betting_cutoff)
+    }
+
+    fn is_accepting_wagers(&self, now: u64) -> bool {
+        self.status == BetStateStatus::Open as u8 && now < self.betting_cutoff_time()
+    }
+
+    // Starts the dispute window, called whenever the outcome gets decided
+    fn open_dispute_window(&mut self, now: u64) {
+        self.dispute_deadline = now.saturating_add(self.dispute_window_secs.saturating_mul(1000));
+    }
+
+    // Claims open once the dispute window passes undisputed or a dispute has been ruled on
+    fn claims_unlocked(&self, now: u64) -> Result<bool> {
+        Ok(match self.dispute_status()? {
+            DisputeStatus::None => now >= self.dispute_deadline,
+            DisputeStatus::Pending => false,
+            DisputeStatus::Upheld | DisputeStatus::Overturned => true,
+        })
+    }
+
+    // A winning wager is paid its share of the winning pool applied to the whole pool, rounded down. On a split
+    // outcome both ranges count as one winning pool, so every winner is paid pro-rata to their stake.
+    fn payout_for(&self, wager_detail: &WagerDetail) -> Result<u64> {
+        let winning_bet_range = self.winning_bet_range()?;
+        let split_bet_range = self.split_bet_range()?;
+
+        let is_winner = wager_detail.range_status == winning_bet_range
+            || (split_bet_range != BetRange::NotAvailable && wager_detail.range_status == split_bet_range);
+        if !is_winner {
+            return Err(error!(ErrorCode::NonWinningParty));
+        }
+
+        let winning_pool = self.winning_pool()?;
+
+        let payout = (wager_detail.bet_value as u128)
+            .checked_mul(self.static_total_pool as u128)
+            .and_then(|total| total.checked_div(winning_pool as u128))
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        Ok(payout as u64)
+    }
+
+    // Stake on the winning range, plus the split range on a split outcome. 0 when nobody won.
+    fn winning_pool(&self) -> Result<u64> {
+        [self.winning_bet_range()?, self.split_bet_range()?]
+            .iter()
+            .filter_map(|range| range.bucket_index())
+            .try_fold(0u64, |total, bucket_index| total.checked_add(self.pool_for_bucket(bucket_index)))
+            .ok_or_else(|| error!(ErrorCode::MathOverflow))
+    }
+
+    // Splits the lamports a bettor puts up into the wager and the protocol and creator fees charged on top of it.
+    // The wager rounds down and the protocol fee takes its exact share of the rest, so nothing is lost to rounding.
+    fn split_wager(&self, lamports: u64, protocol_fee_bps: u16) -> Result<(u64, u64, u64)> {
+        let total_fee_bps = protocol_fee_bps as u128 + self.creator_fee_bps as u128;
+
+        let wager = (lamports as u128)
+            .checked_mul(BASIS_POINTS as u128)
+            .and_then(|scaled| scaled.checked_div(BASIS_POINTS as u128 + total_fee_bps))
+            .ok_or(ErrorCode::MathOverflow)? as u64;
+
+        let fees = lamports - wager;
+        let protocol_fee = match total_fee_bps {
+            0 => 0,
+            _ => (fees as u128 * protocol_fee_bps as u128 / total_fee_bps) as u64,
+        };
+
+        Ok((wager, protocol_fee, fees - protocol_fee))
+    }
+
+    // A wager's cut of a slashed creator bond, pro-rata to its stake and rounded down
+    fn slashed_bond_share(&self, bet_value: u64) -> Result<u64> {
+        if self.slashed_bond == 0 {
+            return Ok(0);
+        }
+
+        let share = (bet_value as u128)
+            .checked_mul(self.slashed_bond as u128)
+            .and_then(|total| total.checked_div(self.static_total_pool as u128))
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        Ok(share as u64)
+    }
+
+    fn market_type(&self) -> Result<MarketType> {
+        match self.market_type {
+            0 => Ok(MarketType::Range),
+            1 => Ok(MarketType::UpDown { dead_zone_bps: self.dead_zone_bps }),
+            2 => {
+                let strikes = self.strikes;
+                let strike_count = (self.strike_count as usize).min(MarketType::MAX_STRIKES);
+                Ok(MarketType::OverUnder { strikes: strikes[..strike_count].to_vec(), expo: self.snapshot_expo })
+            }
+            3 => {
+                let symbol = self.benchmark_symbol;
+                let symbol_len = symbol.iter().position(|byte| *byte == 0).unwrap_or(MAX_SYMBOL_LEN);
+                Ok(MarketType::RelativePerformance {
+                    benchmark_symbol: String::from_utf8(symbol[..symbol_len].to_vec()).map_err(|_| error!(ErrorCode::InvalidBetStateData))?,
+                    bucket_layout: BucketLayout { bucket_width_bps: self.bucket_width_bps },
+                })
+            }
+            _ => Err(error!(ErrorCode::InvalidBetStateData)),
+        }
+    }
+
+    // Strikes are stored at the snapshot's exponent, which has to be able to hold them exactly, so it has to be set first
+    fn set_market_type(&mut self, market_type: MarketType, bucket_layout: &BucketLayout) -> Result<()> {
+        match market_type {
+            MarketType::Range => {
+                self.market_type = 0;
+                self.bucket_width_bps = bucket_layout.bucket_width_bps;
+            }
+            MarketType::UpDown { dead_zone_bps } => {
+                self.market_type = 1;
+                self.dead_zone_bps = dead_zone_bps;
+            }
+            MarketType::OverUnder { strikes, expo } => {
+                let mut stored_strikes = [0i64; MarketType::MAX_STRIKES];
+                for (stored_strike, strike) in stored_strikes.iter_mut().zip(strikes.iter()) {
+                    *stored_strike = Price { mantissa: *strike, expo, ..Price::default() }
+                        .exact_mantissa_at(self.snapshot_expo)
+                        .map_err(|_| error!(ErrorCode::InvalidMarketType))?;
+                }
+                self.market_type = 2;
+                self.strike_count = strikes.len() as u8;
+                self.strikes = stored_strikes;
+            }
+            // The benchmark's feed and snapshot are set along with the bet's own
+            MarketType::RelativePerformance { benchmark_symbol, bucket_layout } => {
+                self.market_type = 3;
+                self.benchmark_symbol = pack_symbol(&benchmark_symbol)?;
+                self.bucket_width_bps = bucket_layout.bucket_width_bps;
+            }
+        }
+        Ok(())
+    }
+
+    // Range for an outcome passed to an endpoint, as long as it's one this bet state's market type has
+    fn outcome_range(&self, bucket_index: u8) -> Result<BetRange> {
+        if bucket_index >= self.market_type()?.outcome_count() {
+            return Err(error!(ErrorCode::InvalidBetOutcome));
+        }
+        BetRange::from_bucket_index(bucket_index)
+    }
+
+    fn bucket_width_bps(&self) -> u16 {
+        match self.bucket_width_bps {
+            0 => DEFAULT_BUCKET_WIDTH_BPS,
+            bucket_width_bps => bucket_width_bps,
+        }
+    }
+
+    fn pool_for_bucket(&self, bucket_index: usize) -> u64 {
+        let pools = self.pools;
+        pools.get(bucket_index).copied().unwrap_or_default()
+    }
+}
+
+// Thresholds an oracle price has to meet before it can decide a bet state's outcome
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Copy, Debug, Default)]
+pub struct OracleGuards {
+    pub max_staleness_secs: u64, // 8
+    pub max_confidence_bps: u16, // 2, 0 disables the check
+    pub retry_window_secs: u64, // 8, how long after the end time resolution is retried before the bet is voided
+}
+
+impl OracleGuards {
+    fn validate(&self) -> Result<()> {
+        // Without a retry window a price that is briefly unusable right at the end time would void the bet
+        if self.max_staleness_secs == 0 || self.retry_window_secs == 0 || self.max_confidence_bps as u64 > BASIS_POINTS {
+            return Err(error!(ErrorCode::InvalidOracleGuards));
+        }
+        Ok(())
+    }
+}
+
+// Escrow for the bond a creator posts with their bet state
+#[account]
+pub struct CreatorBond {
+    pub version: u8, // 1
+    pub bet_state: Pubkey, // 32
+    pub creator: Pubkey, // 32
+    pub amount: u64, // 8, 0 once slashed
+    pub bump: u8, // 1
+}
+
+impl CreatorBond {
+    const MAX_SIZE: usize = 74;
+}
+
+// Members allowed to vote on the outcome of bet states naming this committee
+#[account]
+pub struct ResolverCommittee {
+    pub version: u8, // 1
+    pub members: Vec<Pubkey>, // 4 + (32 * 10)
+    pub threshold: u8, // 1
+}
+
+impl ResolverCommittee {
+    const MAX_SIZE: usize = 326;
+    const MAX_MEMBERS: usize = 10;
+}
+
+// Votes cast so far on a bet state's outcome by its resolver committee
+#[account]
+pub struct PendingResolution {
+    pub version: u8, // 1
+    pub bet_state: Pubkey, // 32
+    pub resolver_committee: Pubkey, // 32
+    pub votes: Vec<ResolverVote>, // 4 + (33 * 10)
+    pub bump: u8, // 1
+}
+
+impl PendingResolution {
+    const MAX_SIZE: usize = 400;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ResolverVote {
+    pub member: Pubkey, // 32
+    pub bet_range: BetRange, // 1
+}
+
+// A bettor's challenge of a decided outcome, holds their bond until the arbitration authority rules
+#[account]
+pub struct OutcomeDispute {
+    pub version: u8, // 1
+    pub bet_state: Pubkey, // 32
+    pub disputer: Pubkey, // 32
+    pub bond: u64, // 8
+    pub disputed_bet_range: BetRange, // 1, the range the disputer says should have won
+    pub raised_at: u64, // 8
+    pub bump: u8, // 1
+}
+
+impl OutcomeDispute {
+    const MAX_SIZE: usize = 83;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DisputeStatus {
+    None,
+    Pending,
+    Upheld,
+    Overturned,
+}
+
+impl DisputeStatus {
+    fn from_u8(value: u8) -> Result<DisputeStatus> {
+        match value {
+            0 => Ok(DisputeStatus::None),
+            1 => Ok(DisputeStatus::Pending),
+            2 => Ok(DisputeStatus::Upheld),
+            3 => Ok(DisputeStatus::Overturned),
+            _ => Err(error!(ErrorCode::InvalidBetStateData)),
+        }
+    }
+}
+
+#[event]
+pub struct BetStateVoided {
+    pub bet_state: Pubkey,
+    pub voided_at: u64,
+}
+
+#[event]
+pub struct BetStateStatusView {
+    pub bet_state: Pubkey,
+    pub status: BetStateStatus,
+    pub winning_bet_range: BetRange,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub betting_cutoff_time: u64,
+    pub accepting_wagers: bool,
+    pub dispute_status: DisputeStatus,
+    pub dispute_deadline: u64,
+    pub claims_unlocked: bool,
+    pub current_time: u64,
+}
+
+#[event]
+pub struct WagerQuote {
+    pub bet_state: Pubkey,
+    pub user_account: Pubkey,
+    pub lamports: u64,
+    pub wager: u64,
+    pub protocol_fee: u64,
+    pub creator_fee: u64,
+    pub protocol_fee_bps: u16,
+    pub creator_fee_bps: u16,
+    pub season_volume: u64,
+}
+
+#[account]
+pub struct WagerDetail {
+    pub version: u8, // 1
+    pub bettor: Pubkey, // 32
+    pub bet_state: Pubkey, // 32
+    pub range_status: BetRange, // 1
+    pub bet_value: u64, // 8
+}
+
+impl WagerDetail {
+    const MAX_SIZE: usize = 1 + 32 + 32 + 1 + 8;
+}
+
+// Running total of a single user's stake on a bet state, used to enforce the per-user and per-bucket limits
+#[account]
+pub struct BettorPosition {
+    pub version: u8, // 1
+    pub bet_state: Pubkey, // 32
+    pub user_account: Pubkey, // 32
+    pub total_stake: u64, // 8
+    pub bucket_stakes: [u64; 8], // 64
+    pub bump: u8, // 1
+}
+
+impl BettorPosition {
+    const MAX_SIZE: usize = 138;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Copy, Debug)]
+pub enum BetStateStatus {
+    Open,
+    Closed,
+    Settled,
+    Voided
+}
+
+impl BetStateStatus {
+    fn from_u8(value: u8) -> Result<BetStateStatus> {
+        match value {
+            0 => Ok(BetStateStatus::Open),
+            1 => Ok(BetStateStatus::Closed),
+            2 => Ok(BetStateStatus::Settled),
+            3 => Ok(BetStateStatus::Voided),
+            _ => Err(error!(ErrorCode::InvalidBetStateData)),
+        }
+    }
+}
+
+// Ranges are named for the 1% bucket layout, with another bucket width each step is one width instead of 1%
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Copy, Debug)]
+pub enum BetRange {
+    NotAvailable,
+    NegThreeAndUnder,
+    NegThreeToNegTwo,
+    NegTwoToNegOne,
+    NegOneToZero,
+    ZeroToPosOne,
+    PosOneToPosTwo,
+    PosTwoToPosThree,
+    PosThreeAndOver
+}
+
+impl BetRange {
+    // Range for a value stored on a bet state, NotAvailable included
+    fn from_u8(value: u8) -> Result<BetRange> {
+        match value {
+            0 => Ok(BetRange::NotAvailable),
+            _ => BetRange::from_bucket_index(value - 1).map_err(|_| error!(ErrorCode::InvalidBetStateData)),
+        }
+    }
+
+    // Maps a percent move in basis points, rounded down, to its range when ranges include their lower boundary
+    fn from_change_bps_lower_inclusive(change_bps: i64, bucket_width_bps: u16) -> BetRange {
+        BetRange::from_bucket_steps(change_bps.div_euclid(bucket_width_bps as i64))
+    }
+
+    // Maps a percent move in basis points, rounded up, to its range when ranges include their upper boundary
+    fn from_change_bps_upper_inclusive(change_bps: i64, bucket_width_bps: u16) -> BetRange {
+        let steps_rounded_up = -change_bps.saturating_neg().div_euclid(bucket_width_bps as i64);
+        BetRange::from_bucket_steps(steps_rounded_up - 1)
+    }
+
+    // Range for a move of `steps` whole bucket widths above the snapshot, negative steps being below it
+    fn from_bucket_steps(steps: i64) -> BetRange {
+        match steps {
+            i64::MIN..=-4 => BetRange::NegThreeAndUnder,
+            -3 => BetRange::NegThreeToNegTwo,
+            -2 => BetRange::NegTwoToNegOne,
+            -1 => BetRange::NegOneToZero,
+            0 => BetRange::ZeroToPosOne,
+            1 => BetRange::PosOneToPosTwo,
+            2 => BetRange::PosTwoToPosThree,
+            _ => BetRange::PosThreeAndOver,
+        }
+    }
+
+    // Range for a bet_range value as passed to place_wager
+    fn from_bucket_index(bucket_index: u8) -> Result<BetRange> {
+        match bucket_index {
+            0 => Ok(BetRange::NegThreeAndUnder),
+            1 => Ok(BetRange::NegThreeToNegTwo),
+            2 => Ok(BetRange::NegTwoToNegOne),
+            3 => Ok(BetRange::NegOneToZero),
+            4 => Ok(BetRange::ZeroToPosOne),
+            5 => Ok(BetRange::PosOneToPosTwo),
+            6 => Ok(BetRange::PosTwoToPosThree),
+            7 => Ok(BetRange::PosThreeAndOver),
+            _ => Err(error!(ErrorCode::InvalidBetOutcome)),
+        }
+    }
+
+    // Index of the range's pool, matching the bet_range value passed to place_wager
+    fn bucket_index(&self) -> Option<usize> {
+        match self {
+            BetRange::NotAvailable => None,
+            BetRange::NegThreeAndUnder => Some(0),
+            BetRange::NegThreeToNegTwo => Some(1),
+            BetRange::NegTwoToNegOne => Some(2),
+            BetRange::NegOneToZero => Some(3),
+            BetRange::ZeroToPosOne => Some(4),
+            BetRange::PosOneToPosTwo => Some(5),
+            BetRange::PosTwoToPosThree => Some(6),
+            BetRange::PosThreeAndOver => Some(7),
+        }
+    }
+}
+
+
+// Which range a move sitting exactly on a boundary (0% or a whole number of bucket widths) belongs to
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BoundaryRule {
+    // Ranges are [lower, upper): exactly 0% is ZeroToPosOne and exactly -3% is NegThreeToNegTwo
+    LowerInclusive,
+    // Ranges are (lower, upper]: exactly 0% is NegOneToZero and exactly -3% is NegThreeAndUnder
+    UpperInclusive,
+}
+
+impl BoundaryRule {
+    fn from_u8(value: u8) -> Result<BoundaryRule> {
+        match value {
+            0 => Ok(BoundaryRule::LowerInclusive),
+            1 => Ok(BoundaryRule::UpperInclusive),
+            _ => Err(error!(ErrorCode::InvalidBetStateData)),
+        }
+    }
+
+    // The percent move is rounded towards the side the boundary belongs to, so the range is exact however
+    // close the price is to a boundary
+    fn bet_range(&self, snapshot_price: &Price, mantissa: i128, expo: i32, bucket_width_bps: u16) -> Result<BetRange> {
+        let change_bps = snapshot_price.change_bps_to(mantissa, expo, self.rounding())?;
+        Ok(self.range_for_change_bps(change_bps, bucket_width_bps))
+    }
+
+    fn rounding(&self) -> Rounding {
+        match self {
+            BoundaryRule::LowerInclusive => Rounding::Down,
+            BoundaryRule::UpperInclusive => Rounding::Up,
+        }
+    }
+
+    // Range for a move in basis points already rounded with this rule's rounding
+    fn range_for_change_bps(&self, change_bps: i64, bucket_width_bps: u16) -> BetRange {
+        match self {
+            BoundaryRule::LowerInclusive => BetRange::from_change_bps_lower_inclusive(change_bps, bucket_width_bps),
+            BoundaryRule::UpperInclusive => BetRange::from_change_bps_upper_inclusive(change_bps, bucket_width_bps),
+        }
+    }
+
+    // Range between the strikes a price lands in, counting the strikes below it, or at it when it's the strike's
+    // range that includes its lower boundary. Both sides are compared at the smaller exponent, so the comparison is
+    // exact.
+    fn strike_range(&self, strikes: &[i64], strike_expo: i32, mantissa: i128, expo: i32) -> Result<BetRange> {
+        let common_expo = expo.min(strike_expo);
+        let closing = mantissa.checked_mul(pow10(expo - common_expo)?).ok_or(ErrorCode::MathOverflow)?;
+        let strike_scale = pow10(strike_expo - common_expo)?;
+
+        let mut strikes_below = 0u8;
+        for &strike in strikes {
+            let strike = (strike as i128).checked_mul(strike_scale).ok_or(ErrorCode::MathOverflow)?;
+            let is_above = match self {
+                BoundaryRule::LowerInclusive => closing >= strike,
+                BoundaryRule::UpperInclusive => closing > strike,
+            };
+            if is_above {
+                strikes_below += 1;
+            }
+        }
+
+        BetRange::from_bucket_index(strikes_below)
+    }
 }
 
-// ***** UTILITY FUNCTIONS ***** //
+// What happens when the closing price's confidence interval covers a boundary
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StraddlePolicy {
+    // Resolution fails and is retried until the retry window runs out, then the bet is voided
+    Reject,
+    // The two ranges either side of the boundary share the pool, as long as only one boundary is covered
+    Split,
+}
 
-fn transfer_winnings(bet_state: &mut Account<BetState>, user_account: &Account<UserAccount>, winnings_amount: u64) -> Result<()> {
-    **bet_state.to_account_info().try_borrow_mut_lamports()? = bet_state.to_account_info().lamports().checked_sub(winnings_amount).ok_or(ProgramError::InvalidArgument)?;
-    **user_account.to_account_info().try_borrow_mut_lamports()? = user_account.to_account_info().lamports().checked_add(winnings_amount).ok_or(ProgramError::InvalidArgument)?;
-    bet_state.running_total_pool -= winnings_amount;
-    Ok(())
+impl StraddlePolicy {
+    fn from_u8(value: u8) -> Result<StraddlePolicy> {
+        match value {
+            0 => Ok(StraddlePolicy::Reject),
+            1 => Ok(StraddlePolicy::Split),
+            _ => Err(error!(ErrorCode::InvalidBetStateData)),
+        }
+    }
 }
 
+// What a bet state's outcomes are. Every type shares the pools, wagers and claims, only the buckets that can be
+// backed and the way the closing price picks one differ.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub enum MarketType {
+    // Eight ranges of percent moves from the snapshot price, laid out by the asset's bucket layout
+    Range,
+    // Two outcomes, a close below the snapshot price on bucket 0 and one above it on bucket 1. A close within
+    // `dead_zone_bps` of the snapshot refunds every wager.
+    UpDown { dead_zone_bps: u16 },
+    // Absolute prices `strikes[i] * 10^expo` in ascending order. Bucket 0 is a close under the first strike, bucket i
+    // one between strikes i - 1 and i, and the last bucket one over the last strike.
+    OverUnder { strikes: Vec<i64>, expo: i32 },
+    // Ranges of the spread between the bet's asset's percent move and a benchmark asset's over the same window, in
+    // basis points and laid out by `bucket_layout`. ZeroToPosOne is the bet's asset outperforming by up to one width.
+    RelativePerformance { benchmark_symbol: String, bucket_layout: BucketLayout },
+}
 
-// ***** BETTING FUNCTIONALITY CONTEXT AND STRUCTS ***** //
-// Adding an account on a context simply means its public key should be provided when sending the instruction
+impl MarketType {
+    const DOWN_BUCKET: u8 = 0;
+    const UP_BUCKET: u8 = 1;
+    // One less than the number of pools
+    const MAX_STRIKES: usize = 7;
+
+    fn validate(&self, straddle_policy: StraddlePolicy) -> Result<()> {
+        match self {
+            MarketType::Range => Ok(()),
+            // A dead zone of 100% would refund every close, and there's no neighbouring bucket to split with
+            MarketType::UpDown { dead_zone_bps } => {
+                if *dead_zone_bps as u64 >= BASIS_POINTS || straddle_policy == StraddlePolicy::Split {
+                    return Err(error!(ErrorCode::InvalidMarketType));
+                }
+                Ok(())
+            }
+            MarketType::OverUnder { strikes, .. } => {
+                if strikes.is_empty()
+                    || strikes.len() > MarketType::MAX_STRIKES
+                    || strikes[0] <= 0
+                    || strikes.windows(2).any(|pair| pair[0] >= pair[1])
+                {
+                    return Err(error!(ErrorCode::InvalidMarketType));
+                }
+                Ok(())
+            }
+            MarketType::RelativePerformance { bucket_layout, .. } => bucket_layout.validate(),
+        }
+    }
 
-// Context to initialize the bet state
-#[derive(Accounts)]
-pub struct InitializeBetState<'info> {
+    // Outcomes are the buckets from 0 up to this
+    fn outcome_count(&self) -> u8 {
+        match self {
+            MarketType::Range | MarketType::RelativePerformance { .. } => 8,
+            MarketType::UpDown { .. } => 2,
+            MarketType::OverUnder { strikes, .. } => strikes.len() as u8 + 1,
+        }
+    }
 
-    #[account(init, payer = bet_creator, space = BetState::MAX_SIZE + 8)]
-    bet_state: Account<'info, BetState>,
+    // Remaining accounts a relative-performance market takes after its secondary feeds: the benchmark's asset entry
+    // and feed at creation, only the feed at resolution
+    fn benchmark_account_count(&self) -> usize {
+        match self {
+            MarketType::RelativePerformance { .. } => 2,
+            _ => 0,
+        }
+    }
 
-    /// CHECK: Used to pay for the bet state account
-    #[account(signer, mut)]
-    bet_creator: AccountInfo<'info>,
+    fn benchmark_feed_count(&self) -> usize {
+        match self {
+            MarketType::RelativePerformance { .. } => 1,
+            _ => 0,
+        }
+    }
+}
 
-    /// CHECK: Not read from or written to
-    #[account(address = system_program::ID)]
-    pub system_program: AccountInfo<'info>,
 
-}
+// ***** MARKET SERIES CONTEXT AND STRUCTS ***** //
 
-// Context to place a wager on a created bet 
+// Context for the admin to start a market series on a registered asset
 #[derive(Accounts)]
-pub struct PlaceWager<'info> {
+#[instruction(symbol: String)]
+pub struct CreateMarketSeries<'info> {
+    #[account(seeds = [b"config"], bump = config.bump, has_one = admin @ ErrorCode::NotConfigAdmin)]
+    config: Account<'info, GlobalConfig>,
 
-    #[account(
-        constraint = bet_state.status == BetStateStatus::Open @ ErrorCode::BetIsClosedOrSettled,
-        constraint = bet_state.winning_bet_range == BetRange::NotAvailable @ ErrorCode::BetAlreadyDecided,
-        mut
-    )]
-    bet_state: Account<'info, BetState>,
-
-    #[account(init, payer = bettor_account, space = WagerDetail::MAX_SIZE + 8)]
-    wager_detail: Account<'info, WagerDetail>,
+    #[account(seeds = [b"asset", symbol.as_bytes()], bump = asset.bump)]
+    asset: Account<'info, AssetEntry>,
 
-    #[account(
-        mut,
-        constraint = user_account.account_owner == bettor_account.key() @ ErrorCode::NotAccountOwnerToPlaceWager,
-    )]
-    user_account: Account<'info, UserAccount>,
+    #[account(init, payer = admin, space = MarketSeries::MAX_SIZE + 8, seeds = [b"series", symbol.as_bytes()], bump)]
+    series: Account<'info, MarketSeries>,
 
-    /// CHECK: Used to pay for the wager detail account
-    #[account(signer, mut)]
-    bettor_account: AccountInfo<'info>,
+    #[account(init, payer = admin, space = SeriesJackpot::MAX_SIZE + 8, seeds = [b"jackpot", series.key().as_ref()], bump)]
+    jackpot: Account<'info, SeriesJackpot>,
 
-    /// CHECK: Used to credit bet manager wallet with take rate
-    // #[account(mut)]
-    // bet_creator: AccountInfo<'info>,
+    #[account(mut)]
+    admin: Signer<'info>,
 
     /// CHECK: Not read from or written to
     #[account(address = system_program::ID)]
-    system_program: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
 }
 
-// Context to cancel a wager on a created bet
+// Context for anyone to close a series' current round and open the next one
 #[derive(Accounts)]
-pub struct CancelWager<'info> {
-
-    #[account(
-        constraint = bet_state.status == BetStateStatus::Open @ ErrorCode::BetIsClosedOrSettled,
-        constraint = bet_state.winning_bet_range == BetRange::NotAvailable @ ErrorCode::BetAlreadyDecided,
-        mut,
-    )]
-    bet_state: Account<'info, BetState>,
-
-    #[account(
-        constraint = wager_detail.bettor == bettor.key(),
-        mut, 
-        close = bettor,
-    )]
-    wager_detail: Account<'info, WagerDetail>,
-
-    #[account(
-        mut,
-        constraint = user_account.account_owner == bettor.key() @ ErrorCode::NotAccountOwnerToPlaceWager,
-        constraint = user_account.active_wagers.len() > 0 @ ErrorCode::ActiveWagersEmpty,
-    )]
-    user_account: Account<'info, UserAccount>,
+pub struct AdvanceSeries<'info> {
+    #[account(mut)]
+    series: Account<'info, MarketSeries>,
 
-    /// CHECK: Used to cancel the wager it owns
-    #[account(signer, mut)]
-    bettor: AccountInfo<'info>
-}
+    #[account(mut, seeds = [b"jackpot", series.key().as_ref()], bump = jackpot.bump)]
+    jackpot: Account<'info, SeriesJackpot>,
 
-// Context to close a wager once the bet state it's attached to has already been decided and the wager is a losing one
-#[derive(Accounts)]
-pub struct CloseLosingWager<'info> {
-    #[account(
-        constraint = bet_state.status == BetStateStatus::Closed @ ErrorCode::BetStillOpen,
-        constraint = bet_state.winning_bet_range != BetRange::NotAvailable @ ErrorCode::BetStillUndecided,
-    )]
-    bet_state: Account<'info, BetState>,
+    /// CHECK: The series' current round, checked against its address in the endpoint since there's none before the first
+    #[account(mut)]
+    current_round: AccountInfo<'info>,
 
     #[account(
-        constraint = wager_detail.bettor == bettor.key(),
-        constraint = wager_detail.range_status != bet_state.winning_bet_range @ ErrorCode::PleaseClaimYourWinnings,
-        mut, 
-        close = bettor,
+        init,
+        payer = keeper,
+        space = BetState::MAX_SIZE + 8,
+        seeds = [b"round", series.key().as_ref(), &(series.round + 1).to_le_bytes()],
+        bump
     )]
-    wager_detail: Account<'info, WagerDetail>,
+    next_round: AccountLoader<'info, BetState>,
 
     #[account(
-        mut,
-        constraint = user_account.account_owner == bettor.key() @ ErrorCode::NotAccountOwnerToPlaceWager,
-        constraint = user_account.active_wagers.len() > 0 @ ErrorCode::ActiveWagersEmpty,
+        init,
+        payer = keeper,
+        space = CreatorBond::MAX_SIZE + 8,
+        seeds = [b"creator_bond", next_round.key().as_ref()],
+        bump
     )]
-    user_account: Account<'info, UserAccount>,
+    next_creator_bond: Account<'info, CreatorBond>,
 
-    /// CHECK: Used to cancel the wager it owns
-    #[account(signer, mut)]
-    bettor: AccountInfo<'info>
-}
+    #[account(constraint = asset.key() == series.asset @ ErrorCode::InvalidAssetAccount)]
+    asset: Account<'info, AssetEntry>,
 
-// Context to claim winnings from a closed bet with a decided outcome
-#[derive(Accounts)]
-pub struct ClaimWinnings<'info> {
+    /// CHECK: Has to be the asset's feed, read through the adapter for its oracle kind
+    #[account(constraint = oracle_feed.key() == asset.oracle_feed @ ErrorCode::AssetOracleMismatch)]
+    oracle_feed: AccountInfo<'info>,
 
-    #[account(
-        constraint = bet_state.status == BetStateStatus::Closed @ ErrorCode::BetStillOpen,
-        constraint = bet_state.winning_bet_range != BetRange::NotAvailable @ ErrorCode::BetStillUndecided,
-        mut
-    )]
-    bet_state: Account<'info, BetState>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    config: Account<'info, GlobalConfig>,
 
-    #[account(
-        has_one = bet_state, 
-        has_one = bettor,
-        constraint = wager_detail.bettor == bettor.key() @ ErrorCode::NotAccountOwnerToClaimWinnings,
-        mut,
-        close = bettor
-    )]
-    wager_detail: Account<'info, WagerDetail>,
+    /// CHECK: Instructions sysvar, signed feeds read their price message from the preceding ed25519 instruction
+    #[account(address = sysvar::instructions::ID)]
+    instructions: AccountInfo<'info>,
 
-    #[account(
-        mut,
-        constraint = user_account.account_owner == bettor.key() @ ErrorCode::NotAccountOwnerToClaimWinnings,
-        constraint = user_account.active_wagers.len() > 0 @ ErrorCode::ActiveWagersEmpty,
-    )]
-    user_account: Account<'info, UserAccount>,
+    #[account(mut)]
+    keeper: Signer<'info>,
 
-    /// CHECK: Used to pay for the bet state account
-    #[account(signer, mut)]
-    bettor: AccountInfo<'info>,
+    /// CHECK: Not read from or written to
+    #[account(address = system_program::ID)]
+    pub system_program: AccountInfo<'info>,
 }
 
-// Context for bet creator to cancel a bet when it's empty
+// Context for anyone to resolve a series' current round
 #[derive(Accounts)]
-pub struct CancelBetState<'info>{
-    #[account(
-        constraint = bet_state.status == BetStateStatus::Open @ ErrorCode::BetIsClosedOrSettled,
-        constraint = bet_state.winning_bet_range == BetRange::NotAvailable @ ErrorCode::BetAlreadyDecided,
-        constraint = bet_state.creator == bettor.key() @ ErrorCode::NotBetCreator,
-        constraint = bet_state.running_total_pool == 0 @ ErrorCode::FundsStillInPlay,
-        mut,
-        close = bettor
-    )]
-    bet_state: Account<'info, BetState>,
-
-    /// CHECK: Used to cancel a wager
-    #[account(signer, mut)]
-    bettor: AccountInfo<'info>
+pub struct ResolveSeriesRound<'info> {
+    series: Account<'info, MarketSeries>,
 
-}
+    #[account(mut, seeds = [b"jackpot", series.key().as_ref()], bump = jackpot.bump)]
+    jackpot: Account<'info, SeriesJackpot>,
 
-// Context for closing a bet before the actual event takes place
-#[derive(Accounts)]
-pub struct CloseBetState<'info> {
     #[account(
-        constraint = bet_state.creator == bet_creator.key() @ ErrorCode::NotBetCreator,
-        constraint = bet_state.status == BetStateStatus::Open @ ErrorCode::BetIsClosedOrSettled,
-        constraint = bet_state.winning_bet_range == BetRange::NotAvailable @ ErrorCode::BetAlreadyDecided,
+        constraint = round.load()?.status == BetStateStatus::Open as u8 || round.load()?.status == BetStateStatus::Closed as u8 @ ErrorCode::BetIsClosedOrSettled,
+        constraint = round.load()?.winning_bet_range == BetRange::NotAvailable as u8 @ ErrorCode::BetAlreadyDecided,
         mut
     )]
-    bet_state: Account<'info, BetState>,
+    round: AccountLoader<'info, BetState>,
 
-    /// CHECK: Not written to
-    #[account()]
-    bet_creator: AccountInfo<'info>
-    
-}
+    /// CHECK: Has to be the feed the round was opened on, read through the adapter for its oracle kind
+    #[account(constraint = oracle_feed.key() == round.load()?.oracle_feed @ ErrorCode::InvalidOracleFeed)]
+    oracle_feed: AccountInfo<'info>,
 
-// General context for a closed bet state
-#[derive(Accounts)]
-pub struct DecideBetState<'info> {
-    #[account(
-        constraint = bet_state.creator == bet_creator.key() @ ErrorCode::NotBetCreator,
-        constraint = bet_state.status == BetStateStatus::Closed @ ErrorCode::BetStillOpen,
-        constraint = bet_state.winning_bet_range == BetRange::NotAvailable @ ErrorCode::BetAlreadyDecided,
-        mut
-    )]
-    bet_state: Account<'info, BetState>,
+    /// CHECK: Registry entry for the series' symbol, checked in the endpoint since the asset may have been deregistered
+    asset: AccountInfo<'info>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    config: Account<'info, GlobalConfig>,
 
-    /// CHECK: Not written to
-    #[account()]
-    bet_creator: AccountInfo<'info>
+    /// CHECK: Instructions sysvar, signed feeds read their price message from the preceding ed25519 instruction
+    #[account(address = sysvar::instructions::ID)]
+    instructions: AccountInfo<'info>,
 }
 
-// General context for a closed and settled bet state
+// Context for anyone to sweep a decided series round into the series' jackpot
 #[derive(Accounts)]
-pub struct SettleBetState<'info> {
+pub struct SweepRoundToJackpot<'info> {
+    #[account(mut, seeds = [b"jackpot", jackpot.series.as_ref()], bump = jackpot.bump)]
+    jackpot: Account<'info, SeriesJackpot>,
+
+    #[account(mut, constraint = round.load()?.creator == jackpot.key() @ ErrorCode::NotSeriesRound)]
+    round: AccountLoader<'info, BetState>,
+
     #[account(
-        constraint = bet_state.status == BetStateStatus::Closed @ ErrorCode::BetStillOpen,
-        constraint = bet_state.winning_bet_range != BetRange::NotAvailable @ ErrorCode::BetStillUndecided,
-        constraint = bet_state.creator == bet_creator.key() @ ErrorCode::NotBetCreator,
         mut,
-        close = bet_creator
+        seeds = [b"creator_bond", round.key().as_ref()],
+        bump = creator_bond.bump,
+        constraint = creator_bond.bet_state == round.key() @ ErrorCode::NotSeriesRound
     )]
-    bet_state: Account<'info, BetState>,
+    creator_bond: Account<'info, CreatorBond>,
 
-    /// CHECK: Not written to
-    #[account()]
-    bet_creator: AccountInfo<'info>
+    /// CHECK: Keeper who opened the round, only receives its rent
+    #[account(mut, address = creator_bond.creator)]
+    keeper: AccountInfo<'info>,
 }
 
-
-// ** Betting Related Types ** //
+// Recurring bet states on one symbol, at the PDA of ["series", symbol]. Round n lives at ["round", series, n] so
+// clients can find the current round from the counter alone.
 #[account]
-pub struct BetState {
-    pub symbol: String, // 14
-    pub creator: Pubkey, //32
-    pub running_total_pool: u64, // 8
-    pub static_total_pool: u64, // 8
-
-    pub neg_three_and_under_pool: u64, //8
-    pub neg_three_to_neg_two_pool: u64, //8
-    pub neg_two_to_neg_one_pool: u64, //8
-    pub neg_one_to_zero_pool: u64, //8
-    pub zero_to_pos_one_pool: u64, //8
-    pub pos_one_to_pos_two_pool: u64, //8
-    pub pos_two_to_pos_three_pool: u64, //8
-    pub pos_three_and_over_pool: u64, //8
-
-    pub status: BetStateStatus, // 33
-    pub start_time: u64, // 8
-    pub end_time: u64, // 8
+pub struct MarketSeries {
+    pub version: u8, // 1
+    pub symbol: [u8; 16], // 16, zero-padded
+    pub asset: Pubkey, // 32
+    pub authority: Pubkey, // 32, admin who created the series
+    pub cadence: u64, // 8, milliseconds between round starts
+    pub duration: u64, // 8, milliseconds
+    pub betting_cutoff: u64, // 8, milliseconds
+    pub bucket_layout: BucketLayout, // 2
+    pub round: u64, // 8, current round, 0 before the first one opens
+    pub next_round_start: u64, // 8, unix milliseconds
+    pub jackpot_fee_bps: u16, // 2, charged on every round's wagers and paid into the jackpot
+    pub jackpot_trigger: JackpotTrigger, // 9
+    pub bump: u8, // 1
+}
 
-    pub snapshot_price: u128, //16
-    pub winning_bet_range: BetRange //33
+impl MarketSeries {
+    const MAX_SIZE: usize = 135;
 }
 
-impl BetState {
-    const MAX_SIZE: usize = 257;
+// When a series' jackpot is paid into a round. Only a round somebody won can take it, until then it keeps growing.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Copy, Debug)]
+pub enum JackpotTrigger {
+    // The next round decided with winners
+    NextRound,
+    // The first round with winners once the jackpot holds at least `lamports`
+    MinBalance { lamports: u64 },
+    // Rounds whose number is a multiple of `rounds`
+    RoundInterval { rounds: u64 },
 }
 
-#[account]
-pub struct WagerDetail {
-    pub bettor: Pubkey, // 32
-    pub bet_state: Pubkey, // 32
-    pub range_status: BetRange, // 33
-    pub bet_value: u64, // 8
+impl JackpotTrigger {
+    fn validate(&self) -> Result<()> {
+        match self {
+            JackpotTrigger::MinBalance { lamports: 0 } | JackpotTrigger::RoundInterval { rounds: 0 } => {
+                Err(error!(ErrorCode::InvalidJackpotTrigger))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn is_hit(&self, balance: u64, round: u64) -> bool {
+        if balance == 0 {
+            return false;
+        }
+        match *self {
+            JackpotTrigger::NextRound => true,
+            JackpotTrigger::MinBalance { lamports } => balance >= lamports,
+            JackpotTrigger::RoundInterval { rounds } => round.checked_rem(rounds) == Some(0),
+        }
+    }
 }
 
-impl WagerDetail {
-    const MAX_SIZE: usize = 105;
+// Value a series carries from round to round, at the PDA of ["jackpot", series]. It's the creator of every round, so
+// it collects their creator fees, along with pools nobody won and rounding dust, on top of its rent.
+#[account]
+pub struct SeriesJackpot {
+    pub version: u8, // 1
+    pub series: Pubkey, // 32
+    pub balance: u64, // 8, lamports waiting to be awarded
+    pub total_awarded: u64, // 8
+    pub last_awarded_round: u64, // 8, 0 if it has never been awarded
+    pub bump: u8, // 1
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Copy, Debug)]
-pub enum BetStateStatus {
-    Open,
-    Closed,
-    Settled
+impl SeriesJackpot {
+    const MAX_SIZE: usize = 58;
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Copy, Debug)]
-pub enum BetRange {
-    NotAvailable,
-    NegThreeAndUnder,
-    NegThreeToNegTwo,
-    NegTwoToNegOne,
-    NegOneToZero,
-    ZeroToPosOne,
-    PosOneToPosTwo,
-    PosTwoToPosThree,
-    PosThreeAndOver
+#[event]
+pub struct JackpotAwarded {
+    pub series: Pubkey,
+    pub round: Pubkey,
+    pub round_number: u64,
+    pub amount: u64,
 }
 
 
@@ -651,6 +3920,21 @@ pub struct CloseUserAccount<'info> {
     account_owner: Signer<'info>
 }
 
+#[derive(Accounts)]
+pub struct SetReferrer<'info> {
+    #[account(
+        mut,
+        constraint = user_account.account_owner == account_owner.key() @ ErrorCode::InvalidAccountOwner,
+        constraint = user_account.referrer == Pubkey::default() @ ErrorCode::ReferrerAlreadySet,
+    )]
+    user_account: Account<'info, UserAccount>,
+
+    #[account(mut)]
+    referrer_account: Account<'info, UserAccount>,
+
+    account_owner: Signer<'info>
+}
+
 #[derive(Accounts)]
 pub struct DepositIntoAccount<'info> {
     #[account(
@@ -680,15 +3964,113 @@ pub struct WithdrawFromAccount<'info> {
 
 #[account]
 pub struct UserAccount {
+    pub version: u8, //1
     pub account_owner: Pubkey, //32
     pub wins: u64, //8
     pub losses: u64, //8
     pub active_wagers: Vec<Pubkey>, //4 + (32 * 5)
     pub current_balance: u64, //8
+    pub referrer: Pubkey, //32, the referrer's user account
+    pub referral_count: u32, //4
+    pub referral_volume: u64, //8, wagered by the users this account referred
+    pub referral_earnings: u64, //8, paid into current_balance
+    pub season_volume: u64, //8, wagered during volume_season
+    pub volume_season: u32, //4
 }
 
 impl UserAccount {
-    const MAX_SIZE: usize = 220;
+    const MAX_SIZE: usize = 1 + 32 + 8 + 8 + (4 + 32 * 5) + 8 + 32 + 4 + 8 + 8 + 8 + 4;
+
+    // Volume counts toward the fee tiers only for the season it was wagered in
+    fn season_volume(&self, season: u32) -> u64 {
+        if self.volume_season == season { self.season_volume } else { 0 }
+    }
+
+    fn add_season_volume(&mut self, season: u32, amount: u64) {
+        self.season_volume = self.season_volume(season) + amount;
+        self.volume_season = season;
+    }
+}
+
+
+// ***** MIGRATION CONTEXT ***** //
+
+// Context to upgrade an account of this program to the current layout
+#[derive(Accounts)]
+pub struct MigrateAccount<'info> {
+    /// CHECK: Checked against the discriminator and size of the layout it's migrated from
+    #[account(mut, owner = crate::ID @ ErrorCode::InvalidMigrationAccount)]
+    account: AccountInfo<'info>,
+
+    // Tops up the rent for the account's new size
+    #[account(mut)]
+    payer: Signer<'info>,
+
+    /// CHECK: Not read from or written to
+    #[account(address = system_program::ID)]
+    system_program: AccountInfo<'info>,
+}
+
+// Context to upgrade a bet state, which is given the creator bond escrow every other bet state has
+#[derive(Accounts)]
+pub struct MigrateBetState<'info> {
+    /// CHECK: Checked against the discriminator and size of the layout it's migrated from
+    #[account(mut, owner = crate::ID @ ErrorCode::InvalidMigrationAccount)]
+    account: AccountInfo<'info>,
+
+    // Only created here, a bet state that already has one is already migrated and the instruction fails on it
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = CreatorBond::MAX_SIZE + 8,
+        seeds = [b"creator_bond", account.key().as_ref()],
+        bump
+    )]
+    creator_bond: Account<'info, CreatorBond>,
+
+    // Tops up the rent for the account's new size and pays for the escrow
+    #[account(mut)]
+    payer: Signer<'info>,
+
+    /// CHECK: Not read from or written to
+    #[account(address = system_program::ID)]
+    system_program: AccountInfo<'info>,
+}
+
+// Context to upgrade a wager detail, along with its already migrated bet state
+#[derive(Accounts)]
+pub struct MigrateWagerDetail<'info> {
+    // Bet states still on a legacy layout share the discriminator but not the size
+    #[account(
+        mut,
+        constraint = bet_state.to_account_info().data_len() == BetState::MAX_SIZE + 8 @ ErrorCode::BetStateNotMigrated
+    )]
+    bet_state: AccountLoader<'info, BetState>,
+
+    /// CHECK: Checked against the discriminator and size of the layout it's migrated from
+    #[account(mut, owner = crate::ID @ ErrorCode::InvalidMigrationAccount)]
+    account: AccountInfo<'info>,
+
+    // The wager's bettor, their user account has to be migrated first
+    user_account: Account<'info, UserAccount>,
+
+    // Legacy wagers predate bettor positions, the position is built up as the bettor's wagers come across
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = BettorPosition::MAX_SIZE + 8,
+        seeds = [b"position", bet_state.key().as_ref(), user_account.key().as_ref()],
+        bump,
+    )]
+    bettor_position: Account<'info, BettorPosition>,
+
+    // Tops up the rent for the account's new size
+    #[account(mut)]
+    payer: Signer<'info>,
+
+    /// CHECK: Not read from or written to
+    #[account(address = system_program::ID)]
+    system_program: AccountInfo<'info>,
 }
 
 
@@ -730,6 +4112,148 @@ pub enum ErrorCode {
     ActiveWagersEmpty,
     #[msg("This is a winning wager, please claim your winnings")]
     PleaseClaimYourWinnings,
+    #[msg("The symbol is longer than a bet state can hold.")]
+    SymbolTooLong,
+    #[msg("The symbol can't be empty.")]
+    EmptySymbol,
+    #[msg("The bet state holds a value that isn't valid for its field.")]
+    InvalidBetStateData,
+    #[msg("The market type's parameters are not valid.")]
+    InvalidMarketType,
+
+    // Betting Limit Errors
+    #[msg("The given betting limits are not valid.")]
+    InvalidWagerLimits,
+    #[msg("This wager is below the minimum wager size for this bet.")]
+    WagerBelowMinimum,
+    #[msg("This wager is above the maximum wager size for this bet.")]
+    WagerAboveMaximum,
+    #[msg("This wager would put your total stake on this bet over the per-user limit.")]
+    UserStakeLimitExceeded,
+    #[msg("This wager would put the bet's total pool over its limit.")]
+    TotalPoolLimitExceeded,
+    #[msg("This wager would give you too large a share of this bet range's pool.")]
+    BucketShareLimitExceeded,
+
+    // Betting Window Errors
+    #[msg("The betting cutoff can't be longer than the bet's duration.")]
+    InvalidBettingCutoff,
+    #[msg("Wagers can no longer be placed or cancelled this close to the bet's end time.")]
+    BettingCutoffReached,
+    #[msg("A bet's duration can't be zero.")]
+    ZeroDuration,
+    #[msg("A bet can't start in the past.")]
+    StartTimeInPast,
+    #[msg("The bet's end time is out of range.")]
+    EndTimeOverflow,
+
+    // Oracle Errors
+    #[msg("The given oracle guards are not valid.")]
+    InvalidOracleGuards,
+    #[msg("The oracle feed is not a valid price feed for this bet.")]
+    InvalidOracleFeed,
+    #[msg("The oracle price is not valid.")]
+    InvalidOraclePrice,
+    #[msg("The snapshot price is not valid.")]
+    InvalidSnapshotPrice,
+    #[msg("The oracle price is not currently trading.")]
+    OraclePriceNotTrading,
+    #[msg("The oracle price is too old to resolve this bet.")]
+    OraclePriceStale,
+    #[msg("The oracle price's confidence interval is too wide to resolve this bet.")]
+    OracleConfidenceTooWide,
+    #[msg("The oracle price's confidence interval straddles a bet range boundary.")]
+    OracleConfidenceStraddlesBoundary,
+    #[msg("Cannot carry out this action until the bet's end time has passed.")]
+    BetNotYetEnded,
+    #[msg("Cannot carry out this action unless the bet has been voided.")]
+    BetNotVoided,
+    #[msg("Resolving a signed feed needs an ed25519 instruction right before it.")]
+    MissingSignedPrice,
+    #[msg("The signed price message is not valid.")]
+    InvalidSignedPrice,
+    #[msg("The price was not signed by a whitelisted publisher.")]
+    UnknownPricePublisher,
+    #[msg("The signed price is not the publisher's first message at or after the bet's start or end time.")]
+    SignedPriceNotAtSettlement,
+    #[msg("Too many price publishers.")]
+    TooManyPricePublishers,
+    #[msg("The oracle sources given for this bet are not valid.")]
+    InvalidOracleSources,
+    #[msg("Not enough oracle sources passed the guards to resolve this bet.")]
+    OracleQuorumNotReached,
+    #[msg("Math overflow.")]
+    MathOverflow,
+
+    // Config Errors
+    #[msg("You are not the admin of the config.")]
+    NotConfigAdmin,
+    #[msg("You are not the resolver.")]
+    NotResolver,
+    #[msg("You are not the pauser.")]
+    NotPauser,
+    #[msg("Betting is paused.")]
+    BettingPaused,
+
+    // Asset Registry Errors
+    #[msg("Bet states can't be created on this symbol, it isn't on the asset registry.")]
+    AssetNotRegistered,
+    #[msg("New bet states on this asset are disabled.")]
+    AssetDisabled,
+    #[msg("The oracle feed is not the asset's registered feed.")]
+    AssetOracleMismatch,
+    #[msg("The bucket layout is not valid.")]
+    InvalidBucketLayout,
+    #[msg("The trading hours are not valid.")]
+    InvalidTradingHours,
+    #[msg("Crypto assets trade around the clock and can't have closed hours or holidays.")]
+    AssetNeverCloses,
+    #[msg("An asset can't have that many holidays.")]
+    TooManyHolidays,
+    #[msg("The asset's market is closed for part of the bet's window.")]
+    MarketClosedDuringBet,
+    #[msg("The asset account doesn't match the bet's symbol.")]
+    InvalidAssetAccount,
+
+    // Resolver Committee Errors
+    #[msg("The resolver committee is not valid.")]
+    InvalidResolverCommittee,
+    #[msg("You are not a member of this bet's resolver committee.")]
+    NotCommitteeMember,
+    #[msg("You have already voted on this outcome.")]
+    AlreadyVoted,
+    #[msg("This bet can only be decided by its resolver committee.")]
+    ResolverCommitteeRequired,
+
+    // Dispute Errors
+    #[msg("The dispute window for this outcome has closed.")]
+    DisputeWindowClosed,
+    #[msg("This outcome has already been disputed.")]
+    OutcomeAlreadyDisputed,
+    #[msg("There is no pending dispute on this bet.")]
+    NoPendingDispute,
+    #[msg("You are not the arbitration authority.")]
+    NotArbitrationAuthority,
+    #[msg("A dispute has to name a different range than the decided outcome.")]
+    InvalidDisputeRuling,
+    #[msg("You have no wager on this bet.")]
+    NotBettorOnBetState,
+    #[msg("Claims are locked until the dispute window passes or the dispute is ruled on.")]
+    ClaimsLocked,
+
+    // Fee Errors
+    #[msg("The protocol fee and the creator fee cap can't add up to more than the maximum total fee.")]
+    InvalidFeeConfig,
+    #[msg("The creator fee is above the cap set in the config.")]
+    InvalidCreatorFee,
+    #[msg("The treasury doesn't match the config.")]
+    InvalidTreasury,
+    #[msg("Fee tiers must need increasing volume and stay within the maximum total fee.")]
+    InvalidFeeTiers,
+
+    // Creator Bond Errors
+    #[msg("Payouts on this bet have already started, it can no longer be voided.")]
+    PayoutsAlreadyStarted,
 
     // User Account Errors
     #[msg("You are not the creator of this account.")]
@@ -743,4 +4267,74 @@ pub enum ErrorCode {
     #[msg("Cannot withdraw from an empty account.")]
     CannotWithdrawFromEmptyAccount,
 
+    // Migration Errors
+    #[msg("This account is already on the current layout.")]
+    AccountAlreadyMigrated,
+    #[msg("This account can't be migrated with this instruction.")]
+    InvalidMigrationAccount,
+    #[msg("The bet state has to be migrated first.")]
+    BetStateNotMigrated,
+    #[msg("This account is on a version this program doesn't know how to migrate.")]
+    UnsupportedAccountVersion,
+
+    // Market Series Errors
+    #[msg("A series' cadence can't be shorter than its rounds.")]
+    InvalidSeriesSchedule,
+    #[msg("The series' next round isn't due yet.")]
+    RoundNotDue,
+    #[msg("The round account isn't the series' current round.")]
+    InvalidSeriesRound,
+    #[msg("A jackpot trigger has to wait for at least one lamport or one round.")]
+    InvalidJackpotTrigger,
+    #[msg("The round doesn't belong to this jackpot's series.")]
+    NotSeriesRound,
+    #[msg("Rounds of a market series can only be resolved through the series.")]
+    ResolvedThroughSeries,
+
+    // Referral Errors
+    #[msg("This account already has a referrer.")]
+    ReferrerAlreadySet,
+    #[msg("You can't refer yourself.")]
+    SelfReferral,
+    #[msg("An account that has referred others can't take a referrer.")]
+    ReferralLoop,
+    #[msg("The referrer's user account has to be passed for a referred bettor.")]
+    ReferrerAccountMismatch,
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wager_detail_max_size_is_its_serialized_size() {
+        let wager_detail = WagerDetail {
+            version: ACCOUNT_VERSION,
+            bettor: Pubkey::default(),
+            bet_state: Pubkey::default(),
+            range_status: BetRange::NotAvailable,
+            bet_value: u64::MAX,
+        };
+        assert_eq!(wager_detail.try_to_vec().unwrap().len(), WagerDetail::MAX_SIZE);
+    }
+
+    #[test]
+    fn user_account_max_size_is_its_serialized_size_with_every_wager_slot_filled() {
+        let user_account = UserAccount {
+            version: ACCOUNT_VERSION,
+            account_owner: Pubkey::default(),
+            wins: 0,
+            losses: 0,
+            active_wagers: vec![Pubkey::default(); 5],
+            current_balance: 0,
+            referrer: Pubkey::default(),
+            referral_count: 0,
+            referral_volume: 0,
+            referral_earnings: 0,
+            season_volume: 0,
+            volume_season: 0,
+        };
+        assert_eq!(user_account.try_to_vec().unwrap().len(), UserAccount::MAX_SIZE);
+    }
 }
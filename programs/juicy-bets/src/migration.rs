@@ -0,0 +1,252 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke;
+use anchor_lang::solana_program::system_instruction;
+use anchor_lang::Discriminator;
+use bytemuck::Zeroable;
+use std::convert::TryFrom;
+
+use crate::oracle::OracleKind;
+use crate::price::Price;
+use crate::{
+    BetRange, BetState, BetStateStatus, BoundaryRule, DisputeStatus, ErrorCode, StraddlePolicy, UserAccount,
+    WagerDetail, ACCOUNT_VERSION,
+};
+
+// Snapshot prices used to be passed in by the creator in thousandths
+const V0_SNAPSHOT_PRICE_EXPO: i32 = -3;
+
+// Layouts accounts were written with before the current one. Those accounts were all allocated at exactly their old
+// size, which is how they're told apart from each other and from current ones.
+
+#[derive(AnchorDeserialize)]
+pub struct BetStateV0 {
+    pub symbol: String,
+    pub creator: Pubkey,
+    pub running_total_pool: u64,
+    pub static_total_pool: u64,
+
+    pub neg_three_and_under_pool: u64,
+    pub neg_three_to_neg_two_pool: u64,
+    pub neg_two_to_neg_one_pool: u64,
+    pub neg_one_to_zero_pool: u64,
+    pub zero_to_pos_one_pool: u64,
+    pub pos_one_to_pos_two_pool: u64,
+    pub pos_two_to_pos_three_pool: u64,
+    pub pos_three_and_over_pool: u64,
+
+    pub status: BetStateStatus,
+    pub start_time: u64,
+    pub end_time: u64,
+
+    pub snapshot_price: u128,
+    pub winning_bet_range: BetRange,
+}
+
+impl BetStateV0 {
+    pub const SPACE: usize = 257 + 8;
+
+    // Version 0 bet states predate oracles, limits, disputes and fees, so they come across with everything switched off
+    // and have to be decided by the resolver. Wagers on them are counted as they're migrated.
+    fn upgrade(self) -> Result<BetState> {
+        let snapshot_mantissa = i64::try_from(self.snapshot_price).map_err(|_| error!(ErrorCode::MathOverflow))?;
+
+        let mut bet_state = BetState::zeroed();
+        bet_state.version = ACCOUNT_VERSION;
+        bet_state.set_symbol(&self.symbol)?;
+        bet_state.creator = self.creator;
+        bet_state.running_total_pool = self.running_total_pool;
+        bet_state.static_total_pool = self.static_total_pool;
+        // Every wager on a version 0 bet state is still open, so their stakes add up to the static pool
+        bet_state.unmigrated_stake = self.static_total_pool;
+        bet_state.pools = [
+            self.neg_three_and_under_pool,
+            self.neg_three_to_neg_two_pool,
+            self.neg_two_to_neg_one_pool,
+            self.neg_one_to_zero_pool,
+            self.zero_to_pos_one_pool,
+            self.pos_one_to_pos_two_pool,
+            self.pos_two_to_pos_three_pool,
+            self.pos_three_and_over_pool,
+        ];
+        bet_state.status = self.status as u8;
+        bet_state.start_time = self.start_time;
+        bet_state.end_time = self.end_time;
+        bet_state.set_snapshot_price(&Price {
+            mantissa: snapshot_mantissa,
+            expo: V0_SNAPSHOT_PRICE_EXPO,
+            conf: 0,
+            publish_time: 0,
+        });
+        bet_state.winning_bet_range = self.winning_bet_range as u8;
+        bet_state.split_bet_range = BetRange::NotAvailable as u8;
+        bet_state.boundary_rule = BoundaryRule::LowerInclusive as u8;
+        bet_state.straddle_policy = StraddlePolicy::Reject as u8;
+        bet_state.dispute_status = DisputeStatus::None as u8;
+        bet_state.oracle_kind = OracleKind::Pyth as u8;
+        bet_state.oracle_quorum = 1;
+
+        Ok(bet_state)
+    }
+}
+
+#[derive(AnchorDeserialize)]
+pub struct WagerDetailV0 {
+    pub bettor: Pubkey,
+    pub bet_state: Pubkey,
+    pub range_status: BetRange,
+    pub bet_value: u64,
+}
+
+impl WagerDetailV0 {
+    pub const SPACE: usize = 105 + 8;
+
+    fn upgrade(self) -> Result<WagerDetail> {
+        Ok(WagerDetail {
+            version: ACCOUNT_VERSION,
+            bettor: self.bettor,
+            bet_state: self.bet_state,
+            range_status: self.range_status,
+            bet_value: self.bet_value,
+        })
+    }
+}
+
+#[derive(AnchorDeserialize)]
+pub struct UserAccountV0 {
+    pub account_owner: Pubkey,
+    pub wins: u64,
+    pub losses: u64,
+    pub active_wagers: Vec<Pubkey>,
+    pub current_balance: u64,
+}
+
+impl UserAccountV0 {
+    pub const SPACE: usize = 220 + 8;
+
+    fn upgrade(self) -> Result<UserAccount> {
+        Ok(UserAccount {
+            version: ACCOUNT_VERSION,
+            account_owner: self.account_owner,
+            wins: self.wins,
+            losses: self.losses,
+            active_wagers: self.active_wagers,
+            current_balance: self.current_balance,
+            referrer: Pubkey::default(),
+            referral_count: 0,
+            referral_volume: 0,
+            referral_earnings: 0,
+            season_volume: 0,
+            volume_season: 0,
+        })
+    }
+}
+
+pub(crate) fn migrate_bet_state<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<BetState> {
+    migrate_account(account, payer, system_program, BetState::MAX_SIZE + 8, |data| match data.len() {
+        BetStateV0::SPACE => read_legacy::<BetStateV0>(data)?.upgrade(),
+        _ => Err(error!(ErrorCode::UnsupportedAccountVersion)),
+    })
+}
+
+pub(crate) fn migrate_wager_detail<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<WagerDetail> {
+    migrate_account(account, payer, system_program, WagerDetail::MAX_SIZE + 8, |data| match data.len() {
+        WagerDetailV0::SPACE => read_legacy::<WagerDetailV0>(data)?.upgrade(),
+        _ => Err(error!(ErrorCode::UnsupportedAccountVersion)),
+    })
+}
+
+pub(crate) fn migrate_user_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<UserAccount> {
+    migrate_account(account, payer, system_program, UserAccount::MAX_SIZE + 8, |data| match data.len() {
+        UserAccountV0::SPACE => read_legacy::<UserAccountV0>(data)?.upgrade(),
+        _ => Err(error!(ErrorCode::UnsupportedAccountVersion)),
+    })
+}
+
+// Legacy layouts are read from the whole account, discriminator included
+fn read_legacy<T: AnchorDeserialize>(data: &[u8]) -> Result<T> {
+    T::deserialize(&mut &data[8..]).map_err(|_| error!(ErrorCode::InvalidMigrationAccount))
+}
+
+// How an upgraded account is written back: Borsh accounts are serialized, zero-copy ones are copied in as they are
+trait MigratedAccount: Discriminator {
+    fn write(&self, data: &mut [u8]) -> Result<()>;
+}
+
+impl MigratedAccount for WagerDetail {
+    fn write(&self, mut data: &mut [u8]) -> Result<()> {
+        self.try_serialize(&mut data)
+    }
+}
+
+impl MigratedAccount for UserAccount {
+    fn write(&self, mut data: &mut [u8]) -> Result<()> {
+        self.try_serialize(&mut data)
+    }
+}
+
+impl MigratedAccount for BetState {
+    fn write(&self, data: &mut [u8]) -> Result<()> {
+        let bytes = bytemuck::bytes_of(self);
+        data[..8].copy_from_slice(&BetState::discriminator());
+        data[8..8 + bytes.len()].copy_from_slice(bytes);
+        Ok(())
+    }
+}
+
+// Reads an account in one of its legacy layouts, resizes it to the current size with the payer topping up its rent,
+// and writes it back in the current layout. Accounts already at the current size are told apart by the version byte
+// after their discriminator and never reach `upgrade`, which is given the whole account and fails with
+// UnsupportedAccountVersion when its size doesn't match any legacy layout.
+fn migrate_account<'info, T, F>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    space: usize,
+    upgrade: F,
+) -> Result<T>
+where
+    T: MigratedAccount,
+    F: FnOnce(&[u8]) -> Result<T>,
+{
+    let upgraded = {
+        let data = account.try_borrow_data()?;
+
+        if data.len() < 8 || data[..8] != T::discriminator() {
+            return Err(error!(ErrorCode::InvalidMigrationAccount));
+        }
+
+        if data.len() == space {
+            return Err(match data[8] {
+                ACCOUNT_VERSION => error!(ErrorCode::AccountAlreadyMigrated),
+                _ => error!(ErrorCode::UnsupportedAccountVersion),
+            });
+        }
+
+        upgrade(&data)?
+    };
+
+    let top_up = Rent::get()?.minimum_balance(space).saturating_sub(account.lamports());
+    if top_up > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, account.key, top_up),
+            &[payer.clone(), account.clone(), system_program.clone()],
+        )?;
+    }
+
+    account.realloc(space, true)?;
+    upgraded.write(&mut account.try_borrow_mut_data()?)?;
+
+    Ok(upgraded)
+}
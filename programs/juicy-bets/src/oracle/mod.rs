@@ -0,0 +1,136 @@
+use anchor_lang::prelude::*;
+
+use crate::price::{pow10, Price};
+use crate::{ErrorCode, GlobalConfig};
+
+pub mod pyth;
+pub mod signed_feed;
+pub mod switchboard;
+
+// Which adapter a bet state's oracle feed is read through
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Copy, Debug)]
+pub enum OracleKind {
+    Pyth,
+    Switchboard,
+    SignedFeed,
+}
+
+impl OracleKind {
+    // Kind for a value stored on a zero-copy bet state
+    pub fn from_u8(value: u8) -> Result<OracleKind> {
+        match value {
+            0 => Ok(OracleKind::Pyth),
+            1 => Ok(OracleKind::Switchboard),
+            2 => Ok(OracleKind::SignedFeed),
+            _ => Err(error!(ErrorCode::InvalidBetStateData)),
+        }
+    }
+}
+
+// One oracle feed a bet state can be resolved from
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Copy, Debug)]
+pub struct OracleSource {
+    pub kind: OracleKind, // 1
+    pub feed: Pubkey, // 32
+}
+
+// Price as reported by any of the adapters, along with whether the feed considers it live
+#[derive(Clone, Copy, Debug)]
+pub struct OraclePrice {
+    pub price: Price,
+    pub trading: bool,
+}
+
+// Checks a feed can be used with the given oracle kind when a bet state is created.
+// Signed feeds have no account of their own, the feed key only identifies which messages belong to the bet.
+pub fn validate_feed(kind: OracleKind, feed: &AccountInfo, config: &GlobalConfig) -> Result<()> {
+    match kind {
+        OracleKind::Pyth => {
+            check_owner(feed, &config.pyth_program)?;
+            pyth::load_price(feed)?;
+        },
+        OracleKind::Switchboard => {
+            check_owner(feed, &config.switchboard_program)?;
+            switchboard::load_price(feed)?;
+        },
+        OracleKind::SignedFeed => {},
+    }
+    Ok(())
+}
+
+// Reads the current price for a bet state's feed through the adapter for its oracle kind. Signed feeds carry their
+// own history, when pricing a bet at `priced_at` (unix millis) they only accept the message that brackets it.
+pub fn load_price(
+    kind: OracleKind,
+    feed: &AccountInfo,
+    config: &GlobalConfig,
+    instructions_sysvar: &AccountInfo,
+    priced_at: Option<u64>,
+) -> Result<OraclePrice> {
+    match kind {
+        OracleKind::Pyth => {
+            check_owner(feed, &config.pyth_program)?;
+            pyth::load_price(feed)
+        },
+        OracleKind::Switchboard => {
+            check_owner(feed, &config.switchboard_program)?;
+            switchboard::load_price(feed)
+        },
+        OracleKind::SignedFeed => signed_feed::load_price(
+            &feed.key(),
+            &config.signed_feed_publishers,
+            instructions_sysvar,
+            // Rounded up, a message published in the second the bet starts or ends but before that time came too early
+            priced_at.map(|priced_at| (priced_at.saturating_add(999) / 1000) as i64),
+        ),
+    }
+}
+
+fn check_owner(feed: &AccountInfo, oracle_program: &Pubkey) -> Result<()> {
+    if feed.owner != oracle_program {
+        return Err(error!(ErrorCode::InvalidOracleFeed));
+    }
+    Ok(())
+}
+
+// Median of several prices, taken at the smallest exponent among them so no source loses precision.
+// An even number of prices averages the middle two (rounded down) and keeps the wider of their confidences.
+// The publish time is the oldest among the prices.
+pub fn median_price(prices: &[OraclePrice]) -> Result<OraclePrice> {
+    let common_expo = prices.iter().map(|price| price.price.expo).min().ok_or(ErrorCode::OracleQuorumNotReached)?;
+
+    let mut scaled = Vec::with_capacity(prices.len());
+    for OraclePrice { price, .. } in prices {
+        let value = price.mantissa_at(common_expo)?;
+        let conf = (price.conf as i128).checked_mul(pow10(price.expo - common_expo)?).ok_or(ErrorCode::MathOverflow)?;
+        scaled.push((value, conf));
+    }
+    scaled.sort_by_key(|(value, _)| *value);
+
+    let middle = scaled.len() / 2;
+    let (mut value, mut conf) = if scaled.len() % 2 == 1 {
+        scaled[middle]
+    } else {
+        let (low, low_conf) = scaled[middle - 1];
+        let (high, high_conf) = scaled[middle];
+        ((low + high).div_euclid(2), low_conf.max(high_conf))
+    };
+
+    // Give back precision until the median fits the i64 every adapter reports
+    let mut expo = common_expo;
+    while value > i64::MAX as i128 || value < i64::MIN as i128 {
+        value /= 10;
+        conf /= 10;
+        expo += 1;
+    }
+
+    Ok(OraclePrice {
+        price: Price {
+            mantissa: value as i64,
+            expo,
+            conf: conf.min(u64::MAX as i128) as u64,
+            publish_time: prices.iter().map(|price| price.price.publish_time).min().unwrap_or_default(),
+        },
+        trading: true,
+    })
+}
@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+use std::convert::TryInto;
+
+use super::OraclePrice;
+use crate::price::Price;
+use crate::ErrorCode;
+
+// Layout of a Pyth v2 price account, only the fields the program reads are mapped
+const MAGIC: u32 = 0xa1b2c3d4;
+const VERSION_2: u32 = 2;
+const ACCOUNT_TYPE_PRICE: u32 = 3;
+
+const MAGIC_OFFSET: usize = 0;
+const VERSION_OFFSET: usize = 4;
+const ACCOUNT_TYPE_OFFSET: usize = 8;
+const EXPONENT_OFFSET: usize = 20;
+const TIMESTAMP_OFFSET: usize = 96;
+const AGGREGATE_PRICE_OFFSET: usize = 208;
+const AGGREGATE_CONF_OFFSET: usize = 216;
+const AGGREGATE_STATUS_OFFSET: usize = 224;
+const PRICE_ACCOUNT_MIN_LEN: usize = 240;
+
+const PRICE_STATUS_TRADING: u32 = 1;
+
+// Reads the aggregate price from a Pyth price account
+pub fn load_price(price_account: &AccountInfo) -> Result<OraclePrice> {
+    let data = price_account.try_borrow_data()?;
+
+    if data.len() < PRICE_ACCOUNT_MIN_LEN
+        || read_u32(&data, MAGIC_OFFSET) != MAGIC
+        || read_u32(&data, VERSION_OFFSET) != VERSION_2
+        || read_u32(&data, ACCOUNT_TYPE_OFFSET) != ACCOUNT_TYPE_PRICE
+    {
+        return Err(error!(ErrorCode::InvalidOracleFeed));
+    }
+
+    Ok(OraclePrice {
+        price: Price::from_pyth(
+            read_i64(&data, AGGREGATE_PRICE_OFFSET),
+            read_u64(&data, AGGREGATE_CONF_OFFSET),
+            read_u32(&data, EXPONENT_OFFSET) as i32,
+            read_i64(&data, TIMESTAMP_OFFSET),
+        ),
+        trading: read_u32(&data, AGGREGATE_STATUS_OFFSET) == PRICE_STATUS_TRADING,
+    })
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+fn read_i64(data: &[u8], offset: usize) -> i64 {
+    i64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
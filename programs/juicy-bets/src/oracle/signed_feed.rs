@@ -0,0 +1,124 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::sysvar::instructions::{load_current_index_checked, load_instruction_at_checked};
+use std::convert::TryInto;
+
+use super::OraclePrice;
+use crate::price::Price;
+use crate::ErrorCode;
+
+// Layout of an ed25519 program instruction carrying a single signature
+const SIGNATURE_COUNT_OFFSET: usize = 0;
+const OFFSETS_START: usize = 2;
+const OFFSETS_LEN: usize = 14;
+const PUBKEY_LEN: usize = 32;
+const SIGNATURE_LEN: usize = 64;
+
+// An instruction index of u16::MAX means the data lives inside the ed25519 instruction itself
+const CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+// Price message signed off-chain by a whitelisted publisher
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct SignedPriceMessage {
+    pub feed: Pubkey,
+    pub price: i64,
+    pub conf: u64,
+    pub expo: i32,
+    pub publish_time: i64,
+    pub trading: bool,
+    // Publish time of the publisher's previous message on the feed, so a message can be shown to be the first one
+    // at or after a given time
+    pub prev_publish_time: i64,
+}
+
+// Reads the signed price message for the feed from the ed25519 instructions placed before the current one.
+// The ed25519 program has already verified each signature by the time this runs, so only the signer and the
+// message contents are left to check. Several feeds can be signed in the same transaction.
+// When pricing a bet at `priced_at` (unix seconds), its start or its end, only the publisher's first message at or
+// after that time is taken, otherwise any earlier message that is still fresh could be picked to move the bet.
+pub fn load_price(
+    feed: &Pubkey,
+    publishers: &[Pubkey],
+    instructions_sysvar: &AccountInfo,
+    priced_at: Option<i64>,
+) -> Result<OraclePrice> {
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    let mut signed_by_unknown_publisher = false;
+    let mut signed_at_other_time = false;
+
+    for index in (0..current_index).rev() {
+        let instruction = load_instruction_at_checked(index as usize, instructions_sysvar)?;
+        if instruction.program_id != ed25519_program::ID {
+            continue;
+        }
+
+        let (publisher, message) = parse_signed_price(&instruction.data)?;
+        if message.feed != *feed {
+            continue;
+        }
+        if !publishers.contains(&publisher) {
+            signed_by_unknown_publisher = true;
+            continue;
+        }
+        if let Some(priced_at) = priced_at {
+            if message.prev_publish_time >= priced_at || message.publish_time < priced_at {
+                signed_at_other_time = true;
+                continue;
+            }
+        }
+
+        return Ok(OraclePrice {
+            price: Price {
+                mantissa: message.price,
+                expo: message.expo,
+                conf: message.conf,
+                publish_time: message.publish_time,
+            },
+            trading: message.trading,
+        });
+    }
+
+    if signed_by_unknown_publisher {
+        return Err(error!(ErrorCode::UnknownPricePublisher));
+    }
+    if signed_at_other_time {
+        return Err(error!(ErrorCode::SignedPriceNotAtSettlement));
+    }
+    Err(error!(ErrorCode::MissingSignedPrice))
+}
+
+// Pulls the signer and the price message out of an ed25519 instruction carrying a single signature
+fn parse_signed_price(data: &[u8]) -> Result<(Pubkey, SignedPriceMessage)> {
+    if data.len() < OFFSETS_START + OFFSETS_LEN || data[SIGNATURE_COUNT_OFFSET] != 1 {
+        return Err(error!(ErrorCode::InvalidSignedPrice));
+    }
+
+    let signature_offset = read_u16(data, OFFSETS_START) as usize;
+    let signature_instruction = read_u16(data, OFFSETS_START + 2);
+    let pubkey_offset = read_u16(data, OFFSETS_START + 4) as usize;
+    let pubkey_instruction = read_u16(data, OFFSETS_START + 6);
+    let message_offset = read_u16(data, OFFSETS_START + 8) as usize;
+    let message_len = read_u16(data, OFFSETS_START + 10) as usize;
+    let message_instruction = read_u16(data, OFFSETS_START + 12);
+
+    // Everything that was verified has to come from the ed25519 instruction we are looking at
+    if signature_instruction != CURRENT_INSTRUCTION
+        || pubkey_instruction != CURRENT_INSTRUCTION
+        || message_instruction != CURRENT_INSTRUCTION
+        || signature_offset + SIGNATURE_LEN > data.len()
+        || pubkey_offset + PUBKEY_LEN > data.len()
+        || message_offset + message_len > data.len()
+    {
+        return Err(error!(ErrorCode::InvalidSignedPrice));
+    }
+
+    let publisher = Pubkey::new(&data[pubkey_offset..pubkey_offset + PUBKEY_LEN]);
+    let message = SignedPriceMessage::try_from_slice(&data[message_offset..message_offset + message_len])
+        .map_err(|_| error!(ErrorCode::InvalidSignedPrice))?;
+
+    Ok((publisher, message))
+}
+
+fn read_u16(data: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes(data[offset..offset + 2].try_into().unwrap())
+}
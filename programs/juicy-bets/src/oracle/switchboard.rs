@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use std::convert::TryInto;
+
+use super::OraclePrice;
+use crate::price::Price;
+use crate::ErrorCode;
+
+// Layout of a Switchboard v2 aggregator account (8 byte discriminator followed by the packed
+// AggregatorAccountData), only the fields the program reads are mapped
+const MIN_ORACLE_RESULTS_OFFSET: usize = 236;
+const LATEST_ROUND_OFFSET: usize = 341;
+
+const ROUND_NUM_SUCCESS_OFFSET: usize = LATEST_ROUND_OFFSET;
+const ROUND_OPEN_TIMESTAMP_OFFSET: usize = LATEST_ROUND_OFFSET + 17;
+const ROUND_RESULT_OFFSET: usize = LATEST_ROUND_OFFSET + 25;
+const ROUND_STD_DEVIATION_OFFSET: usize = LATEST_ROUND_OFFSET + 45;
+const AGGREGATOR_ACCOUNT_MIN_LEN: usize = LATEST_ROUND_OFFSET + 65;
+
+// Switchboard decimals are `mantissa / 10^scale`
+struct SwitchboardDecimal {
+    mantissa: i128,
+    scale: u32,
+}
+
+// Reads the latest confirmed round of a Switchboard aggregator. The round only counts as trading
+// when enough oracles responded to it.
+pub fn load_price(aggregator_account: &AccountInfo) -> Result<OraclePrice> {
+    let data = aggregator_account.try_borrow_data()?;
+
+    if data.len() < AGGREGATOR_ACCOUNT_MIN_LEN {
+        return Err(error!(ErrorCode::InvalidOracleFeed));
+    }
+
+    let min_oracle_results = read_u32(&data, MIN_ORACLE_RESULTS_OFFSET);
+    let num_success = read_u32(&data, ROUND_NUM_SUCCESS_OFFSET);
+    let mut result = read_decimal(&data, ROUND_RESULT_OFFSET);
+    let std_deviation = read_decimal(&data, ROUND_STD_DEVIATION_OFFSET);
+
+    // Drop precision until the mantissa fits the i64 every other adapter reports
+    while result.mantissa > i64::MAX as i128 || result.mantissa < i64::MIN as i128 {
+        if result.scale == 0 {
+            return Err(error!(ErrorCode::InvalidOraclePrice));
+        }
+        result.mantissa /= 10;
+        result.scale -= 1;
+    }
+
+    let conf = rescale(&std_deviation, result.scale)?;
+
+    Ok(OraclePrice {
+        price: Price {
+            mantissa: result.mantissa as i64,
+            expo: -(result.scale as i32),
+            conf: conf.unsigned_abs().min(u64::MAX as u128) as u64,
+            publish_time: read_i64(&data, ROUND_OPEN_TIMESTAMP_OFFSET),
+        },
+        trading: num_success > 0 && num_success >= min_oracle_results,
+    })
+}
+
+// Expresses a decimal as a mantissa at the given scale
+fn rescale(decimal: &SwitchboardDecimal, scale: u32) -> Result<i128> {
+    if decimal.scale <= scale {
+        let factor = 10i128.checked_pow(scale - decimal.scale).ok_or(ErrorCode::MathOverflow)?;
+        decimal.mantissa.checked_mul(factor).ok_or_else(|| error!(ErrorCode::MathOverflow))
+    } else {
+        let factor = 10i128.checked_pow(decimal.scale - scale).ok_or(ErrorCode::MathOverflow)?;
+        Ok(decimal.mantissa / factor)
+    }
+}
+
+fn read_decimal(data: &[u8], offset: usize) -> SwitchboardDecimal {
+    SwitchboardDecimal {
+        mantissa: i128::from_le_bytes(data[offset..offset + 16].try_into().unwrap()),
+        scale: read_u32(data, offset + 16),
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_i64(data: &[u8], offset: usize) -> i64 {
+    i64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
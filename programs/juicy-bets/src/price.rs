@@ -0,0 +1,269 @@
+use anchor_lang::prelude::*;
+use std::convert::TryFrom;
+
+use crate::{ErrorCode, BASIS_POINTS};
+
+// Direction a percent change is rounded in when it doesn't land on a whole basis point
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Rounding {
+    Down,
+    Up,
+}
+
+// Fixed-point price, the real value is `mantissa * 10^expo` and the confidence is given at the same exponent
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Price {
+    pub mantissa: i64, // 8
+    pub expo: i32, // 4
+    pub conf: u64, // 8
+    pub publish_time: i64, // 8, unix seconds
+}
+
+impl Price {
+    pub const SIZE: usize = 28;
+
+    // Pyth reports an i64 price and a u64 confidence at an i32 exponent, the same widths kept here,
+    // so converting is a plain copy and never rounds
+    pub fn from_pyth(price: i64, conf: u64, expo: i32, publish_time: i64) -> Price {
+        Price {
+            mantissa: price,
+            expo,
+            conf,
+            publish_time,
+        }
+    }
+
+    // Mantissa expressed at a smaller or equal exponent, exact as long as it fits an i128
+    pub fn mantissa_at(&self, expo: i32) -> Result<i128> {
+        if expo > self.expo {
+            return Err(error!(ErrorCode::MathOverflow));
+        }
+        (self.mantissa as i128)
+            .checked_mul(pow10(self.expo - expo)?)
+            .ok_or_else(|| error!(ErrorCode::MathOverflow))
+    }
+
+    // Mantissa expressed at any exponent, failing unless it's exact and fits an i64
+    pub fn exact_mantissa_at(&self, expo: i32) -> Result<i64> {
+        let mantissa = if expo <= self.expo {
+            self.mantissa_at(expo)?
+        } else {
+            let divisor = pow10(expo - self.expo)?;
+            if self.mantissa as i128 % divisor != 0 {
+                return Err(error!(ErrorCode::MathOverflow));
+            }
+            self.mantissa as i128 / divisor
+        };
+        i64::try_from(mantissa).map_err(|_| error!(ErrorCode::MathOverflow))
+    }
+
+    // Both ends of the confidence interval, as mantissas at this price's exponent
+    pub fn confidence_interval(&self) -> (i128, i128) {
+        let mantissa = self.mantissa as i128;
+        let conf = self.conf as i128;
+        (mantissa - conf, mantissa + conf)
+    }
+
+    // Percent change in basis points from this price to `mantissa * 10^expo`.
+    // Both sides are brought to the smaller exponent so nothing is dropped before the division, which then rounds
+    // toward negative infinity (Down) or positive infinity (Up). Rounding down, a fall of 1.001% is -101 bps and
+    // a move just under a bucket boundary never reaches it; rounding up, a rise of 0.999% is 100 bps and a move
+    // just over a boundary never stays on it.
+    pub fn change_bps_to(&self, mantissa: i128, expo: i32, rounding: Rounding) -> Result<i64> {
+        let (opening, closing) = self.aligned_with(mantissa, expo)?;
+
+        let scaled_change = closing
+            .checked_sub(opening)
+            .and_then(|change| change.checked_mul(BASIS_POINTS as i128))
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        divide_rounded(scaled_change, opening, rounding)
+    }
+
+    // Percent change from this price to `mantissa * 10^expo` less a benchmark's percent change from
+    // `benchmark` to `benchmark_mantissa * 10^benchmark_expo`, in basis points. The two changes are subtracted as
+    // fractions over a common denominator, so the spread is rounded once and the same way as change_bps_to.
+    pub fn spread_bps_to(
+        &self,
+        mantissa: i128,
+        expo: i32,
+        benchmark: &Price,
+        benchmark_mantissa: i128,
+        benchmark_expo: i32,
+        rounding: Rounding,
+    ) -> Result<i64> {
+        let (opening, closing) = self.aligned_with(mantissa, expo)?;
+        let (benchmark_opening, benchmark_closing) = benchmark.aligned_with(benchmark_mantissa, benchmark_expo)?;
+
+        // closing / opening - benchmark_closing / benchmark_opening
+        let scaled_spread = closing
+            .checked_mul(benchmark_opening)
+            .zip(benchmark_closing.checked_mul(opening))
+            .and_then(|(ratio, benchmark_ratio)| ratio.checked_sub(benchmark_ratio))
+            .and_then(|spread| spread.checked_mul(BASIS_POINTS as i128))
+            .ok_or(ErrorCode::MathOverflow)?;
+        let denominator = opening.checked_mul(benchmark_opening).ok_or(ErrorCode::MathOverflow)?;
+
+        divide_rounded(scaled_spread, denominator, rounding)
+    }
+
+    // This price and `mantissa * 10^expo` as mantissas at the smaller of the two exponents
+    fn aligned_with(&self, mantissa: i128, expo: i32) -> Result<(i128, i128)> {
+        if self.mantissa <= 0 {
+            return Err(error!(ErrorCode::InvalidSnapshotPrice));
+        }
+
+        let common_expo = expo.min(self.expo);
+        let opening = self.mantissa_at(common_expo)?;
+        let closing = mantissa
+            .checked_mul(pow10(expo - common_expo)?)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        Ok((opening, closing))
+    }
+}
+
+// `numerator / denominator` for a positive denominator, rounded toward negative or positive infinity
+fn divide_rounded(numerator: i128, denominator: i128, rounding: Rounding) -> Result<i64> {
+    let mut quotient = numerator.div_euclid(denominator);
+    if rounding == Rounding::Up && numerator.rem_euclid(denominator) != 0 {
+        quotient += 1;
+    }
+
+    i64::try_from(quotient).map_err(|_| error!(ErrorCode::MathOverflow))
+}
+
+pub(crate) fn pow10(exponent: i32) -> Result<i128> {
+    if exponent < 0 {
+        return Err(error!(ErrorCode::MathOverflow));
+    }
+    10i128.checked_pow(exponent as u32).ok_or_else(|| error!(ErrorCode::MathOverflow))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn price(mantissa: i64, expo: i32) -> Price {
+        Price {
+            mantissa,
+            expo,
+            ..Price::default()
+        }
+    }
+
+    #[test]
+    fn divide_rounded_leaves_exact_quotients_alone() {
+        assert_eq!(divide_rounded(10_000, 100, Rounding::Down).unwrap(), 100);
+        assert_eq!(divide_rounded(10_000, 100, Rounding::Up).unwrap(), 100);
+        assert_eq!(divide_rounded(-10_000, 100, Rounding::Down).unwrap(), -100);
+        assert_eq!(divide_rounded(-10_000, 100, Rounding::Up).unwrap(), -100);
+        assert_eq!(divide_rounded(0, 100, Rounding::Up).unwrap(), 0);
+    }
+
+    #[test]
+    fn divide_rounded_rounds_toward_the_requested_infinity() {
+        assert_eq!(divide_rounded(10_001, 100, Rounding::Down).unwrap(), 100);
+        assert_eq!(divide_rounded(10_001, 100, Rounding::Up).unwrap(), 101);
+        assert_eq!(divide_rounded(-10_001, 100, Rounding::Down).unwrap(), -101);
+        assert_eq!(divide_rounded(-10_001, 100, Rounding::Up).unwrap(), -100);
+    }
+
+    #[test]
+    fn divide_rounded_rejects_quotients_outside_an_i64() {
+        assert_eq!(divide_rounded(i64::MAX as i128, 1, Rounding::Up).unwrap(), i64::MAX);
+        assert_eq!(divide_rounded(i64::MIN as i128, 1, Rounding::Down).unwrap(), i64::MIN);
+        assert!(divide_rounded(i64::MAX as i128 + 1, 1, Rounding::Down).is_err());
+        assert!(divide_rounded(i64::MIN as i128 - 1, 1, Rounding::Up).is_err());
+        // Only rounding up carries it past the largest i64
+        assert_eq!(divide_rounded(i64::MAX as i128 * 2 + 1, 2, Rounding::Down).unwrap(), i64::MAX);
+        assert!(divide_rounded(i64::MAX as i128 * 2 + 1, 2, Rounding::Up).is_err());
+    }
+
+    #[test]
+    fn change_bps_to_is_exact_on_a_boundary() {
+        let opening = price(10_000, -2);
+        assert_eq!(opening.change_bps_to(10_100, -2, Rounding::Down).unwrap(), 100);
+        assert_eq!(opening.change_bps_to(10_100, -2, Rounding::Up).unwrap(), 100);
+        assert_eq!(opening.change_bps_to(9_900, -2, Rounding::Down).unwrap(), -100);
+        assert_eq!(opening.change_bps_to(9_900, -2, Rounding::Up).unwrap(), -100);
+        assert_eq!(opening.change_bps_to(10_000, -2, Rounding::Up).unwrap(), 0);
+    }
+
+    #[test]
+    fn change_bps_to_rounds_moves_between_basis_points() {
+        let opening = price(100_000, -3);
+        // A fall of 1.001%
+        assert_eq!(opening.change_bps_to(98_999, -3, Rounding::Down).unwrap(), -101);
+        assert_eq!(opening.change_bps_to(98_999, -3, Rounding::Up).unwrap(), -100);
+        // A rise of 0.999%
+        assert_eq!(opening.change_bps_to(100_999, -3, Rounding::Down).unwrap(), 99);
+        assert_eq!(opening.change_bps_to(100_999, -3, Rounding::Up).unwrap(), 100);
+    }
+
+    #[test]
+    fn change_bps_to_aligns_mismatched_exponents() {
+        // $100.00 to $101.0000000
+        assert_eq!(price(10_000, -2).change_bps_to(1_010_000_000, -7, Rounding::Down).unwrap(), 100);
+        // $725.45 to $726.00, a rise of 7.58 bps
+        let opening = price(72_545_000_000, -8);
+        assert_eq!(opening.change_bps_to(72_600, -2, Rounding::Down).unwrap(), 7);
+        assert_eq!(opening.change_bps_to(72_600, -2, Rounding::Up).unwrap(), 8);
+        // $250 to $260 with the closing price at a positive exponent
+        assert_eq!(price(250, 0).change_bps_to(26, 1, Rounding::Down).unwrap(), 400);
+    }
+
+    #[test]
+    fn change_bps_to_rejects_a_non_positive_opening_price() {
+        assert!(price(0, -2).change_bps_to(10_000, -2, Rounding::Down).is_err());
+        assert!(price(-10_000, -2).change_bps_to(10_000, -2, Rounding::Down).is_err());
+    }
+
+    #[test]
+    fn change_bps_to_rejects_overflow() {
+        // Aligning the exponents needs a power of ten past an i128
+        assert!(price(10_000, -10).change_bps_to(1, 40, Rounding::Down).is_err());
+        assert!(price(10_000, 30).change_bps_to(1, -30, Rounding::Down).is_err());
+        // Scaling the change to basis points
+        assert!(price(1, 0).change_bps_to(i128::MAX / 2, 0, Rounding::Down).is_err());
+        // A change too large for the i64 result
+        assert!(price(1, 0).change_bps_to(i64::MAX as i128, 0, Rounding::Down).is_err());
+    }
+
+    #[test]
+    fn spread_bps_to_subtracts_the_benchmark_change() {
+        // +2% against +0.5%
+        let (opening, benchmark) = (price(100, 0), price(200, 0));
+        assert_eq!(opening.spread_bps_to(102, 0, &benchmark, 201, 0, Rounding::Down).unwrap(), 150);
+        assert_eq!(opening.spread_bps_to(102, 0, &benchmark, 201, 0, Rounding::Up).unwrap(), 150);
+        // Level with the benchmark
+        assert_eq!(opening.spread_bps_to(102, 0, &benchmark, 204, 0, Rounding::Up).unwrap(), 0);
+    }
+
+    #[test]
+    fn spread_bps_to_rounds_the_spread_once() {
+        // +33.33% against a flat benchmark
+        assert_eq!(price(3, 0).spread_bps_to(4, 0, &price(1, 0), 1, 0, Rounding::Down).unwrap(), 3_333);
+        assert_eq!(price(3, 0).spread_bps_to(4, 0, &price(1, 0), 1, 0, Rounding::Up).unwrap(), 3_334);
+        // Flat against a benchmark up 33.33%
+        assert_eq!(price(100, 0).spread_bps_to(100, 0, &price(3, 0), 4, 0, Rounding::Down).unwrap(), -3_334);
+        assert_eq!(price(100, 0).spread_bps_to(100, 0, &price(3, 0), 4, 0, Rounding::Up).unwrap(), -3_333);
+    }
+
+    #[test]
+    fn spread_bps_to_aligns_mismatched_exponents() {
+        // $100.00 to $102.0000 against $200 to $201.00
+        let (opening, benchmark) = (price(10_000, -2), price(200, 0));
+        assert_eq!(opening.spread_bps_to(1_020_000, -4, &benchmark, 20_100, -2, Rounding::Down).unwrap(), 150);
+    }
+
+    #[test]
+    fn spread_bps_to_rejects_bad_prices_and_overflow() {
+        assert!(price(100, 0).spread_bps_to(102, 0, &price(0, 0), 201, 0, Rounding::Down).is_err());
+        assert!(price(0, 0).spread_bps_to(102, 0, &price(200, 0), 201, 0, Rounding::Down).is_err());
+        assert!(price(100, 0).spread_bps_to(102, 0, &price(200, -10), 201, 40, Rounding::Down).is_err());
+        // The cross product fits an i128 but not once it's scaled to basis points
+        let large = price(i64::MAX, 0);
+        assert!(large.spread_bps_to(i64::MAX as i128, 0, &large, 1, 0, Rounding::Down).is_err());
+    }
+}
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+
+declare_id!("9KhdAyJz82egQhZNhFvgGZZWEg2A9tZPgwfMNeqkYiCa");
+
+// Local test stand-in for oracle programs. Tests create accounts owned by this program,
+// write Pyth or Switchboard shaped bytes into them and point the juicy bets config at this program id.
+
+#[program]
+pub mod mock_oracle {
+    use super::*;
+
+    // Endpoint that overwrites part of an oracle account's data
+    pub fn write(ctx: Context<Write>, offset: u64, data: Vec<u8>) -> Result<()> {
+
+        let oracle_account = &ctx.accounts.oracle_account;
+        let mut account_data = oracle_account.try_borrow_mut_data()?;
+
+        let start = offset as usize;
+        let end = start.checked_add(data.len()).ok_or(ErrorCode::WriteOutOfBounds)?;
+        if end > account_data.len() {
+            return Err(error!(ErrorCode::WriteOutOfBounds));
+        }
+
+        account_data[start..end].copy_from_slice(&data);
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Write<'info> {
+    /// CHECK: Raw oracle account owned by this program
+    #[account(mut, owner = crate::ID)]
+    oracle_account: AccountInfo<'info>,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("The write goes past the end of the oracle account.")]
+    WriteOutOfBounds,
+}
@@ -2,12 +2,23 @@ import * as anchor from '@project-serum/anchor';
 import { Program } from '@project-serum/anchor';
 import { LAMPORTS_PER_SOL, SystemProgram } from "@solana/web3.js";
 import { JuicyBets } from '../target/types/juicy_bets';
+import { MockOracle } from '../target/types/mock_oracle';
 import assert from 'assert';
+import * as fs from 'fs';
 import { 
   calculateWinnings,
   findWagerForUser,
   matchingWagerFound
 } from '../app/utils'
+import {
+  createMockPythFeed,
+  createMockSwitchboardFeed,
+  nowInSeconds,
+  setMockPythPrice,
+  setMockSwitchboardRound,
+  signedPriceInstruction,
+  PYTH_STATUS_UNKNOWN,
+} from './utils/mock-oracle'
 
 describe('juicy-bets', () => {
 
@@ -19,12 +30,250 @@ describe('juicy-bets', () => {
   // Use that registered provider to create a new Program object that we can use in our tests
   const program = anchor.workspace.JuicyBets as Program<JuicyBets>;
 
+  // Stands in for the Pyth program on localnet
+  const mockOracle = anchor.workspace.MockOracle as Program<MockOracle>;
+
   const providerWallet = program.provider.wallet
 
   const JUICED_BETS_TAKE_RATE = 1.02
 
   const TICKERS = ["TSLA/USD", "SPY/USD", "AAPL/USD"];
 
+  // Bet states are zero-copy and store their enums as variant indexes
+  const BET_STATE_STATUSES = ["open", "closed", "settled", "voided"];
+  const BET_RANGES = [
+    "notAvailable",
+    "negThreeAndUnder",
+    "negThreeToNegTwo",
+    "negTwoToNegOne",
+    "negOneToZero",
+    "zeroToPosOne",
+    "posOneToPosTwo",
+    "posTwoToPosThree",
+    "posThreeAndOver",
+  ];
+  const DISPUTE_STATUSES = ["none", "pending", "upheld", "overturned"];
+  const ORACLE_KINDS = ["pyth", "switchboard", "signedFeed"];
+
+  // Symbols are stored as a fixed-size byte array padded with zeroes
+  const decodeSymbol = (symbol: number[]) => Buffer.from(symbol).toString("utf8").replace(/\0+$/, "");
+
+  let configPDA: anchor.web3.PublicKey;
+  let spyPriceFeed: anchor.web3.PublicKey;
+  let spyAssetPDA: anchor.web3.PublicKey;
+
+  // Whitelisted to sign off-chain prices
+  const pricePublisherKP = anchor.web3.Keypair.generate();
+
+  const sleep = (ms: number) => new Promise(resolve => setTimeout(resolve, ms));
+
+  const findBettorPosition = async (betStateKP, userAccountKP) => (await anchor.web3.PublicKey.findProgramAddress(
+    [Buffer.from("position"), betStateKP.publicKey.toBuffer(), userAccountKP.publicKey.toBuffer()],
+    program.programId
+  ))[0];
+
+  const findCreatorBondPDA = async (betStateKP) => (await anchor.web3.PublicKey.findProgramAddress(
+    [Buffer.from("creator_bond"), betStateKP.publicKey.toBuffer()],
+    program.programId
+  ))[0];
+
+  const findAssetPDA = async (symbol: string) => (await anchor.web3.PublicKey.findProgramAddress(
+    [Buffer.from("asset"), Buffer.from(symbol)],
+    program.programId
+  ))[0];
+
+  const findSeriesPDA = async (symbol: string) => (await anchor.web3.PublicKey.findProgramAddress(
+    [Buffer.from("series"), Buffer.from(symbol)],
+    program.programId
+  ))[0];
+
+  const findJackpotPDA = async (seriesPDA: anchor.web3.PublicKey) => (await anchor.web3.PublicKey.findProgramAddress(
+    [Buffer.from("jackpot"), seriesPDA.toBuffer()],
+    program.programId
+  ))[0];
+
+  const findRoundPDA = async (seriesPDA: anchor.web3.PublicKey, round: number) => (await anchor.web3.PublicKey.findProgramAddress(
+    [Buffer.from("round"), seriesPDA.toBuffer(), new anchor.BN(round).toArrayLike(Buffer, "le", 8)],
+    program.programId
+  ))[0];
+
+  // Clients only need the series' round counter to find the round that is open now
+  const advanceSeries = async (seriesPDA: anchor.web3.PublicKey, asset: anchor.web3.PublicKey, feed: anchor.web3.PublicKey) => {
+    const series = await program.account.marketSeries.fetch(seriesPDA);
+    const nextRound = await findRoundPDA(seriesPDA, series.round.toNumber() + 1);
+
+    await program.rpc.advanceSeries({
+      accounts: {
+        series: seriesPDA,
+        jackpot: await findJackpotPDA(seriesPDA),
+        currentRound: await findRoundPDA(seriesPDA, series.round.toNumber()),
+        nextRound,
+        nextCreatorBond: await findCreatorBondPDA({ publicKey: nextRound }),
+        asset,
+        oracleFeed: feed,
+        config: configPDA,
+        instructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+        keeper: providerWallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId
+      }
+    });
+
+    return { publicKey: nextRound };
+  };
+
+  // Open all day every day, so bet states can be created whenever the tests run
+  const ALWAYS_TRADING = { tradingDays: 0b1111111, openMinute: 0, closeMinute: 1440 };
+
+  const registerAsset = async (symbol: string, feed: anchor.web3.PublicKey, oracleKind: object = { pyth: {} }, bucketWidthBps = 100, defaultLimits = null) => {
+    const asset = await findAssetPDA(symbol);
+
+    await program.rpc.registerAsset(symbol, { equity: {} }, oracleKind, { bucketWidthBps }, defaultLimits, ALWAYS_TRADING, {
+      accounts: {
+        config: configPDA,
+        asset,
+        oracleFeed: feed,
+        admin: providerWallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId
+      }
+    });
+
+    return asset;
+  };
+
+  // Registry entries outlive a test run, so a symbol registered by an earlier run is pointed at this run's feed
+  const ensureAsset = async (symbol: string, feed: anchor.web3.PublicKey, oracleKind: object = { pyth: {} }) => {
+    const asset = await findAssetPDA(symbol);
+    if (await program.account.assetEntry.fetchNullable(asset) === null) {
+      return registerAsset(symbol, feed, oracleKind);
+    }
+
+    await program.rpc.setAssetOracle(oracleKind, {
+      accounts: {
+        config: configPDA,
+        asset,
+        oracleFeed: feed,
+        admin: providerWallet.publicKey,
+      }
+    });
+
+    return asset;
+  };
+
+  // Bet states have to be on their asset's feed, so every other feed gets its own symbol
+  const symbolForFeed = (feed: anchor.web3.PublicKey) => feed.equals(spyPriceFeed) ? 'SPY' : feed.toBase58().slice(0, 8);
+
+  const expectProgramError = async (promise: Promise<any>, code: string) => {
+    try {
+      await promise;
+    } catch (error) {
+      assert.equal(error.error?.errorCode?.code ?? error.code, code);
+      return;
+    }
+    assert.fail(`Expected the instruction to fail with ${code}`);
+  };
+
+  const createFundedUserAccount = async (lamportsToDeposit: number) => {
+    const userKP = anchor.web3.Keypair.generate();
+    const userAccountKP = anchor.web3.Keypair.generate();
+
+    const airdropSig = await program.provider.connection.requestAirdrop(userKP.publicKey, lamportsToDeposit + LAMPORTS_PER_SOL);
+    await program.provider.connection.confirmTransaction(airdropSig, "finalized");
+
+    await program.rpc.initializeUserAccount({
+      accounts: {
+        userAccount: userAccountKP.publicKey,
+        accountOwner: userKP.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId
+      },
+      signers: [userKP, userAccountKP]
+    });
+
+    await program.rpc.depositIntoAccount(new anchor.BN(lamportsToDeposit), {
+      accounts: {
+        userAccount: userAccountKP.publicKey,
+        accountOwner: userKP.publicKey
+      },
+      preInstructions: [
+        SystemProgram.transfer({
+          fromPubkey: userKP.publicKey,
+          lamports: lamportsToDeposit,
+          toPubkey: userAccountKP.publicKey
+        })
+      ],
+      signers: [userKP]
+    });
+
+    return { userKP, userAccountKP };
+  };
+
+  const placeWager = async (betStateKP, user, betRange: number, lamports: number) => {
+    const wagerDetailKP = anchor.web3.Keypair.generate();
+
+    await program.rpc.placeWager(betRange, new anchor.BN(Math.floor(lamports * JUICED_BETS_TAKE_RATE)), {
+      accounts: {
+        betState: betStateKP.publicKey,
+        wagerDetail: wagerDetailKP.publicKey,
+        userAccount: user.userAccountKP.publicKey,
+        bettorPosition: await findBettorPosition(betStateKP, user.userAccountKP),
+        config: configPDA,
+        treasury: providerWallet.publicKey,
+        bettorAccount: user.userKP.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId
+      },
+      signers: [user.userKP, wagerDetailKP]
+    });
+
+    return wagerDetailKP;
+  };
+
+  // The config is a singleton PDA, so only the first run against a validator creates it
+  before(async () => {
+    [configPDA] = await anchor.web3.PublicKey.findProgramAddress([Buffer.from("config")], program.programId);
+
+    spyPriceFeed = await createMockPythFeed(mockOracle, { price: 72545000000, conf: 1000000, expo: -8 });
+
+    if (await program.account.globalConfig.fetchNullable(configPDA) === null) {
+      await program.rpc.initializeConfig(
+        {
+          minWager: new anchor.BN(0),
+          maxWager: new anchor.BN(0),
+          maxUserStake: new anchor.BN(0),
+          maxTotalPool: new anchor.BN(0),
+          maxBucketShareBps: 0,
+        },
+        mockOracle.programId,
+        mockOracle.programId,
+        {
+          maxStalenessSecs: new anchor.BN(60),
+          maxConfidenceBps: 0,
+          retryWindowSecs: new anchor.BN(60 * 60),
+        },
+        {
+          accounts: {
+            config: configPDA,
+            admin: providerWallet.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId
+          }
+        }
+      );
+    }
+
+    await program.rpc.setSignedFeedPublishers([pricePublisherKP.publicKey], {
+      accounts: {
+        config: configPDA,
+        admin: providerWallet.publicKey,
+      }
+    });
+
+    // Bet states can only be created on registered symbols, most tests bet on SPY
+    spyAssetPDA = await ensureAsset('SPY', spyPriceFeed);
+  });
+
+  // Snapshot prices are read from the feed when a bet state is created, so keep it fresh for every test
+  beforeEach(async () => {
+    await setMockPythPrice(mockOracle, spyPriceFeed, { price: 72545000000, conf: 1000000, expo: -8 });
+  });
+
 
   // E2E Betting Tests //
 
@@ -42,14 +291,31 @@ describe('juicy-bets', () => {
     console.log("Starting the 'initialize bet state' functionality...");
 
     await program.rpc.initializeBetState(
-      start,
-      duration,
-      'SPY',
-      new anchor.BN(725.45 * 1000),
+      {
+        start,
+        duration,
+        symbol: 'SPY',
+        limits: null,
+        bettingCutoff: new anchor.BN(0),
+        oracleGuards: null,
+        oracleKind: { pyth: {} },
+        secondaryOracles: [],
+        oracleQuorum: 1,
+        boundaryRule: { lowerInclusive: {} },
+        straddlePolicy: { reject: {} },
+        resolverCommittee: null,
+        creatorFeeBps: 0,
+        marketType: { range: {} },
+      },
       {
         accounts: {
           betState: betStateKP.publicKey,
           betCreator: providerWallet.publicKey,
+          config: configPDA,
+          asset: spyAssetPDA,
+          oracleFeed: spyPriceFeed,
+          instructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+          creatorBond: await findCreatorBondPDA(betStateKP),
           systemProgram: anchor.web3.SystemProgram.programId
         },
         signers:[betStateKP]
@@ -63,16 +329,16 @@ describe('juicy-bets', () => {
     assert.ok(betStateAccount);
     assert.ok(betStateAccount.staticTotalPool.eq(new anchor.BN(0)));
     assert.ok(betStateAccount.runningTotalPool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.negThreeAndUnderPool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.negThreeToNegTwoPool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.negTwoToNegOnePool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.negOneToZeroPool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.zeroToPosOnePool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.posOneToPosTwoPool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.posTwoToPosThreePool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.posThreeAndOverPool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.status.hasOwnProperty("open"));
-    assert.ok(betStateAccount.winningBetRange.hasOwnProperty("notAvailable"));
+    assert.ok(betStateAccount.pools[0].eq(new anchor.BN(0)));
+    assert.ok(betStateAccount.pools[1].eq(new anchor.BN(0)));
+    assert.ok(betStateAccount.pools[2].eq(new anchor.BN(0)));
+    assert.ok(betStateAccount.pools[3].eq(new anchor.BN(0)));
+    assert.ok(betStateAccount.pools[4].eq(new anchor.BN(0)));
+    assert.ok(betStateAccount.pools[5].eq(new anchor.BN(0)));
+    assert.ok(betStateAccount.pools[6].eq(new anchor.BN(0)));
+    assert.ok(betStateAccount.pools[7].eq(new anchor.BN(0)));
+    assert.equal(BET_STATE_STATUSES[betStateAccount.status], "open");
+    assert.equal(BET_RANGES[betStateAccount.winningBetRange], "notAvailable");
 
 
 
@@ -156,6 +422,9 @@ describe('juicy-bets', () => {
         betState: betStateKP.publicKey,
         wagerDetail: wagerDetail1KP.publicKey,
         userAccount: user1AccountKP.publicKey,
+        bettorPosition: await findBettorPosition(betStateKP, user1AccountKP),
+        config: configPDA,
+        treasury: providerWallet.publicKey,
         bettorAccount: bettorKP.publicKey,
         betCreator: providerWallet.publicKey,
         systemProgram: anchor.web3.SystemProgram.programId
@@ -183,14 +452,14 @@ describe('juicy-bets', () => {
     // Test the newly modified bet state obj with the updates from the placed wager
     console.log(`Bet state static total pool after first wager placement: ${betStateAfterFirstWager.staticTotalPool.toNumber()/LAMPORTS_PER_SOL}`);
     console.log(`Bet state running total pool after first wager placement: ${betStateAfterFirstWager.runningTotalPool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state NegThreeAndUnder pool after first wager placement: ${betStateAfterFirstWager.negThreeAndUnderPool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state NegThreeToNegTwo pool after first wager placement: ${betStateAfterFirstWager.negThreeToNegTwoPool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state NegTwoToNegOne pool after first wager placement: ${betStateAfterFirstWager.negTwoToNegOnePool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state NegOneToZero pool after first wager placement: ${betStateAfterFirstWager.negOneToZeroPool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state ZeroToPosOne pool after first wager placement: ${betStateAfterFirstWager.zeroToPosOnePool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state PosOneToPosTwo pool after first wager placement: ${betStateAfterFirstWager.posOneToPosTwoPool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state PosTwoToPosThree pool after first wager placement: ${betStateAfterFirstWager.posTwoToPosThreePool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state PosThreeAndOver pool after first wager placement: ${betStateAfterFirstWager.posThreeAndOverPool.toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state NegThreeAndUnder pool after first wager placement: ${betStateAfterFirstWager.pools[0].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state NegThreeToNegTwo pool after first wager placement: ${betStateAfterFirstWager.pools[1].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state NegTwoToNegOne pool after first wager placement: ${betStateAfterFirstWager.pools[2].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state NegOneToZero pool after first wager placement: ${betStateAfterFirstWager.pools[3].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state ZeroToPosOne pool after first wager placement: ${betStateAfterFirstWager.pools[4].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state PosOneToPosTwo pool after first wager placement: ${betStateAfterFirstWager.pools[5].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state PosTwoToPosThree pool after first wager placement: ${betStateAfterFirstWager.pools[6].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state PosThreeAndOver pool after first wager placement: ${betStateAfterFirstWager.pools[7].toNumber()/LAMPORTS_PER_SOL}`);
     
     console.log(`Bet State lamports after first wager placement: ${await program.provider.connection.getBalance(betStateKP.publicKey)/LAMPORTS_PER_SOL}`)
 
@@ -206,6 +475,7 @@ describe('juicy-bets', () => {
           betState: betStateKP.publicKey,
           wagerDetail: wagerDetail1KP.publicKey,
           userAccount: user1AccountKP.publicKey,
+          bettorPosition: await findBettorPosition(betStateKP, user1AccountKP),
           bettor: bettorKP.publicKey,
         },
         signers: [bettorKP]
@@ -228,16 +498,16 @@ describe('juicy-bets', () => {
     assert.equal(wagerDetailPAPostCancel.length, 0);
     assert.ok(betStateAfterWagerCancellation.staticTotalPool.eq(new anchor.BN(0)));
     assert.ok(betStateAfterWagerCancellation.runningTotalPool.eq(new anchor.BN(0)));
-    assert.ok(betStateAfterWagerCancellation.negThreeAndUnderPool.eq(new anchor.BN(0)));
-    assert.ok(betStateAfterWagerCancellation.negThreeToNegTwoPool.eq(new anchor.BN(0)));
-    assert.ok(betStateAfterWagerCancellation.negTwoToNegOnePool.eq(new anchor.BN(0)));
-    assert.ok(betStateAfterWagerCancellation.negOneToZeroPool.eq(new anchor.BN(0)));
-    assert.ok(betStateAfterWagerCancellation.zeroToPosOnePool.eq(new anchor.BN(0)));
-    assert.ok(betStateAfterWagerCancellation.posOneToPosTwoPool.eq(new anchor.BN(0)));
-    assert.ok(betStateAfterWagerCancellation.posTwoToPosThreePool.eq(new anchor.BN(0)));
-    assert.ok(betStateAfterWagerCancellation.posThreeAndOverPool.eq(new anchor.BN(0)));
-    assert.ok(betStateAfterWagerCancellation.status.hasOwnProperty("open"));
-    assert.ok(betStateAfterWagerCancellation.winningBetRange.hasOwnProperty("notAvailable"));
+    assert.ok(betStateAfterWagerCancellation.pools[0].eq(new anchor.BN(0)));
+    assert.ok(betStateAfterWagerCancellation.pools[1].eq(new anchor.BN(0)));
+    assert.ok(betStateAfterWagerCancellation.pools[2].eq(new anchor.BN(0)));
+    assert.ok(betStateAfterWagerCancellation.pools[3].eq(new anchor.BN(0)));
+    assert.ok(betStateAfterWagerCancellation.pools[4].eq(new anchor.BN(0)));
+    assert.ok(betStateAfterWagerCancellation.pools[5].eq(new anchor.BN(0)));
+    assert.ok(betStateAfterWagerCancellation.pools[6].eq(new anchor.BN(0)));
+    assert.ok(betStateAfterWagerCancellation.pools[7].eq(new anchor.BN(0)));
+    assert.equal(BET_STATE_STATUSES[betStateAfterWagerCancellation.status], "open");
+    assert.equal(BET_RANGES[betStateAfterWagerCancellation.winningBetRange], "notAvailable");
 
     console.log(`Bet State lamports after cancellation: ${await program.provider.connection.getBalance(betStateKP.publicKey)/LAMPORTS_PER_SOL}`);
     console.log(`User Account lamports after cancellation: ${await program.provider.connection.getBalance(user1AccountKP.publicKey)/LAMPORTS_PER_SOL}`);
@@ -245,6 +515,7 @@ describe('juicy-bets', () => {
     console.log(`Bettor 1 User Account Balance after wager: ${userAccountAfterWagerCancellation.currentBalance.toNumber()/LAMPORTS_PER_SOL}`);
     assert.equal(userAccountAfterWagerCancellation.activeWagers.length, 0);
     assert.equal(userAccountAfterWagerCancellation.betsInvolved.length, 0);
+    assert.equal(await program.account.bettorPosition.fetchNullable(await findBettorPosition(betStateKP, user1AccountKP)), null);
 
   });
     
@@ -262,14 +533,31 @@ describe('juicy-bets', () => {
     console.log('--------------------')
 
     await program.rpc.initializeBetState(
-      start,
-      duration,
-      'SPY',
-      new anchor.BN(725.45 * 1000),
+      {
+        start,
+        duration,
+        symbol: 'SPY',
+        limits: null,
+        bettingCutoff: new anchor.BN(0),
+        oracleGuards: null,
+        oracleKind: { pyth: {} },
+        secondaryOracles: [],
+        oracleQuorum: 1,
+        boundaryRule: { lowerInclusive: {} },
+        straddlePolicy: { reject: {} },
+        resolverCommittee: null,
+        creatorFeeBps: 0,
+        marketType: { range: {} },
+      },
       {
         accounts: {
           betState: betStateKP.publicKey,
           betCreator: providerWallet.publicKey,
+          config: configPDA,
+          asset: spyAssetPDA,
+          oracleFeed: spyPriceFeed,
+          instructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+          creatorBond: await findCreatorBondPDA(betStateKP),
           systemProgram: anchor.web3.SystemProgram.programId
         },
         signers:[betStateKP]
@@ -285,16 +573,16 @@ describe('juicy-bets', () => {
     assert.ok(betStateAccount);
     assert.ok(betStateAccount.staticTotalPool.eq(new anchor.BN(0)));
     assert.ok(betStateAccount.runningTotalPool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.negThreeAndUnderPool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.negThreeToNegTwoPool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.negTwoToNegOnePool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.negOneToZeroPool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.zeroToPosOnePool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.posOneToPosTwoPool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.posTwoToPosThreePool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.posThreeAndOverPool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.status.hasOwnProperty("open"));
-    assert.ok(betStateAccount.winningBetRange.hasOwnProperty("notAvailable"));
+    assert.ok(betStateAccount.pools[0].eq(new anchor.BN(0)));
+    assert.ok(betStateAccount.pools[1].eq(new anchor.BN(0)));
+    assert.ok(betStateAccount.pools[2].eq(new anchor.BN(0)));
+    assert.ok(betStateAccount.pools[3].eq(new anchor.BN(0)));
+    assert.ok(betStateAccount.pools[4].eq(new anchor.BN(0)));
+    assert.ok(betStateAccount.pools[5].eq(new anchor.BN(0)));
+    assert.ok(betStateAccount.pools[6].eq(new anchor.BN(0)));
+    assert.ok(betStateAccount.pools[7].eq(new anchor.BN(0)));
+    assert.equal(BET_STATE_STATUSES[betStateAccount.status], "open");
+    assert.equal(BET_RANGES[betStateAccount.winningBetRange], "notAvailable");
 
     console.log('--------------------')
     console.log(`Bet Creator Sol Balance post bet init: ${await program.provider.connection.getBalance(providerWallet.publicKey)/LAMPORTS_PER_SOL}`)
@@ -385,6 +673,9 @@ describe('juicy-bets', () => {
         betState: betStateKP.publicKey,
         wagerDetail: wagerDetail1KP.publicKey,
         userAccount: user1AccountKP.publicKey,
+        bettorPosition: await findBettorPosition(betStateKP, user1AccountKP),
+        config: configPDA,
+        treasury: providerWallet.publicKey,
         bettorAccount: user1KP.publicKey,
         betCreator: providerWallet.publicKey,
         systemProgram: anchor.web3.SystemProgram.programId
@@ -413,14 +704,14 @@ describe('juicy-bets', () => {
     // Test the newly modified bet state obj with the updates from the placed wager
     console.log(`Bet state static total pool after first wager placement: ${betStateAfterFirstWager.staticTotalPool.toNumber()/LAMPORTS_PER_SOL}`);
     console.log(`Bet state running total pool after first wager placement: ${betStateAfterFirstWager.runningTotalPool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state NegThreeAndUnder pool after first wager placement: ${betStateAfterFirstWager.negThreeAndUnderPool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state NegThreeToNegTwo pool after first wager placement: ${betStateAfterFirstWager.negThreeToNegTwoPool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state NegTwoToNegOne pool after first wager placement: ${betStateAfterFirstWager.negTwoToNegOnePool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state NegOneToZero pool after first wager placement: ${betStateAfterFirstWager.negOneToZeroPool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state ZeroToPosOne pool after first wager placement: ${betStateAfterFirstWager.zeroToPosOnePool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state PosOneToPosTwo pool after first wager placement: ${betStateAfterFirstWager.posOneToPosTwoPool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state PosTwoToPosThree pool after first wager placement: ${betStateAfterFirstWager.posTwoToPosThreePool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state PosThreeAndOver pool after first wager placement: ${betStateAfterFirstWager.posThreeAndOverPool.toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state NegThreeAndUnder pool after first wager placement: ${betStateAfterFirstWager.pools[0].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state NegThreeToNegTwo pool after first wager placement: ${betStateAfterFirstWager.pools[1].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state NegTwoToNegOne pool after first wager placement: ${betStateAfterFirstWager.pools[2].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state NegOneToZero pool after first wager placement: ${betStateAfterFirstWager.pools[3].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state ZeroToPosOne pool after first wager placement: ${betStateAfterFirstWager.pools[4].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state PosOneToPosTwo pool after first wager placement: ${betStateAfterFirstWager.pools[5].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state PosTwoToPosThree pool after first wager placement: ${betStateAfterFirstWager.pools[6].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state PosThreeAndOver pool after first wager placement: ${betStateAfterFirstWager.pools[7].toNumber()/LAMPORTS_PER_SOL}`);
     
     console.log(`Bet State lamports after first wager placement: ${await program.provider.connection.getBalance(betStateKP.publicKey)/LAMPORTS_PER_SOL}`)
 
@@ -512,6 +803,9 @@ describe('juicy-bets', () => {
         betState: betStateKP.publicKey,
         wagerDetail: wagerDetail2KP.publicKey,
         userAccount: user2AccountKP.publicKey,
+        bettorPosition: await findBettorPosition(betStateKP, user2AccountKP),
+        config: configPDA,
+        treasury: providerWallet.publicKey,
         bettorAccount: user2KP.publicKey,
         betCreator: providerWallet.publicKey,
         systemProgram: anchor.web3.SystemProgram.programId
@@ -540,14 +834,14 @@ describe('juicy-bets', () => {
     // Test the newly modified bet state obj with the updates from the placed wager
     console.log(`Bet state static total pool after second wager placement: ${betStateAfterSecondWager.staticTotalPool.toNumber()/LAMPORTS_PER_SOL}`);
     console.log(`Bet state running total pool after second wager placement: ${betStateAfterSecondWager.runningTotalPool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state NegThreeAndUnder pool after second wager placement: ${betStateAfterSecondWager.negThreeAndUnderPool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state NegThreeToNegTwo pool after second wager placement: ${betStateAfterSecondWager.negThreeToNegTwoPool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state NegTwoToNegOne pool after second wager placement: ${betStateAfterSecondWager.negTwoToNegOnePool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state NegOneToZero pool after second wager placement: ${betStateAfterSecondWager.negOneToZeroPool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state ZeroToPosOne pool after second wager placement: ${betStateAfterSecondWager.zeroToPosOnePool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state PosOneToPosTwo pool after second wager placement: ${betStateAfterSecondWager.posOneToPosTwoPool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state PosTwoToPosThree pool after second wager placement: ${betStateAfterSecondWager.posTwoToPosThreePool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state PosThreeAndOver pool after second wager placement: ${betStateAfterSecondWager.posThreeAndOverPool.toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state NegThreeAndUnder pool after second wager placement: ${betStateAfterSecondWager.pools[0].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state NegThreeToNegTwo pool after second wager placement: ${betStateAfterSecondWager.pools[1].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state NegTwoToNegOne pool after second wager placement: ${betStateAfterSecondWager.pools[2].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state NegOneToZero pool after second wager placement: ${betStateAfterSecondWager.pools[3].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state ZeroToPosOne pool after second wager placement: ${betStateAfterSecondWager.pools[4].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state PosOneToPosTwo pool after second wager placement: ${betStateAfterSecondWager.pools[5].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state PosTwoToPosThree pool after second wager placement: ${betStateAfterSecondWager.pools[6].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state PosThreeAndOver pool after second wager placement: ${betStateAfterSecondWager.pools[7].toNumber()/LAMPORTS_PER_SOL}`);
     console.log(`Bet State lamports after second wager placement: ${await program.provider.connection.getBalance(betStateKP.publicKey)/LAMPORTS_PER_SOL}`)
 
 
@@ -639,6 +933,9 @@ describe('juicy-bets', () => {
         betState: betStateKP.publicKey,
         wagerDetail: wagerDetail3KP.publicKey,
         userAccount: user3AccountKP.publicKey,
+        bettorPosition: await findBettorPosition(betStateKP, user3AccountKP),
+        config: configPDA,
+        treasury: providerWallet.publicKey,
         bettorAccount: user3KP.publicKey,
         betCreator: providerWallet.publicKey,
         systemProgram: anchor.web3.SystemProgram.programId
@@ -667,14 +964,14 @@ describe('juicy-bets', () => {
     // Test the newly modified bet state obj with the updates from the placed wager
     console.log(`Bet state static total pool after third wager placement: ${betStateAfterThirdWager.staticTotalPool.toNumber()/LAMPORTS_PER_SOL}`);
     console.log(`Bet state running total pool after third wager placement: ${betStateAfterThirdWager.runningTotalPool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state NegThreeAndUnder pool after third wager placement: ${betStateAfterThirdWager.negThreeAndUnderPool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state NegThreeToNegTwo pool after third wager placement: ${betStateAfterThirdWager.negThreeToNegTwoPool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state NegTwoToNegOne pool after third wager placement: ${betStateAfterThirdWager.negTwoToNegOnePool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state NegOneToZero pool after third wager placement: ${betStateAfterThirdWager.negOneToZeroPool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state ZeroToPosOne pool after third wager placement: ${betStateAfterThirdWager.zeroToPosOnePool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state PosOneToPosTwo pool after third wager placement: ${betStateAfterThirdWager.posOneToPosTwoPool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state PosTwoToPosThree pool after third wager placement: ${betStateAfterThirdWager.posTwoToPosThreePool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state PosThreeAndOver pool after third wager placement: ${betStateAfterThirdWager.posThreeAndOverPool.toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state NegThreeAndUnder pool after third wager placement: ${betStateAfterThirdWager.pools[0].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state NegThreeToNegTwo pool after third wager placement: ${betStateAfterThirdWager.pools[1].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state NegTwoToNegOne pool after third wager placement: ${betStateAfterThirdWager.pools[2].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state NegOneToZero pool after third wager placement: ${betStateAfterThirdWager.pools[3].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state ZeroToPosOne pool after third wager placement: ${betStateAfterThirdWager.pools[4].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state PosOneToPosTwo pool after third wager placement: ${betStateAfterThirdWager.pools[5].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state PosTwoToPosThree pool after third wager placement: ${betStateAfterThirdWager.pools[6].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state PosThreeAndOver pool after third wager placement: ${betStateAfterThirdWager.pools[7].toNumber()/LAMPORTS_PER_SOL}`);
     console.log(`Bet State lamports after third wager placement: ${await program.provider.connection.getBalance(betStateKP.publicKey)/LAMPORTS_PER_SOL}`)
 
 
@@ -682,25 +979,24 @@ describe('juicy-bets', () => {
 
     ///// ***** CLOSING A BET STATE ***** /////
 
-    const end_time = new anchor.BN(Date.now());
-
     const betStateBeforeClosing = await program.account.betState.fetch(betStateKP.publicKey);
-    assert.ok(betStateBeforeClosing.status.hasOwnProperty("open"));
+    assert.equal(BET_STATE_STATUSES[betStateBeforeClosing.status], "open");
 
     await program.rpc.closeBetState(
-      end_time,
       {
         accounts: {
           betState: betStateKP.publicKey,
-          betCreator: providerWallet.publicKey
+          config: configPDA,
+          resolver: providerWallet.publicKey
         }
       }
     )
 
     const betStateAfterClosing = await program.account.betState.fetch(betStateKP.publicKey);
     assert.ok(betStateAfterClosing);
-    assert.ok(betStateAfterClosing.winningBetRange.hasOwnProperty("notAvailable"));
-    assert.ok(betStateAfterClosing.status.hasOwnProperty("closed"));
+    assert.equal(BET_RANGES[betStateAfterClosing.winningBetRange], "notAvailable");
+    assert.equal(BET_STATE_STATUSES[betStateAfterClosing.status], "closed");
+    assert.ok(betStateAfterClosing.endTime.eq(betStateBeforeClosing.endTime));
 
 
 
@@ -712,15 +1008,16 @@ describe('juicy-bets', () => {
       {
         accounts: {
           betState: betStateKP.publicKey,
-          betCreator: providerWallet.publicKey
+          config: configPDA,
+          resolver: providerWallet.publicKey
         }
       }
     )
 
     const betStateAfterBetOutcomeDecided = await program.account.betState.fetch(betStateKP.publicKey);
     assert.ok(betStateAfterBetOutcomeDecided);
-    console.log(`Bet state winnings bet range after 3 bets: ${JSON.stringify(betStateAfterBetOutcomeDecided.winningBetRange, null, 2)}`)
-    assert.ok(betStateAfterBetOutcomeDecided.winningBetRange.hasOwnProperty("negThreeToNegTwo"));
+    console.log(`Bet state winnings bet range after 3 bets: ${BET_RANGES[betStateAfterBetOutcomeDecided.winningBetRange]}`)
+    assert.equal(BET_RANGES[betStateAfterBetOutcomeDecided.winningBetRange], "negThreeToNegTwo");
 
 
 
@@ -760,12 +1057,12 @@ describe('juicy-bets', () => {
 
     // TODO: Abstract into utils
     await program.rpc.claimWinnings(
-      bettor1Winnings,
       {
         accounts: {
           betState: betStateKP.publicKey,
           wagerDetail: wagerDetail1KP.publicKey,
           userAccount: user1AccountKP.publicKey,
+          bettorPosition: await findBettorPosition(betStateKP, user1AccountKP),
           bettor: user1KP.publicKey
         },
         signers:[user1KP]
@@ -788,12 +1085,12 @@ describe('juicy-bets', () => {
     ///// ***** LAST BETTOR CLAIMS WINNINGS ***** /////
 
     await program.rpc.claimWinnings(
-      bettor2Winnings,
       {
         accounts: {
           betState: betStateKP.publicKey,
           wagerDetail: wagerDetail2KP.publicKey,
           userAccount: user2AccountKP.publicKey,
+          bettorPosition: await findBettorPosition(betStateKP, user2AccountKP),
           bettor: user2KP.publicKey
         },
         signers:[user2KP]
@@ -820,6 +1117,7 @@ describe('juicy-bets', () => {
           betState: betStateKP.publicKey,
           wagerDetail: wagerDetail3KP.publicKey,
           userAccount: user3AccountKP.publicKey,
+          bettorPosition: await findBettorPosition(betStateKP, user3AccountKP),
           bettor: user3KP.publicKey 
         },
         signers:[user3KP]
@@ -834,6 +1132,11 @@ describe('juicy-bets', () => {
     assert.equal(user2AccountPostClaim.activeWagers.length, 0);
     assert.equal(user3AccountPostClaim.activeWagers.length, 0);
 
+    // Each bettor's position is closed back to them with their last wager on the bet state
+    for (const userAccountKP of [user1AccountKP, user2AccountKP, user3AccountKP]) {
+      assert.equal(await program.account.bettorPosition.fetchNullable(await findBettorPosition(betStateKP, userAccountKP)), null);
+    }
+
 
     ///// ***** BET CREATOR SETTLES BET WHEN ALL WINNINGS ARE CLAIMED ***** /////
 
@@ -841,6 +1144,7 @@ describe('juicy-bets', () => {
       {
         accounts: {
           betState: betStateKP.publicKey,
+          creatorBond: await findCreatorBondPDA(betStateKP),
           betCreator: providerWallet.publicKey,
         }
       }
@@ -876,14 +1180,31 @@ describe('juicy-bets', () => {
     console.log('--------------------')
 
     await program.rpc.initializeBetState(
-      start,
-      duration,
-      'SPY',
-      new anchor.BN(725.45 * 1000),
+      {
+        start,
+        duration,
+        symbol: 'SPY',
+        limits: null,
+        bettingCutoff: new anchor.BN(0),
+        oracleGuards: null,
+        oracleKind: { pyth: {} },
+        secondaryOracles: [],
+        oracleQuorum: 1,
+        boundaryRule: { lowerInclusive: {} },
+        straddlePolicy: { reject: {} },
+        resolverCommittee: null,
+        creatorFeeBps: 0,
+        marketType: { range: {} },
+      },
       {
         accounts: {
           betState: betStateKP.publicKey,
           betCreator: providerWallet.publicKey,
+          config: configPDA,
+          asset: spyAssetPDA,
+          oracleFeed: spyPriceFeed,
+          instructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+          creatorBond: await findCreatorBondPDA(betStateKP),
           systemProgram: anchor.web3.SystemProgram.programId
         },
         signers:[betStateKP]
@@ -899,16 +1220,16 @@ describe('juicy-bets', () => {
     assert.ok(betStateAccount);
     assert.ok(betStateAccount.staticTotalPool.eq(new anchor.BN(0)));
     assert.ok(betStateAccount.runningTotalPool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.negThreeAndUnderPool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.negThreeToNegTwoPool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.negTwoToNegOnePool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.negOneToZeroPool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.zeroToPosOnePool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.posOneToPosTwoPool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.posTwoToPosThreePool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.posThreeAndOverPool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.status.hasOwnProperty("open"));
-    assert.ok(betStateAccount.winningBetRange.hasOwnProperty("notAvailable"));
+    assert.ok(betStateAccount.pools[0].eq(new anchor.BN(0)));
+    assert.ok(betStateAccount.pools[1].eq(new anchor.BN(0)));
+    assert.ok(betStateAccount.pools[2].eq(new anchor.BN(0)));
+    assert.ok(betStateAccount.pools[3].eq(new anchor.BN(0)));
+    assert.ok(betStateAccount.pools[4].eq(new anchor.BN(0)));
+    assert.ok(betStateAccount.pools[5].eq(new anchor.BN(0)));
+    assert.ok(betStateAccount.pools[6].eq(new anchor.BN(0)));
+    assert.ok(betStateAccount.pools[7].eq(new anchor.BN(0)));
+    assert.equal(BET_STATE_STATUSES[betStateAccount.status], "open");
+    assert.equal(BET_RANGES[betStateAccount.winningBetRange], "notAvailable");
 
     console.log('--------------------')
     console.log(`Bet Creator Sol Balance post bet init: ${await program.provider.connection.getBalance(providerWallet.publicKey)/LAMPORTS_PER_SOL}`)
@@ -999,6 +1320,9 @@ describe('juicy-bets', () => {
         betState: betStateKP.publicKey,
         wagerDetail: wagerDetail1KP.publicKey,
         userAccount: user1AccountKP.publicKey,
+        bettorPosition: await findBettorPosition(betStateKP, user1AccountKP),
+        config: configPDA,
+        treasury: providerWallet.publicKey,
         bettorAccount: user1KP.publicKey,
         betCreator: providerWallet.publicKey,
         systemProgram: anchor.web3.SystemProgram.programId
@@ -1027,39 +1351,37 @@ describe('juicy-bets', () => {
     // Test the newly modified bet state obj with the updates from the placed wager
     console.log(`Bet state static total pool after first wager placement: ${betStateAfterFirstWager.staticTotalPool.toNumber()/LAMPORTS_PER_SOL}`);
     console.log(`Bet state running total pool after first wager placement: ${betStateAfterFirstWager.runningTotalPool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state NegThreeAndUnder pool after first wager placement: ${betStateAfterFirstWager.negThreeAndUnderPool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state NegThreeToNegTwo pool after first wager placement: ${betStateAfterFirstWager.negThreeToNegTwoPool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state NegTwoToNegOne pool after first wager placement: ${betStateAfterFirstWager.negTwoToNegOnePool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state NegOneToZero pool after first wager placement: ${betStateAfterFirstWager.negOneToZeroPool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state ZeroToPosOne pool after first wager placement: ${betStateAfterFirstWager.zeroToPosOnePool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state PosOneToPosTwo pool after first wager placement: ${betStateAfterFirstWager.posOneToPosTwoPool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state PosTwoToPosThree pool after first wager placement: ${betStateAfterFirstWager.posTwoToPosThreePool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state PosThreeAndOver pool after first wager placement: ${betStateAfterFirstWager.posThreeAndOverPool.toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state NegThreeAndUnder pool after first wager placement: ${betStateAfterFirstWager.pools[0].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state NegThreeToNegTwo pool after first wager placement: ${betStateAfterFirstWager.pools[1].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state NegTwoToNegOne pool after first wager placement: ${betStateAfterFirstWager.pools[2].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state NegOneToZero pool after first wager placement: ${betStateAfterFirstWager.pools[3].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state ZeroToPosOne pool after first wager placement: ${betStateAfterFirstWager.pools[4].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state PosOneToPosTwo pool after first wager placement: ${betStateAfterFirstWager.pools[5].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state PosTwoToPosThree pool after first wager placement: ${betStateAfterFirstWager.pools[6].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state PosThreeAndOver pool after first wager placement: ${betStateAfterFirstWager.pools[7].toNumber()/LAMPORTS_PER_SOL}`);
     
     console.log(`Bet State lamports after first wager placement: ${await program.provider.connection.getBalance(betStateKP.publicKey)/LAMPORTS_PER_SOL}`)
 
 
     ///// ***** CLOSING A BET STATE ***** /////
 
-    const end_time = new anchor.BN(Date.now());
-
     const betStateBeforeClosing = await program.account.betState.fetch(betStateKP.publicKey);
-    assert.ok(betStateBeforeClosing.status.hasOwnProperty("open"));
+    assert.equal(BET_STATE_STATUSES[betStateBeforeClosing.status], "open");
 
     await program.rpc.closeBetState(
-      end_time,
       {
         accounts: {
           betState: betStateKP.publicKey,
-          betCreator: providerWallet.publicKey
+          config: configPDA,
+          resolver: providerWallet.publicKey
         }
       }
     )
 
     const betStateAfterClosing = await program.account.betState.fetch(betStateKP.publicKey);
     assert.ok(betStateAfterClosing);
-    assert.ok(betStateAfterClosing.winningBetRange.hasOwnProperty("notAvailable"));
-    assert.ok(betStateAfterClosing.status.hasOwnProperty("closed"));
+    assert.equal(BET_RANGES[betStateAfterClosing.winningBetRange], "notAvailable");
+    assert.equal(BET_STATE_STATUSES[betStateAfterClosing.status], "closed");
 
     try {
 
@@ -1142,6 +1464,9 @@ describe('juicy-bets', () => {
           betState: betStateKP.publicKey,
           wagerDetail: wagerDetail2KP.publicKey,
           userAccount: user2AccountKP.publicKey,
+          bettorPosition: await findBettorPosition(betStateKP, user2AccountKP),
+          config: configPDA,
+          treasury: providerWallet.publicKey,
           bettorAccount: user2KP.publicKey,
           betCreator: providerWallet.publicKey,
           systemProgram: anchor.web3.SystemProgram.programId
@@ -1174,14 +1499,31 @@ describe('juicy-bets', () => {
     console.log('--------------------')
 
     await program.rpc.initializeBetState(
-      start,
-      duration,
-      'SPY',
-      new anchor.BN(725.45 * 1000),
+      {
+        start,
+        duration,
+        symbol: 'SPY',
+        limits: null,
+        bettingCutoff: new anchor.BN(0),
+        oracleGuards: null,
+        oracleKind: { pyth: {} },
+        secondaryOracles: [],
+        oracleQuorum: 1,
+        boundaryRule: { lowerInclusive: {} },
+        straddlePolicy: { reject: {} },
+        resolverCommittee: null,
+        creatorFeeBps: 0,
+        marketType: { range: {} },
+      },
       {
         accounts: {
           betState: betStateKP.publicKey,
           betCreator: providerWallet.publicKey,
+          config: configPDA,
+          asset: spyAssetPDA,
+          oracleFeed: spyPriceFeed,
+          instructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+          creatorBond: await findCreatorBondPDA(betStateKP),
           systemProgram: anchor.web3.SystemProgram.programId
         },
         signers:[betStateKP]
@@ -1197,16 +1539,16 @@ describe('juicy-bets', () => {
     assert.ok(betStateAccount);
     assert.ok(betStateAccount.staticTotalPool.eq(new anchor.BN(0)));
     assert.ok(betStateAccount.runningTotalPool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.negThreeAndUnderPool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.negThreeToNegTwoPool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.negTwoToNegOnePool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.negOneToZeroPool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.zeroToPosOnePool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.posOneToPosTwoPool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.posTwoToPosThreePool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.posThreeAndOverPool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.status.hasOwnProperty("open"));
-    assert.ok(betStateAccount.winningBetRange.hasOwnProperty("notAvailable"));
+    assert.ok(betStateAccount.pools[0].eq(new anchor.BN(0)));
+    assert.ok(betStateAccount.pools[1].eq(new anchor.BN(0)));
+    assert.ok(betStateAccount.pools[2].eq(new anchor.BN(0)));
+    assert.ok(betStateAccount.pools[3].eq(new anchor.BN(0)));
+    assert.ok(betStateAccount.pools[4].eq(new anchor.BN(0)));
+    assert.ok(betStateAccount.pools[5].eq(new anchor.BN(0)));
+    assert.ok(betStateAccount.pools[6].eq(new anchor.BN(0)));
+    assert.ok(betStateAccount.pools[7].eq(new anchor.BN(0)));
+    assert.equal(BET_STATE_STATUSES[betStateAccount.status], "open");
+    assert.equal(BET_RANGES[betStateAccount.winningBetRange], "notAvailable");
 
     console.log('--------------------')
     console.log(`Bet Creator Sol Balance post bet init: ${await program.provider.connection.getBalance(providerWallet.publicKey)/LAMPORTS_PER_SOL}`)
@@ -1297,6 +1639,9 @@ describe('juicy-bets', () => {
         betState: betStateKP.publicKey,
         wagerDetail: wagerDetail1KP.publicKey,
         userAccount: user1AccountKP.publicKey,
+        bettorPosition: await findBettorPosition(betStateKP, user1AccountKP),
+        config: configPDA,
+        treasury: providerWallet.publicKey,
         bettorAccount: user1KP.publicKey,
         betCreator: providerWallet.publicKey,
         systemProgram: anchor.web3.SystemProgram.programId
@@ -1325,39 +1670,37 @@ describe('juicy-bets', () => {
     // Test the newly modified bet state obj with the updates from the placed wager
     console.log(`Bet state static total pool after first wager placement: ${betStateAfterFirstWager.staticTotalPool.toNumber()/LAMPORTS_PER_SOL}`);
     console.log(`Bet state running total pool after first wager placement: ${betStateAfterFirstWager.runningTotalPool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state NegThreeAndUnder pool after first wager placement: ${betStateAfterFirstWager.negThreeAndUnderPool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state NegThreeToNegTwo pool after first wager placement: ${betStateAfterFirstWager.negThreeToNegTwoPool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state NegTwoToNegOne pool after first wager placement: ${betStateAfterFirstWager.negTwoToNegOnePool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state NegOneToZero pool after first wager placement: ${betStateAfterFirstWager.negOneToZeroPool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state ZeroToPosOne pool after first wager placement: ${betStateAfterFirstWager.zeroToPosOnePool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state PosOneToPosTwo pool after first wager placement: ${betStateAfterFirstWager.posOneToPosTwoPool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state PosTwoToPosThree pool after first wager placement: ${betStateAfterFirstWager.posTwoToPosThreePool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state PosThreeAndOver pool after first wager placement: ${betStateAfterFirstWager.posThreeAndOverPool.toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state NegThreeAndUnder pool after first wager placement: ${betStateAfterFirstWager.pools[0].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state NegThreeToNegTwo pool after first wager placement: ${betStateAfterFirstWager.pools[1].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state NegTwoToNegOne pool after first wager placement: ${betStateAfterFirstWager.pools[2].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state NegOneToZero pool after first wager placement: ${betStateAfterFirstWager.pools[3].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state ZeroToPosOne pool after first wager placement: ${betStateAfterFirstWager.pools[4].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state PosOneToPosTwo pool after first wager placement: ${betStateAfterFirstWager.pools[5].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state PosTwoToPosThree pool after first wager placement: ${betStateAfterFirstWager.pools[6].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state PosThreeAndOver pool after first wager placement: ${betStateAfterFirstWager.pools[7].toNumber()/LAMPORTS_PER_SOL}`);
     
     console.log(`Bet State lamports after first wager placement: ${await program.provider.connection.getBalance(betStateKP.publicKey)/LAMPORTS_PER_SOL}`)
 
 
     ///// ***** CLOSING A BET STATE ***** /////
 
-    const end_time = new anchor.BN(Date.now());
-
     const betStateBeforeClosing = await program.account.betState.fetch(betStateKP.publicKey);
-    assert.ok(betStateBeforeClosing.status.hasOwnProperty("open"));
+    assert.equal(BET_STATE_STATUSES[betStateBeforeClosing.status], "open");
 
     await program.rpc.closeBetState(
-      end_time,
       {
         accounts: {
           betState: betStateKP.publicKey,
-          betCreator: providerWallet.publicKey
+          config: configPDA,
+          resolver: providerWallet.publicKey
         }
       }
     )
 
     const betStateAfterClosing = await program.account.betState.fetch(betStateKP.publicKey);
     assert.ok(betStateAfterClosing);
-    assert.ok(betStateAfterClosing.winningBetRange.hasOwnProperty("notAvailable"));
-    assert.ok(betStateAfterClosing.status.hasOwnProperty("closed"));
+    assert.equal(BET_RANGES[betStateAfterClosing.winningBetRange], "notAvailable");
+    assert.equal(BET_STATE_STATUSES[betStateAfterClosing.status], "closed");
 
 
     ///// ***** DECIDE THE BET STATE OUTCOME ***** /////
@@ -1367,7 +1710,8 @@ describe('juicy-bets', () => {
       {
         accounts: {
           betState: betStateKP.publicKey,
-          betCreator: providerWallet.publicKey
+          config: configPDA,
+          resolver: providerWallet.publicKey
         }
       }
     )
@@ -1379,6 +1723,7 @@ describe('juicy-bets', () => {
         {
           accounts: {
             betState: betStateKP.publicKey,
+            creatorBond: await findCreatorBondPDA(betStateKP),
             betCreator: providerWallet.publicKey,
           }
         }
@@ -1407,14 +1752,31 @@ describe('juicy-bets', () => {
     console.log('--------------------')
 
     await program.rpc.initializeBetState(
-      start,
-      duration,
-      'SPY',
-      new anchor.BN(725.45 * 1000),
+      {
+        start,
+        duration,
+        symbol: 'SPY',
+        limits: null,
+        bettingCutoff: new anchor.BN(0),
+        oracleGuards: null,
+        oracleKind: { pyth: {} },
+        secondaryOracles: [],
+        oracleQuorum: 1,
+        boundaryRule: { lowerInclusive: {} },
+        straddlePolicy: { reject: {} },
+        resolverCommittee: null,
+        creatorFeeBps: 0,
+        marketType: { range: {} },
+      },
       {
         accounts: {
           betState: betStateKP.publicKey,
           betCreator: providerWallet.publicKey,
+          config: configPDA,
+          asset: spyAssetPDA,
+          oracleFeed: spyPriceFeed,
+          instructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+          creatorBond: await findCreatorBondPDA(betStateKP),
           systemProgram: anchor.web3.SystemProgram.programId
         },
         signers:[betStateKP]
@@ -1430,16 +1792,16 @@ describe('juicy-bets', () => {
     assert.ok(betStateAccount);
     assert.ok(betStateAccount.staticTotalPool.eq(new anchor.BN(0)));
     assert.ok(betStateAccount.runningTotalPool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.negThreeAndUnderPool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.negThreeToNegTwoPool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.negTwoToNegOnePool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.negOneToZeroPool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.zeroToPosOnePool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.posOneToPosTwoPool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.posTwoToPosThreePool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.posThreeAndOverPool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.status.hasOwnProperty("open"));
-    assert.ok(betStateAccount.winningBetRange.hasOwnProperty("notAvailable"));
+    assert.ok(betStateAccount.pools[0].eq(new anchor.BN(0)));
+    assert.ok(betStateAccount.pools[1].eq(new anchor.BN(0)));
+    assert.ok(betStateAccount.pools[2].eq(new anchor.BN(0)));
+    assert.ok(betStateAccount.pools[3].eq(new anchor.BN(0)));
+    assert.ok(betStateAccount.pools[4].eq(new anchor.BN(0)));
+    assert.ok(betStateAccount.pools[5].eq(new anchor.BN(0)));
+    assert.ok(betStateAccount.pools[6].eq(new anchor.BN(0)));
+    assert.ok(betStateAccount.pools[7].eq(new anchor.BN(0)));
+    assert.equal(BET_STATE_STATUSES[betStateAccount.status], "open");
+    assert.equal(BET_RANGES[betStateAccount.winningBetRange], "notAvailable");
 
     console.log('--------------------')
     console.log(`Bet Creator Sol Balance post bet init: ${await program.provider.connection.getBalance(providerWallet.publicKey)/LAMPORTS_PER_SOL}`)
@@ -1530,6 +1892,9 @@ describe('juicy-bets', () => {
         betState: betStateKP.publicKey,
         wagerDetail: wagerDetail1KP.publicKey,
         userAccount: user1AccountKP.publicKey,
+        bettorPosition: await findBettorPosition(betStateKP, user1AccountKP),
+        config: configPDA,
+        treasury: providerWallet.publicKey,
         bettorAccount: user1KP.publicKey,
         betCreator: providerWallet.publicKey,
         systemProgram: anchor.web3.SystemProgram.programId
@@ -1558,14 +1923,14 @@ describe('juicy-bets', () => {
     // Test the newly modified bet state obj with the updates from the placed wager
     console.log(`Bet state static total pool after first wager placement: ${betStateAfterFirstWager.staticTotalPool.toNumber()/LAMPORTS_PER_SOL}`);
     console.log(`Bet state running total pool after first wager placement: ${betStateAfterFirstWager.runningTotalPool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state NegThreeAndUnder pool after first wager placement: ${betStateAfterFirstWager.negThreeAndUnderPool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state NegThreeToNegTwo pool after first wager placement: ${betStateAfterFirstWager.negThreeToNegTwoPool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state NegTwoToNegOne pool after first wager placement: ${betStateAfterFirstWager.negTwoToNegOnePool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state NegOneToZero pool after first wager placement: ${betStateAfterFirstWager.negOneToZeroPool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state ZeroToPosOne pool after first wager placement: ${betStateAfterFirstWager.zeroToPosOnePool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state PosOneToPosTwo pool after first wager placement: ${betStateAfterFirstWager.posOneToPosTwoPool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state PosTwoToPosThree pool after first wager placement: ${betStateAfterFirstWager.posTwoToPosThreePool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state PosThreeAndOver pool after first wager placement: ${betStateAfterFirstWager.posThreeAndOverPool.toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state NegThreeAndUnder pool after first wager placement: ${betStateAfterFirstWager.pools[0].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state NegThreeToNegTwo pool after first wager placement: ${betStateAfterFirstWager.pools[1].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state NegTwoToNegOne pool after first wager placement: ${betStateAfterFirstWager.pools[2].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state NegOneToZero pool after first wager placement: ${betStateAfterFirstWager.pools[3].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state ZeroToPosOne pool after first wager placement: ${betStateAfterFirstWager.pools[4].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state PosOneToPosTwo pool after first wager placement: ${betStateAfterFirstWager.pools[5].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state PosTwoToPosThree pool after first wager placement: ${betStateAfterFirstWager.pools[6].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state PosThreeAndOver pool after first wager placement: ${betStateAfterFirstWager.pools[7].toNumber()/LAMPORTS_PER_SOL}`);
     
     console.log(`Bet State lamports after first wager placement: ${await program.provider.connection.getBalance(betStateKP.publicKey)/LAMPORTS_PER_SOL}`)
 
@@ -1581,12 +1946,12 @@ describe('juicy-bets', () => {
       ///// ***** USER 1 CLAIMS WINNINGS ***** /////
 
       await program.rpc.claimWinnings(
-        bettor1Winnings,
         {
           accounts: {
             betState: betStateKP.publicKey,
             wagerDetail: wagerDetail1KP.publicKey,
             userAccount: user1AccountKP.publicKey,
+            bettorPosition: await findBettorPosition(betStateKP, user1AccountKP),
             bettor: user1KP.publicKey
           },
           signers:[user1KP]
@@ -1614,14 +1979,31 @@ describe('juicy-bets', () => {
     console.log('--------------------')
 
     await program.rpc.initializeBetState(
-      start,
-      duration,
-      'SPY',
-      new anchor.BN(725.45 * 1000),
+      {
+        start,
+        duration,
+        symbol: 'SPY',
+        limits: null,
+        bettingCutoff: new anchor.BN(0),
+        oracleGuards: null,
+        oracleKind: { pyth: {} },
+        secondaryOracles: [],
+        oracleQuorum: 1,
+        boundaryRule: { lowerInclusive: {} },
+        straddlePolicy: { reject: {} },
+        resolverCommittee: null,
+        creatorFeeBps: 0,
+        marketType: { range: {} },
+      },
       {
         accounts: {
           betState: betStateKP.publicKey,
           betCreator: providerWallet.publicKey,
+          config: configPDA,
+          asset: spyAssetPDA,
+          oracleFeed: spyPriceFeed,
+          instructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+          creatorBond: await findCreatorBondPDA(betStateKP),
           systemProgram: anchor.web3.SystemProgram.programId
         },
         signers:[betStateKP]
@@ -1637,16 +2019,16 @@ describe('juicy-bets', () => {
     assert.ok(betStateAccount);
     assert.ok(betStateAccount.staticTotalPool.eq(new anchor.BN(0)));
     assert.ok(betStateAccount.runningTotalPool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.negThreeAndUnderPool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.negThreeToNegTwoPool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.negTwoToNegOnePool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.negOneToZeroPool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.zeroToPosOnePool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.posOneToPosTwoPool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.posTwoToPosThreePool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.posThreeAndOverPool.eq(new anchor.BN(0)));
-    assert.ok(betStateAccount.status.hasOwnProperty("open"));
-    assert.ok(betStateAccount.winningBetRange.hasOwnProperty("notAvailable"));
+    assert.ok(betStateAccount.pools[0].eq(new anchor.BN(0)));
+    assert.ok(betStateAccount.pools[1].eq(new anchor.BN(0)));
+    assert.ok(betStateAccount.pools[2].eq(new anchor.BN(0)));
+    assert.ok(betStateAccount.pools[3].eq(new anchor.BN(0)));
+    assert.ok(betStateAccount.pools[4].eq(new anchor.BN(0)));
+    assert.ok(betStateAccount.pools[5].eq(new anchor.BN(0)));
+    assert.ok(betStateAccount.pools[6].eq(new anchor.BN(0)));
+    assert.ok(betStateAccount.pools[7].eq(new anchor.BN(0)));
+    assert.equal(BET_STATE_STATUSES[betStateAccount.status], "open");
+    assert.equal(BET_RANGES[betStateAccount.winningBetRange], "notAvailable");
 
     console.log('--------------------')
     console.log(`Bet Creator Sol Balance post bet init: ${await program.provider.connection.getBalance(providerWallet.publicKey)/LAMPORTS_PER_SOL}`)
@@ -1737,6 +2119,9 @@ describe('juicy-bets', () => {
         betState: betStateKP.publicKey,
         wagerDetail: wagerDetail1KP.publicKey,
         userAccount: user1AccountKP.publicKey,
+        bettorPosition: await findBettorPosition(betStateKP, user1AccountKP),
+        config: configPDA,
+        treasury: providerWallet.publicKey,
         bettorAccount: user1KP.publicKey,
         betCreator: providerWallet.publicKey,
         systemProgram: anchor.web3.SystemProgram.programId
@@ -1765,38 +2150,36 @@ describe('juicy-bets', () => {
     // Test the newly modified bet state obj with the updates from the placed wager
     console.log(`Bet state static total pool after first wager placement: ${betStateAfterFirstWager.staticTotalPool.toNumber()/LAMPORTS_PER_SOL}`);
     console.log(`Bet state running total pool after first wager placement: ${betStateAfterFirstWager.runningTotalPool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state NegThreeAndUnder pool after first wager placement: ${betStateAfterFirstWager.negThreeAndUnderPool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state NegThreeToNegTwo pool after first wager placement: ${betStateAfterFirstWager.negThreeToNegTwoPool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state NegTwoToNegOne pool after first wager placement: ${betStateAfterFirstWager.negTwoToNegOnePool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state NegOneToZero pool after first wager placement: ${betStateAfterFirstWager.negOneToZeroPool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state ZeroToPosOne pool after first wager placement: ${betStateAfterFirstWager.zeroToPosOnePool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state PosOneToPosTwo pool after first wager placement: ${betStateAfterFirstWager.posOneToPosTwoPool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state PosTwoToPosThree pool after first wager placement: ${betStateAfterFirstWager.posTwoToPosThreePool.toNumber()/LAMPORTS_PER_SOL}`);
-    console.log(`Bet state PosThreeAndOver pool after first wager placement: ${betStateAfterFirstWager.posThreeAndOverPool.toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state NegThreeAndUnder pool after first wager placement: ${betStateAfterFirstWager.pools[0].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state NegThreeToNegTwo pool after first wager placement: ${betStateAfterFirstWager.pools[1].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state NegTwoToNegOne pool after first wager placement: ${betStateAfterFirstWager.pools[2].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state NegOneToZero pool after first wager placement: ${betStateAfterFirstWager.pools[3].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state ZeroToPosOne pool after first wager placement: ${betStateAfterFirstWager.pools[4].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state PosOneToPosTwo pool after first wager placement: ${betStateAfterFirstWager.pools[5].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state PosTwoToPosThree pool after first wager placement: ${betStateAfterFirstWager.pools[6].toNumber()/LAMPORTS_PER_SOL}`);
+    console.log(`Bet state PosThreeAndOver pool after first wager placement: ${betStateAfterFirstWager.pools[7].toNumber()/LAMPORTS_PER_SOL}`);
     
     console.log(`Bet State lamports after first wager placement: ${await program.provider.connection.getBalance(betStateKP.publicKey)/LAMPORTS_PER_SOL}`)
 
     ///// ***** CLOSING A BET STATE ***** /////
 
-    const end_time = new anchor.BN(Date.now());
-
     const betStateBeforeClosing = await program.account.betState.fetch(betStateKP.publicKey);
-    assert.ok(betStateBeforeClosing.status.hasOwnProperty("open"));
+    assert.equal(BET_STATE_STATUSES[betStateBeforeClosing.status], "open");
 
     await program.rpc.closeBetState(
-      end_time,
       {
         accounts: {
           betState: betStateKP.publicKey,
-          betCreator: providerWallet.publicKey
+          config: configPDA,
+          resolver: providerWallet.publicKey
         }
       }
     )
 
     const betStateAfterClosing = await program.account.betState.fetch(betStateKP.publicKey);
     assert.ok(betStateAfterClosing);
-    assert.ok(betStateAfterClosing.winningBetRange.hasOwnProperty("notAvailable"));
-    assert.ok(betStateAfterClosing.status.hasOwnProperty("closed"));
+    assert.equal(BET_RANGES[betStateAfterClosing.winningBetRange], "notAvailable");
+    assert.equal(BET_STATE_STATUSES[betStateAfterClosing.status], "closed");
 
     const bettor1Winnings = new anchor.BN(calculateWinnings(wagerDetails1Account, betStateAfterClosing));
 
@@ -1807,12 +2190,12 @@ describe('juicy-bets', () => {
       ///// ***** USER 1 CLAIMS WINNINGS ***** /////
 
       await program.rpc.claimWinnings(
-        bettor1Winnings,
         {
           accounts: {
             betState: betStateKP.publicKey,
             wagerDetail: wagerDetail1KP.publicKey,
             userAccount: user1AccountKP.publicKey,
+            bettorPosition: await findBettorPosition(betStateKP, user1AccountKP),
             bettor: user1KP.publicKey
           },
           signers:[user1KP]
@@ -1826,4 +2209,2427 @@ describe('juicy-bets', () => {
   });
 
 
+  // Betting Limits //
+
+  it('Enforces the per-wager, per-user and per-bucket limits set on a bet state', async() => {
+
+    const betStateKP = await createBetState({
+      limits: {
+        minWager: new anchor.BN(LAMPORTS_PER_SOL * 0.1),
+        maxWager: new anchor.BN(LAMPORTS_PER_SOL * 0.5),
+        maxUserStake: new anchor.BN(LAMPORTS_PER_SOL * 0.65),
+        maxTotalPool: new anchor.BN(LAMPORTS_PER_SOL * 1.5),
+        maxBucketShareBps: 6000,
+      },
+    });
+
+    const betStateAccount = await program.account.betState.fetch(betStateKP.publicKey);
+    assert.ok(betStateAccount.maxWager.eq(new anchor.BN(LAMPORTS_PER_SOL * 0.5)));
+    assert.equal(betStateAccount.maxBucketShareBps, 6000);
+
+    const user1 = await createFundedUserAccount(LAMPORTS_PER_SOL * 1);
+    const user2 = await createFundedUserAccount(LAMPORTS_PER_SOL * 1);
+    const user3 = await createFundedUserAccount(LAMPORTS_PER_SOL * 1);
+
+    await expectProgramError(placeWager(betStateKP, user1, 1, LAMPORTS_PER_SOL * 0.05), "WagerBelowMinimum");
+    await expectProgramError(placeWager(betStateKP, user1, 1, LAMPORTS_PER_SOL * 0.6), "WagerAboveMaximum");
+
+    await placeWager(betStateKP, user1, 1, LAMPORTS_PER_SOL * 0.45);
+    await placeWager(betStateKP, user2, 1, LAMPORTS_PER_SOL * 0.15);
+
+    // User 1 would hold 0.6 of the 0.75 SOL bucket, over the 60% share
+    await expectProgramError(placeWager(betStateKP, user1, 1, LAMPORTS_PER_SOL * 0.15), "BucketShareLimitExceeded");
+
+    // The same stake on another bucket is fine until it takes user 1 over their per-user limit
+    await placeWager(betStateKP, user1, 2, LAMPORTS_PER_SOL * 0.15);
+    await expectProgramError(placeWager(betStateKP, user1, 3, LAMPORTS_PER_SOL * 0.1), "UserStakeLimitExceeded");
+
+    await placeWager(betStateKP, user3, 4, LAMPORTS_PER_SOL * 0.5);
+    await expectProgramError(placeWager(betStateKP, user2, 5, LAMPORTS_PER_SOL * 0.3), "TotalPoolLimitExceeded");
+
+    const user1Position = await program.account.bettorPosition.fetch(await findBettorPosition(betStateKP, user1.userAccountKP));
+    assert.equal(user1Position.bucketStakes.length, 8);
+    assert.ok(user1Position.totalStake.eq(user1Position.bucketStakes[1].add(user1Position.bucketStakes[2])));
+
+  });
+
+
+  // Betting Cutoff //
+
+  it('Rejects wagers once a bet state is inside its betting cutoff and reports it in the status view', async() => {
+
+    const start = new anchor.BN(Date.now());
+    const duration = new anchor.BN(5 * 60 * 1000);
+
+    // A cutoff as long as the bet means it is locked from the start
+    const betStateKP = await createBetState({ start, duration, bettingCutoff: duration });
+
+    const betStateAccount = await program.account.betState.fetch(betStateKP.publicKey);
+    assert.ok(betStateAccount.bettingCutoff.eq(duration));
+
+    const statusView = await program.simulate.viewBetStateStatus({
+      accounts: {
+        betState: betStateKP.publicKey,
+      }
+    });
+    const status = statusView.events[0].data;
+
+    assert.equal(statusView.events[0].name, "BetStateStatusView");
+    assert.ok(status.status.hasOwnProperty("open"));
+    assert.equal(status.acceptingWagers, false);
+    assert.ok(status.bettingCutoffTime.eq(start));
+
+    const user1 = await createFundedUserAccount(LAMPORTS_PER_SOL * 1);
+    await expectProgramError(placeWager(betStateKP, user1, 1, LAMPORTS_PER_SOL * 0.5), "BettingCutoffReached");
+
+  });
+
+
+  // Market Creation Validation //
+
+  // Bet states on SPY unless told otherwise
+  const createBetState = async ({
+    start = new anchor.BN(Date.now()),
+    duration = new anchor.BN(5 * 60 * 1000),
+    symbol = 'SPY',
+    asset = spyAssetPDA,
+    feed = spyPriceFeed,
+    limits = null,
+    bettingCutoff = new anchor.BN(0),
+  }) => {
+    const betStateKP = anchor.web3.Keypair.generate();
+
+    await program.rpc.initializeBetState(
+      {
+        start,
+        duration,
+        symbol,
+        limits,
+        bettingCutoff,
+        oracleGuards: null,
+        oracleKind: { pyth: {} },
+        secondaryOracles: [],
+        oracleQuorum: 1,
+        boundaryRule: { lowerInclusive: {} },
+        straddlePolicy: { reject: {} },
+        resolverCommittee: null,
+        creatorFeeBps: 0,
+        marketType: { range: {} },
+      },
+      {
+        accounts: {
+          betState: betStateKP.publicKey,
+          betCreator: providerWallet.publicKey,
+          config: configPDA,
+          asset,
+          oracleFeed: feed,
+          instructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+          creatorBond: await findCreatorBondPDA(betStateKP),
+          systemProgram: anchor.web3.SystemProgram.programId
+        },
+        signers:[betStateKP]
+      },
+    );
+
+    return betStateKP;
+  };
+
+  it('Rejects bet states with an invalid symbol, window or snapshot price, or on an unregistered asset', async() => {
+
+    await expectProgramError(createBetState({ symbol: '' }), "EmptySymbol");
+    await expectProgramError(createBetState({ symbol: 'SPY-DAILY-CLOSE-1' }), "SymbolTooLong");
+
+    // Neither a symbol's own missing entry nor another symbol's entry lets it through
+    const qqqAssetPDA = await findAssetPDA('QQQ');
+    await expectProgramError(createBetState({ symbol: 'QQQ', asset: qqqAssetPDA }), "AssetNotRegistered");
+    await expectProgramError(createBetState({ symbol: 'QQQ' }), "AssetNotRegistered");
+
+    // A symbol taken off the registry can no longer get new bet states
+    await registerAsset('QQQ', spyPriceFeed);
+    assert.equal(decodeSymbol((await program.account.assetEntry.fetch(qqqAssetPDA)).symbol), "QQQ");
+    await program.rpc.deregisterAsset({
+      accounts: {
+        config: configPDA,
+        asset: qqqAssetPDA,
+        admin: providerWallet.publicKey,
+      }
+    });
+    await expectProgramError(createBetState({ symbol: 'QQQ', asset: qqqAssetPDA }), "AssetNotRegistered");
+
+    await expectProgramError(createBetState({ duration: new anchor.BN(0) }), "ZeroDuration");
+    await expectProgramError(createBetState({ start: new anchor.BN(Date.now() - 10 * 60 * 1000) }), "StartTimeInPast");
+    await expectProgramError(createBetState({ start: new anchor.BN("18446744073709551000"), duration: new anchor.BN(1000) }), "EndTimeOverflow");
+
+    const zeroPriceFeed = await createMockPythFeed(mockOracle, { price: 0, conf: 0, expo: -8 });
+    const zeroPriceSymbol = symbolForFeed(zeroPriceFeed);
+    const zeroPriceAsset = await registerAsset(zeroPriceSymbol, zeroPriceFeed);
+    await expectProgramError(createBetState({ symbol: zeroPriceSymbol, asset: zeroPriceAsset, feed: zeroPriceFeed }), "InvalidSnapshotPrice");
+
+    // A start slightly behind the cluster's clock is still accepted
+    const betStateKP = await createBetState({ start: new anchor.BN(Date.now() - 5 * 1000) });
+    assert.equal(decodeSymbol((await program.account.betState.fetch(betStateKP.publicKey)).symbol), "SPY");
+
+  });
+
+
+  // Oracle Resolution //
+
+  const createOracleBetState = async ({
+    feed,
+    durationMs,
+    oracleGuards = null,
+    oracleKind = { pyth: {} } as object,
+    secondaryOracles = [],
+    oracleQuorum = 1,
+    preInstructions = [],
+    boundaryRule = { lowerInclusive: {} } as object,
+    straddlePolicy = { reject: {} } as object,
+    resolverCommittee = null as anchor.web3.PublicKey | null,
+    creatorFeeBps = 0,
+    marketType = { range: {} } as object,
+    benchmarkAccounts = [] as anchor.web3.PublicKey[],
+    start = Date.now(),
+  }) => {
+    const betStateKP = anchor.web3.Keypair.generate();
+    const symbol = symbolForFeed(feed);
+    const asset = await ensureAsset(symbol, feed, oracleKind);
+
+    await program.rpc.initializeBetState(
+      {
+        start: new anchor.BN(start),
+        duration: new anchor.BN(durationMs),
+        symbol,
+        limits: null,
+        bettingCutoff: new anchor.BN(0),
+        oracleGuards,
+        oracleKind,
+        secondaryOracles,
+        oracleQuorum,
+        boundaryRule,
+        straddlePolicy,
+        resolverCommittee,
+        creatorFeeBps,
+        marketType,
+      },
+      {
+        accounts: {
+          betState: betStateKP.publicKey,
+          betCreator: providerWallet.publicKey,
+          config: configPDA,
+          asset,
+          oracleFeed: feed,
+          instructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+          creatorBond: await findCreatorBondPDA(betStateKP),
+          systemProgram: anchor.web3.SystemProgram.programId
+        },
+        remainingAccounts: [...secondaryOracles.map(source => source.feed), ...benchmarkAccounts]
+          .map(pubkey => ({ pubkey, isWritable: false, isSigner: false })),
+        preInstructions,
+        signers:[betStateKP]
+      },
+    );
+
+    return betStateKP;
+  };
+
+  const resolveBetState = async (betStateKP, feed: anchor.web3.PublicKey, preInstructions = [], secondaryFeeds: anchor.web3.PublicKey[] = []) => program.rpc.resolveBetStateOutcome({
+    accounts: {
+      betState: betStateKP.publicKey,
+      oracleFeed: feed,
+      asset: await findAssetPDA(symbolForFeed(feed)),
+      config: configPDA,
+      instructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+    },
+    remainingAccounts: secondaryFeeds.map(pubkey => ({ pubkey, isWritable: false, isSigner: false })),
+    preInstructions,
+  });
+
+  it('Captures the snapshot price from the oracle feed when a bet state is created', async() => {
+
+    const oracleGuards = {
+      maxStalenessSecs: new anchor.BN(60),
+      maxConfidenceBps: 100,
+      retryWindowSecs: new anchor.BN(60 * 60),
+    };
+
+    const publishTime = nowInSeconds() - 5;
+    const feed = await createMockPythFeed(mockOracle, { price: 72545000000, conf: 1000000, expo: -8, publishTime });
+
+    const betStateKP = await createOracleBetState({ feed, durationMs: 60 * 1000, oracleGuards });
+
+    const betStateAccount = await program.account.betState.fetch(betStateKP.publicKey);
+    assert.ok(betStateAccount.snapshotMantissa.eq(new anchor.BN(72545000000)));
+    assert.equal(betStateAccount.snapshotExpo, -8);
+    assert.ok(betStateAccount.snapshotConf.eq(new anchor.BN(1000000)));
+    assert.equal(betStateAccount.snapshotPublishTime.toNumber(), publishTime);
+
+    await setMockPythPrice(mockOracle, feed, { price: 72545000000, conf: 1000000, expo: -8, publishTime: nowInSeconds() - 600 });
+    await expectProgramError(createOracleBetState({ feed, durationMs: 60 * 1000, oracleGuards }), "OraclePriceStale");
+
+    await setMockPythPrice(mockOracle, feed, { price: 72545000000, conf: 1000000, expo: -8, status: PYTH_STATUS_UNKNOWN });
+    await expectProgramError(createOracleBetState({ feed, durationMs: 60 * 1000, oracleGuards }), "OraclePriceNotTrading");
+
+  });
+
+  it('Maps closing prices on and around each bucket boundary to the bucket set by the bet state\'s boundary rule', async() => {
+
+    const oracleGuards = {
+      maxStalenessSecs: new anchor.BN(60),
+      maxConfidenceBps: 0,
+      retryWindowSecs: new anchor.BN(60 * 60),
+    };
+
+    const lowerInclusive = { lowerInclusive: {} };
+    const upperInclusive = { upperInclusive: {} };
+
+    // Moves from 725.45. With lower-inclusive ranges a move sitting exactly on a boundary belongs to the bucket
+    // above it and anything below a boundary, however small, stays in the bucket under it. Upper-inclusive ranges
+    // are the mirror image.
+    const cases = [
+      { rule: lowerInclusive, price: 74721350000, range: "posThreeAndOver" }, // +3.00%
+      { rule: lowerInclusive, price: 74721349999, range: "posTwoToPosThree" },
+      { rule: lowerInclusive, price: 73270450000, range: "posOneToPosTwo" }, // +1.00%
+      { rule: lowerInclusive, price: 73270449999, range: "zeroToPosOne" },
+      { rule: lowerInclusive, price: 72545000000, range: "zeroToPosOne" }, // unchanged
+      { rule: lowerInclusive, price: 72544999999, range: "negOneToZero" },
+      { rule: lowerInclusive, price: 71819550000, range: "negOneToZero" }, // -1.00%
+      { rule: lowerInclusive, price: 71819549999, range: "negTwoToNegOne" },
+      { rule: lowerInclusive, price: 70368650000, range: "negThreeToNegTwo" }, // -3.00%
+      { rule: lowerInclusive, price: 70368649999, range: "negThreeAndUnder" },
+      { rule: upperInclusive, price: 73270450001, range: "posOneToPosTwo" },
+      { rule: upperInclusive, price: 73270450000, range: "zeroToPosOne" }, // +1.00%
+      { rule: upperInclusive, price: 72545000001, range: "zeroToPosOne" },
+      { rule: upperInclusive, price: 72545000000, range: "negOneToZero" }, // unchanged
+      { rule: upperInclusive, price: 70368650001, range: "negThreeToNegTwo" },
+      { rule: upperInclusive, price: 70368650000, range: "negThreeAndUnder" }, // -3.00%
+    ];
+
+    const markets = [];
+    for (const boundaryCase of cases) {
+      const feed = await createMockPythFeed(mockOracle, { price: 72545000000, conf: 0, expo: -8 });
+      const betStateKP = await createOracleBetState({ feed, durationMs: 2 * 1000, oracleGuards, boundaryRule: boundaryCase.rule });
+      markets.push({ ...boundaryCase, feed, betStateKP });
+    }
+
+    await sleep(4 * 1000);
+
+    for (const market of markets) {
+      await setMockPythPrice(mockOracle, market.feed, { price: market.price, conf: 0, expo: -8 });
+      await resolveBetState(market.betStateKP, market.feed);
+
+      const betStateAfterResolution = await program.account.betState.fetch(market.betStateKP.publicKey);
+      assert.equal(BET_RANGES[betStateAfterResolution.winningBetRange], market.range, `${market.price} should resolve to ${market.range}`);
+      assert.equal(BET_RANGES[betStateAfterResolution.splitBetRange], "notAvailable");
+      assert.ok(betStateAfterResolution.closingMantissa.eq(new anchor.BN(market.price)));
+      assert.equal(betStateAfterResolution.closingExpo, -8);
+    }
+
+  });
+
+  it('Creates bet states with the bucket layout and default limits of their asset, on its own feed and only while it is enabled', async() => {
+
+    const feed = await createMockPythFeed(mockOracle, { price: 72545000000, conf: 0, expo: -8 });
+    const symbol = symbolForFeed(feed);
+    const defaultLimits = {
+      minWager: new anchor.BN(0),
+      maxWager: new anchor.BN(LAMPORTS_PER_SOL * 0.5),
+      maxUserStake: new anchor.BN(0),
+      maxTotalPool: new anchor.BN(0),
+      maxBucketShareBps: 0,
+    };
+    const asset = await registerAsset(symbol, feed, { pyth: {} }, 250, defaultLimits);
+
+    const assetEntry = await program.account.assetEntry.fetch(asset);
+    assert.ok(assetEntry.oracleFeed.equals(feed));
+    assert.equal(assetEntry.bucketLayout.bucketWidthBps, 250);
+    assert.ok(assetEntry.defaultLimits.maxWager.eq(defaultLimits.maxWager));
+    assert.equal(assetEntry.enabled, true);
+
+    const assetAdminAccounts = {
+      config: configPDA,
+      asset,
+      admin: providerWallet.publicKey,
+    };
+    await expectProgramError(
+      program.rpc.updateAsset({ equity: {} }, { bucketWidthBps: 0 }, defaultLimits, ALWAYS_TRADING, { accounts: assetAdminAccounts }),
+      "InvalidBucketLayout"
+    );
+    await expectProgramError(
+      program.rpc.updateAsset({ equity: {} }, { bucketWidthBps: 250 }, defaultLimits, { ...ALWAYS_TRADING, tradingDays: 0 }, { accounts: assetAdminAccounts }),
+      "InvalidTradingHours"
+    );
+    // Crypto never closes, weekday hours are fine for an equity but not for a coin
+    await expectProgramError(
+      program.rpc.updateAsset({ crypto: {} }, { bucketWidthBps: 250 }, defaultLimits, { ...ALWAYS_TRADING, tradingDays: 0b0011111 }, { accounts: assetAdminAccounts }),
+      "AssetNeverCloses"
+    );
+
+    // Only the asset's feed can decide its bet states
+    await expectProgramError(createBetState({ symbol, asset }), "AssetOracleMismatch");
+
+    await program.rpc.setAssetEnabled(false, { accounts: assetAdminAccounts });
+    await expectProgramError(createBetState({ symbol, asset, feed }), "AssetDisabled");
+    await program.rpc.setAssetEnabled(true, { accounts: assetAdminAccounts });
+
+    const betStateKP = await createBetState({ symbol, asset, feed, duration: new anchor.BN(2 * 1000) });
+
+    const betStateAccount = await program.account.betState.fetch(betStateKP.publicKey);
+    assert.equal(betStateAccount.bucketWidthBps, 250);
+    assert.ok(betStateAccount.maxWager.eq(defaultLimits.maxWager));
+
+    await sleep(4 * 1000);
+
+    // +2.00% is under the first 2.5% boundary, where with the usual 1% buckets it would be posTwoToPosThree
+    await setMockPythPrice(mockOracle, feed, { price: 73995900000, conf: 0, expo: -8 });
+    await resolveBetState(betStateKP, feed);
+
+    const betStateAfterResolution = await program.account.betState.fetch(betStateKP.publicKey);
+    assert.equal(BET_RANGES[betStateAfterResolution.winningBetRange], "zeroToPosOne");
+
+  });
+
+  it('Rejects bet states the market is closed for part of and voids one a holiday is added to before it resolves', async() => {
+
+    const feed = await createMockPythFeed(mockOracle, { price: 72545000000, conf: 0, expo: -8 });
+    const symbol = symbolForFeed(feed);
+    const asset = await registerAsset(symbol, feed);
+    const assetAdminAccounts = {
+      config: configPDA,
+      asset,
+      admin: providerWallet.publicKey,
+    };
+
+    // Days since the unix epoch, which fell on a Thursday
+    const today = Math.floor(Date.now() / (24 * 60 * 60 * 1000));
+    const todayBit = 1 << ((today + 3) % 7);
+
+    await expectProgramError(
+      program.rpc.setAssetHolidays(Array.from({ length: 33 }, (_, index) => today + index), { accounts: assetAdminAccounts }),
+      "TooManyHolidays"
+    );
+
+    await program.rpc.setAssetHolidays([today], { accounts: assetAdminAccounts });
+    await expectProgramError(createBetState({ symbol, asset, feed }), "MarketClosedDuringBet");
+
+    // Crypto has no holidays, neither can an asset with one become crypto nor can a crypto asset be given one
+    const defaultLimits = (await program.account.assetEntry.fetch(asset)).defaultLimits;
+    await expectProgramError(
+      program.rpc.updateAsset({ crypto: {} }, { bucketWidthBps: 100 }, defaultLimits, ALWAYS_TRADING, { accounts: assetAdminAccounts }),
+      "AssetNeverCloses"
+    );
+    await program.rpc.setAssetHolidays([], { accounts: assetAdminAccounts });
+    await program.rpc.updateAsset({ crypto: {} }, { bucketWidthBps: 100 }, defaultLimits, ALWAYS_TRADING, { accounts: assetAdminAccounts });
+    await expectProgramError(program.rpc.setAssetHolidays([today], { accounts: assetAdminAccounts }), "AssetNeverCloses");
+    await program.rpc.updateAsset({ equity: {} }, { bucketWidthBps: 100 }, defaultLimits, ALWAYS_TRADING, { accounts: assetAdminAccounts });
+
+    const closedTodayHours = { ...ALWAYS_TRADING, tradingDays: ALWAYS_TRADING.tradingDays & ~todayBit };
+    await program.rpc.updateAsset({ equity: {} }, { bucketWidthBps: 100 }, defaultLimits, closedTodayHours, { accounts: assetAdminAccounts });
+    await expectProgramError(createBetState({ symbol, asset, feed }), "MarketClosedDuringBet");
+    await program.rpc.updateAsset({ equity: {} }, { bucketWidthBps: 100 }, defaultLimits, ALWAYS_TRADING, { accounts: assetAdminAccounts });
+
+    const betStateKP = await createBetState({ symbol, asset, feed, duration: new anchor.BN(2 * 1000) });
+    const spyBetStateKP = await createBetState({ duration: new anchor.BN(2 * 1000) });
+
+    // The exchange closes for the day after the bet state opened
+    await program.rpc.setAssetHolidays([today], { accounts: assetAdminAccounts });
+    await sleep(4 * 1000);
+    await setMockPythPrice(mockOracle, feed, { price: 73270000000, conf: 0, expo: -8 });
+    await resolveBetState(betStateKP, feed);
+
+    const betStateAfterResolution = await program.account.betState.fetch(betStateKP.publicKey);
+    assert.equal(BET_STATE_STATUSES[betStateAfterResolution.status], "voided");
+    assert.equal(BET_RANGES[betStateAfterResolution.winningBetRange], "notAvailable");
+
+    // Another symbol's entry can't stand in for the bet's own
+    await expectProgramError(
+      program.rpc.resolveBetStateOutcome({
+        accounts: {
+          betState: spyBetStateKP.publicKey,
+          oracleFeed: spyPriceFeed,
+          asset,
+          config: configPDA,
+          instructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+        },
+      }),
+      "InvalidAssetAccount"
+    );
+
+  });
+
+  it('Advances a market series by resolving its current round and opening the next one at the round\'s PDA', async() => {
+
+    const feed = await createMockPythFeed(mockOracle, { price: 72545000000, conf: 0, expo: -8 });
+    const symbol = symbolForFeed(feed);
+    const asset = await registerAsset(symbol, feed);
+
+    const seriesPDA = await findSeriesPDA(symbol);
+    const jackpot = await findJackpotPDA(seriesPDA);
+
+    const seriesAccounts = {
+      config: configPDA,
+      asset,
+      series: seriesPDA,
+      jackpot,
+      admin: providerWallet.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    };
+    await expectProgramError(
+      program.rpc.createMarketSeries(symbol, new anchor.BN(2 * 1000), new anchor.BN(3 * 1000), new anchor.BN(0), { bucketWidthBps: 100 }, new anchor.BN(Date.now()), 0, { nextRound: {} }, { accounts: seriesAccounts }),
+      "InvalidSeriesSchedule"
+    );
+    // Starting a second early keeps the first round due however far the cluster's clock trails this one
+    await program.rpc.createMarketSeries(symbol, new anchor.BN(5 * 1000), new anchor.BN(5 * 1000), new anchor.BN(0), { bucketWidthBps: 100 }, new anchor.BN(Date.now() - 1000), 0, { nextRound: {} }, { accounts: seriesAccounts });
+
+    const firstRound = await advanceSeries(seriesPDA, asset, feed);
+    const firstRoundAccount = await program.account.betState.fetch(firstRound.publicKey);
+    assert.equal(decodeSymbol(firstRoundAccount.symbol), symbol);
+    assert.ok(firstRoundAccount.creator.equals(jackpot));
+    assert.ok(firstRoundAccount.snapshotMantissa.eq(new anchor.BN(72545000000)));
+    assert.equal((await program.account.marketSeries.fetch(seriesPDA)).round.toNumber(), 1);
+
+    const user1 = await createFundedUserAccount(LAMPORTS_PER_SOL * 1);
+    await placeWager(firstRound, user1, 4, LAMPORTS_PER_SOL * 0.5);
+
+    await expectProgramError(advanceSeries(seriesPDA, asset, feed), "RoundNotDue");
+
+    await sleep(6 * 1000);
+    await setMockPythPrice(mockOracle, feed, { price: 72907725000, conf: 0, expo: -8 }); // +0.50%
+
+    const secondRound = await advanceSeries(seriesPDA, asset, feed);
+
+    const firstRoundAfterAdvance = await program.account.betState.fetch(firstRound.publicKey);
+    assert.equal(BET_STATE_STATUSES[firstRoundAfterAdvance.status], "closed");
+    assert.equal(BET_RANGES[firstRoundAfterAdvance.winningBetRange], "zeroToPosOne");
+
+    // The next round opens from the price the last one closed on
+    const secondRoundAccount = await program.account.betState.fetch(secondRound.publicKey);
+    assert.equal(BET_STATE_STATUSES[secondRoundAccount.status], "open");
+    assert.ok(secondRoundAccount.snapshotMantissa.eq(new anchor.BN(72907725000)));
+    assert.ok(secondRoundAccount.startTime.gte(firstRoundAccount.endTime));
+    assert.equal((await program.account.marketSeries.fetch(seriesPDA)).round.toNumber(), 2);
+
+  });
+
+  it('Sweeps a round nobody won into the series\' jackpot and pays it into the next round with winners however that round is resolved', async() => {
+
+    const openSeries = async () => {
+      const feed = await createMockPythFeed(mockOracle, { price: 72545000000, conf: 0, expo: -8 });
+      const symbol = symbolForFeed(feed);
+      const asset = await registerAsset(symbol, feed);
+      const seriesPDA = await findSeriesPDA(symbol);
+      const jackpot = await findJackpotPDA(seriesPDA);
+
+      const seriesAccounts = {
+        config: configPDA,
+        asset,
+        series: seriesPDA,
+        jackpot,
+        admin: providerWallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId
+      };
+      await expectProgramError(
+        program.rpc.createMarketSeries(symbol, new anchor.BN(5 * 1000), new anchor.BN(5 * 1000), new anchor.BN(0), { bucketWidthBps: 100 }, new anchor.BN(Date.now() - 1000), 0, { minBalance: { lamports: new anchor.BN(0) } }, { accounts: seriesAccounts }),
+        "InvalidJackpotTrigger"
+      );
+      await program.rpc.createMarketSeries(symbol, new anchor.BN(5 * 1000), new anchor.BN(5 * 1000), new anchor.BN(0), { bucketWidthBps: 100 }, new anchor.BN(Date.now() - 1000), 0, { nextRound: {} }, { accounts: seriesAccounts });
+
+      return { feed, asset, seriesPDA, jackpot };
+    };
+
+    const sweepRound = async (jackpot, round) => program.rpc.sweepRoundToJackpot({
+      accounts: {
+        jackpot,
+        round: round.publicKey,
+        creatorBond: await findCreatorBondPDA(round),
+        keeper: providerWallet.publicKey
+      }
+    });
+
+    // One series has its rounds resolved as it advances, the other has its second round resolved on its own first
+    const advancedSeries = await openSeries();
+    const resolvedSeries = await openSeries();
+    const allSeries = [advancedSeries, resolvedSeries];
+
+    // Nobody backs the range either series' first round closes in
+    const firstRounds = [];
+    for (const series of allSeries) {
+      const firstRound = await advanceSeries(series.seriesPDA, series.asset, series.feed);
+      const user = await createFundedUserAccount(LAMPORTS_PER_SOL * 1);
+      const wagerKP = await placeWager(firstRound, user, 0, LAMPORTS_PER_SOL * 0.5);
+      await expectProgramError(sweepRound(series.jackpot, firstRound), "BetStillOpen");
+      firstRounds.push({ firstRound, user, wagerKP });
+    }
+
+    const winners = [await createFundedUserAccount(LAMPORTS_PER_SOL * 1), await createFundedUserAccount(LAMPORTS_PER_SOL * 1)];
+
+    await sleep(6 * 1000);
+
+    const secondRounds = [];
+    for (const [index, series] of allSeries.entries()) {
+      const { firstRound, user, wagerKP } = firstRounds[index];
+
+      await setMockPythPrice(mockOracle, series.feed, { price: 72907725000, conf: 0, expo: -8 }); // +0.50%
+      const secondRound = await advanceSeries(series.seriesPDA, series.asset, series.feed);
+
+      // The second round has a winner, who takes the jackpot along with the pool
+      const winner = winners[index];
+      const winningWagerKP = await placeWager(secondRound, winner, 4, LAMPORTS_PER_SOL * 0.25);
+
+      const firstRoundAfterAdvance = await program.account.betState.fetch(firstRound.publicKey);
+      assert.equal(BET_RANGES[firstRoundAfterAdvance.winningBetRange], "zeroToPosOne");
+
+      // The whole pool is unclaimable as soon as claims open, the round itself stays until the losing wager is closed
+      await sweepRound(series.jackpot, firstRound);
+      const jackpotAccount = await program.account.seriesJackpot.fetch(series.jackpot);
+      assert.ok(jackpotAccount.balance.eq(firstRoundAfterAdvance.staticTotalPool));
+      assert.ok((await program.account.betState.fetch(firstRound.publicKey)).runningTotalPool.eqn(0));
+
+      await program.rpc.closeLosingWager({
+        accounts: {
+          betState: firstRound.publicKey,
+          wagerDetail: wagerKP.publicKey,
+          userAccount: user.userAccountKP.publicKey,
+          bettorPosition: await findBettorPosition(firstRound, user.userAccountKP),
+          bettor: user.userKP.publicKey
+        },
+        signers: [user.userKP]
+      });
+      await sweepRound(series.jackpot, firstRound);
+      assert.equal(await program.account.betState.fetchNullable(firstRound.publicKey), null);
+
+      secondRounds.push({ secondRound, winner, winningWagerKP, jackpotBalance: jackpotAccount.balance });
+    }
+
+    await sleep(6 * 1000);
+
+    for (const [index, series] of allSeries.entries()) {
+      const { secondRound, winner, winningWagerKP, jackpotBalance } = secondRounds[index];
+
+      await setMockPythPrice(mockOracle, series.feed, { price: 73272263625, conf: 0, expo: -8 }); // +0.50%
+      if (series === resolvedSeries) {
+        // Resolving a round like any other bet state would skip the jackpot
+        await expectProgramError(resolveBetState(secondRound, series.feed), "ResolvedThroughSeries");
+
+        await program.rpc.resolveSeriesRound({
+          accounts: {
+            series: series.seriesPDA,
+            jackpot: series.jackpot,
+            round: secondRound.publicKey,
+            oracleFeed: series.feed,
+            asset: series.asset,
+            config: configPDA,
+            instructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+          }
+        });
+      }
+      await advanceSeries(series.seriesPDA, series.asset, series.feed);
+
+      const secondRoundAfterAdvance = await program.account.betState.fetch(secondRound.publicKey);
+      assert.equal(BET_RANGES[secondRoundAfterAdvance.winningBetRange], "zeroToPosOne");
+      assert.ok(secondRoundAfterAdvance.staticTotalPool.eq(secondRoundAfterAdvance.pools[4].add(jackpotBalance)));
+
+      const jackpotAfterAward = await program.account.seriesJackpot.fetch(series.jackpot);
+      assert.ok(jackpotAfterAward.balance.eqn(0));
+      assert.ok(jackpotAfterAward.totalAwarded.eq(jackpotBalance));
+      assert.equal(jackpotAfterAward.lastAwardedRound.toNumber(), 2);
+
+      const userAccountBeforeClaim = await program.account.userAccount.fetch(winner.userAccountKP.publicKey);
+      await program.rpc.claimWinnings({
+        accounts: {
+          betState: secondRound.publicKey,
+          wagerDetail: winningWagerKP.publicKey,
+          userAccount: winner.userAccountKP.publicKey,
+          bettorPosition: await findBettorPosition(secondRound, winner.userAccountKP),
+          bettor: winner.userKP.publicKey
+        },
+        signers: [winner.userKP]
+      });
+      const userAccountAfterClaim = await program.account.userAccount.fetch(winner.userAccountKP.publicKey);
+      assert.ok(userAccountAfterClaim.currentBalance.eq(userAccountBeforeClaim.currentBalance.add(secondRoundAfterAdvance.staticTotalPool)));
+    }
+
+  });
+
+  it('Splits the pool pro-rata between the two buckets either side of a boundary the closing price is too close to call', async() => {
+
+    const oracleGuards = {
+      maxStalenessSecs: new anchor.BN(60),
+      maxConfidenceBps: 0,
+      retryWindowSecs: new anchor.BN(60 * 60),
+    };
+
+    const user1 = await createFundedUserAccount(LAMPORTS_PER_SOL * 1);
+    const user2 = await createFundedUserAccount(LAMPORTS_PER_SOL * 1);
+    const user3 = await createFundedUserAccount(LAMPORTS_PER_SOL * 1);
+
+    const feed = await createMockPythFeed(mockOracle, { price: 72545000000, conf: 0, expo: -8 });
+    const betStateKP = await createOracleBetState({ feed, durationMs: 6 * 1000, oracleGuards, straddlePolicy: { split: {} } });
+
+    const wideFeed = await createMockPythFeed(mockOracle, { price: 72545000000, conf: 0, expo: -8 });
+    const wideBetStateKP = await createOracleBetState({
+      feed: wideFeed,
+      durationMs: 6 * 1000,
+      oracleGuards,
+      straddlePolicy: { split: {} },
+    });
+
+    const wager1KP = await placeWager(betStateKP, user1, 4, LAMPORTS_PER_SOL * 0.5);
+    const wager2KP = await placeWager(betStateKP, user2, 5, LAMPORTS_PER_SOL * 0.25);
+    const wager3KP = await placeWager(betStateKP, user3, 0, LAMPORTS_PER_SOL * 0.25);
+
+    await sleep(8 * 1000);
+
+    // 732.70 +/- 0.01 sits on both sides of the +1% boundary at 732.7045
+    await setMockPythPrice(mockOracle, feed, { price: 73270000000, conf: 1000000, expo: -8 });
+    await resolveBetState(betStateKP, feed);
+
+    // 732.70 +/- 8.00 covers the 0%, +1% and +2% boundaries, which is too wide to split
+    await setMockPythPrice(mockOracle, wideFeed, { price: 73270000000, conf: 800000000, expo: -8 });
+    await expectProgramError(resolveBetState(wideBetStateKP, wideFeed), "OracleConfidenceStraddlesBoundary");
+
+    const betStateAfterResolution = await program.account.betState.fetch(betStateKP.publicKey);
+    assert.equal(BET_RANGES[betStateAfterResolution.winningBetRange], "zeroToPosOne");
+    assert.equal(BET_RANGES[betStateAfterResolution.splitBetRange], "posOneToPosTwo");
+
+    const winningPool = betStateAfterResolution.pools[4].add(betStateAfterResolution.pools[5]);
+
+    const claimWinnings = async (user, wagerKP) => program.rpc.claimWinnings({
+      accounts: {
+        betState: betStateKP.publicKey,
+        wagerDetail: wagerKP.publicKey,
+        userAccount: user.userAccountKP.publicKey,
+        bettorPosition: await findBettorPosition(betStateKP, user.userAccountKP),
+        bettor: user.userKP.publicKey
+      },
+      signers: [user.userKP]
+    });
+
+    for (const [user, wagerKP] of [[user1, wager1KP], [user2, wager2KP]]) {
+      const wagerDetail = await program.account.wagerDetail.fetch(wagerKP.publicKey);
+      const userAccountBeforeClaim = await program.account.userAccount.fetch(user.userAccountKP.publicKey);
+
+      await claimWinnings(user, wagerKP);
+
+      const expectedWinnings = wagerDetail.betValue.mul(betStateAfterResolution.staticTotalPool).div(winningPool);
+      const userAccountAfterClaim = await program.account.userAccount.fetch(user.userAccountKP.publicKey);
+      assert.ok(userAccountAfterClaim.currentBalance.eq(userAccountBeforeClaim.currentBalance.add(expectedWinnings)));
+    }
+
+    await expectProgramError(claimWinnings(user3, wager3KP), "NonWinningParty");
+
+  });
+
+  it('Voids a bet state nobody won when its first losing wager is closed and refunds every wager instead of paying the creator', async() => {
+
+    const oracleGuards = {
+      maxStalenessSecs: new anchor.BN(60),
+      maxConfidenceBps: 0,
+      retryWindowSecs: new anchor.BN(60 * 60),
+    };
+
+    const user1 = await createFundedUserAccount(LAMPORTS_PER_SOL * 1);
+    const user2 = await createFundedUserAccount(LAMPORTS_PER_SOL * 1);
+
+    const feed = await createMockPythFeed(mockOracle, { price: 72545000000, conf: 0, expo: -8 });
+    const betStateKP = await createOracleBetState({ feed, durationMs: 6 * 1000, oracleGuards });
+
+    const wager1KP = await placeWager(betStateKP, user1, 0, LAMPORTS_PER_SOL * 0.5);
+    const wager2KP = await placeWager(betStateKP, user2, 1, LAMPORTS_PER_SOL * 0.25);
+
+    await sleep(8 * 1000);
+
+    // +0.50% lands in a range nobody backed
+    await setMockPythPrice(mockOracle, feed, { price: 72907725000, conf: 0, expo: -8 });
+    await resolveBetState(betStateKP, feed);
+    assert.equal(BET_RANGES[(await program.account.betState.fetch(betStateKP.publicKey)).winningBetRange], "zeroToPosOne");
+
+    const wagerAccounts = async (user, wagerKP) => ({
+      betState: betStateKP.publicKey,
+      wagerDetail: wagerKP.publicKey,
+      userAccount: user.userAccountKP.publicKey,
+      bettorPosition: await findBettorPosition(betStateKP, user.userAccountKP),
+      bettor: user.userKP.publicKey
+    });
+
+    // The creator can't settle while the stakes are still in the bet state
+    const settleAccounts = {
+      betState: betStateKP.publicKey,
+      creatorBond: await findCreatorBondPDA(betStateKP),
+      betCreator: providerWallet.publicKey,
+    };
+    await expectProgramError(program.rpc.settleBetState({ accounts: settleAccounts }), "FundsStillInPlay");
+
+    const wager1 = await program.account.wagerDetail.fetch(wager1KP.publicKey);
+    const user1AccountBeforeClose = await program.account.userAccount.fetch(user1.userAccountKP.publicKey);
+    await program.rpc.closeLosingWager({ accounts: await wagerAccounts(user1, wager1KP), signers: [user1.userKP] });
+    const user1AccountAfterClose = await program.account.userAccount.fetch(user1.userAccountKP.publicKey);
+    assert.ok(user1AccountAfterClose.currentBalance.eq(user1AccountBeforeClose.currentBalance.add(wager1.betValue)));
+    assert.equal(BET_STATE_STATUSES[(await program.account.betState.fetch(betStateKP.publicKey)).status], "voided");
+
+    // The rest are refunded like any other voided bet state
+    await expectProgramError(
+      program.rpc.closeLosingWager({ accounts: await wagerAccounts(user2, wager2KP), signers: [user2.userKP] }),
+      "BetStillOpen"
+    );
+    const wager2 = await program.account.wagerDetail.fetch(wager2KP.publicKey);
+    const user2AccountBeforeRefund = await program.account.userAccount.fetch(user2.userAccountKP.publicKey);
+    await program.rpc.refundVoidedWager({ accounts: await wagerAccounts(user2, wager2KP), signers: [user2.userKP] });
+    const user2AccountAfterRefund = await program.account.userAccount.fetch(user2.userAccountKP.publicKey);
+    assert.ok(user2AccountAfterRefund.currentBalance.eq(user2AccountBeforeRefund.currentBalance.add(wager2.betValue)));
+
+    const betStateBeforeSettling = await program.account.betState.fetch(betStateKP.publicKey);
+    assert.equal(betStateBeforeSettling.openWagers, 0);
+    assert.ok(betStateBeforeSettling.runningTotalPool.eqn(0));
+    await program.rpc.settleBetState({ accounts: settleAccounts });
+
+  });
+
+  it('Resolves up/down markets above or below their snapshot alongside range markets, refunding a close inside the dead zone', async() => {
+
+    const oracleGuards = {
+      maxStalenessSecs: new anchor.BN(60),
+      maxConfidenceBps: 0,
+      retryWindowSecs: new anchor.BN(60 * 60),
+    };
+    // Up/down outcomes are stored the same way as ranges, a bucket index plus one
+    const UP_DOWN_OUTCOMES = ["notAvailable", "down", "up"];
+    const upDown = { upDown: { deadZoneBps: 10 } };
+
+    const user1 = await createFundedUserAccount(LAMPORTS_PER_SOL * 1);
+    const user2 = await createFundedUserAccount(LAMPORTS_PER_SOL * 1);
+    const user3 = await createFundedUserAccount(LAMPORTS_PER_SOL * 1);
+
+    const feed = await createMockPythFeed(mockOracle, { price: 72545000000, conf: 0, expo: -8 });
+    const deadZoneFeed = await createMockPythFeed(mockOracle, { price: 72545000000, conf: 0, expo: -8 });
+
+    await expectProgramError(
+      createOracleBetState({ feed, durationMs: 6 * 1000, oracleGuards, straddlePolicy: { split: {} }, marketType: upDown }),
+      "InvalidMarketType"
+    );
+    await expectProgramError(
+      createOracleBetState({ feed, durationMs: 6 * 1000, oracleGuards, marketType: { upDown: { deadZoneBps: 10000 } } }),
+      "InvalidMarketType"
+    );
+
+    const upDownBetStateKP = await createOracleBetState({ feed, durationMs: 6 * 1000, oracleGuards, marketType: upDown });
+    const rangeBetStateKP = await createOracleBetState({ feed, durationMs: 6 * 1000, oracleGuards });
+    const deadZoneBetStateKP = await createOracleBetState({ feed: deadZoneFeed, durationMs: 6 * 1000, oracleGuards, marketType: upDown });
+
+    // Only the two sides of the snapshot can be backed
+    await expectProgramError(placeWager(upDownBetStateKP, user1, 2, LAMPORTS_PER_SOL * 0.25), "InvalidBetRange");
+
+    const upWagerKP = await placeWager(upDownBetStateKP, user1, 1, LAMPORTS_PER_SOL * 0.25);
+    const downWagerKP = await placeWager(upDownBetStateKP, user2, 0, LAMPORTS_PER_SOL * 0.25);
+    await placeWager(rangeBetStateKP, user3, 4, LAMPORTS_PER_SOL * 0.25);
+    const deadZoneWagerKP = await placeWager(deadZoneBetStateKP, user3, 1, LAMPORTS_PER_SOL * 0.25);
+
+    await sleep(8 * 1000);
+
+    await setMockPythPrice(mockOracle, feed, { price: 72907725000, conf: 0, expo: -8 }); // +0.50%
+    await setMockPythPrice(mockOracle, deadZoneFeed, { price: 72581272500, conf: 0, expo: -8 }); // +0.05%
+    await resolveBetState(upDownBetStateKP, feed);
+    await resolveBetState(rangeBetStateKP, feed);
+    await resolveBetState(deadZoneBetStateKP, deadZoneFeed);
+
+    // The same close is Up on one market and a range on the other
+    const upDownAfterResolution = await program.account.betState.fetch(upDownBetStateKP.publicKey);
+    assert.equal(UP_DOWN_OUTCOMES[upDownAfterResolution.winningBetRange], "up");
+    const rangeAfterResolution = await program.account.betState.fetch(rangeBetStateKP.publicKey);
+    assert.equal(BET_RANGES[rangeAfterResolution.winningBetRange], "zeroToPosOne");
+
+    const claimWinnings = async (user, wagerKP) => program.rpc.claimWinnings({
+      accounts: {
+        betState: upDownBetStateKP.publicKey,
+        wagerDetail: wagerKP.publicKey,
+        userAccount: user.userAccountKP.publicKey,
+        bettorPosition: await findBettorPosition(upDownBetStateKP, user.userAccountKP),
+        bettor: user.userKP.publicKey
+      },
+      signers: [user.userKP]
+    });
+
+    const user1AccountBeforeClaim = await program.account.userAccount.fetch(user1.userAccountKP.publicKey);
+    await claimWinnings(user1, upWagerKP);
+    const user1AccountAfterClaim = await program.account.userAccount.fetch(user1.userAccountKP.publicKey);
+    assert.ok(user1AccountAfterClaim.currentBalance.eq(user1AccountBeforeClaim.currentBalance.add(upDownAfterResolution.staticTotalPool)));
+    await expectProgramError(claimWinnings(user2, downWagerKP), "NonWinningParty");
+
+    // A close within 0.10% of the snapshot gives every stake back
+    const deadZoneAfterResolution = await program.account.betState.fetch(deadZoneBetStateKP.publicKey);
+    assert.equal(BET_STATE_STATUSES[deadZoneAfterResolution.status], "voided");
+    assert.ok(deadZoneAfterResolution.closingMantissa.eq(new anchor.BN(72581272500)));
+
+    const deadZoneWager = await program.account.wagerDetail.fetch(deadZoneWagerKP.publicKey);
+    const user3AccountBeforeRefund = await program.account.userAccount.fetch(user3.userAccountKP.publicKey);
+    await program.rpc.refundVoidedWager({
+      accounts: {
+        betState: deadZoneBetStateKP.publicKey,
+        wagerDetail: deadZoneWagerKP.publicKey,
+        userAccount: user3.userAccountKP.publicKey,
+        bettorPosition: await findBettorPosition(deadZoneBetStateKP, user3.userAccountKP),
+        bettor: user3.userKP.publicKey,
+      },
+      signers: [user3.userKP]
+    });
+    const user3AccountAfterRefund = await program.account.userAccount.fetch(user3.userAccountKP.publicKey);
+    assert.ok(user3AccountAfterRefund.currentBalance.eq(user3AccountBeforeRefund.currentBalance.add(deadZoneWager.betValue)));
+
+  });
+
+  it('Resolves over/under markets against their strikes at the snapshot\'s exponent, a close on a strike going by the boundary rule', async() => {
+
+    const oracleGuards = {
+      maxStalenessSecs: new anchor.BN(60),
+      maxConfidenceBps: 0,
+      retryWindowSecs: new anchor.BN(60 * 60),
+    };
+    // Under $240, $240 to $250, $250 to $260 and over $260
+    const overUnder = { overUnder: { strikes: [new anchor.BN(240), new anchor.BN(250), new anchor.BN(260)], expo: 0 } };
+    const strikeBucket = (betState) => betState.winningBetRange - 1;
+
+    const user1 = await createFundedUserAccount(LAMPORTS_PER_SOL * 1);
+    const user2 = await createFundedUserAccount(LAMPORTS_PER_SOL * 1);
+
+    const feed = await createMockPythFeed(mockOracle, { price: 24500000000, conf: 0, expo: -8 });
+
+    await expectProgramError(
+      createOracleBetState({
+        feed,
+        durationMs: 6 * 1000,
+        oracleGuards,
+        marketType: { overUnder: { strikes: [new anchor.BN(250), new anchor.BN(240)], expo: 0 } },
+      }),
+      "InvalidMarketType"
+    );
+    // $250.000000001 can't be held at the feed's 8 decimals
+    await expectProgramError(
+      createOracleBetState({
+        feed,
+        durationMs: 6 * 1000,
+        oracleGuards,
+        marketType: { overUnder: { strikes: [new anchor.BN(250000000001)], expo: -9 } },
+      }),
+      "InvalidMarketType"
+    );
+
+    const lowerInclusiveKP = await createOracleBetState({ feed, durationMs: 6 * 1000, oracleGuards, marketType: overUnder });
+    const upperInclusiveKP = await createOracleBetState({
+      feed,
+      durationMs: 6 * 1000,
+      oracleGuards,
+      boundaryRule: { upperInclusive: {} },
+      marketType: overUnder,
+    });
+
+    const lowerInclusive = await program.account.betState.fetch(lowerInclusiveKP.publicKey);
+    assert.equal(lowerInclusive.strikeCount, 3);
+    assert.deepEqual(lowerInclusive.strikes.slice(0, 3).map(strike => strike.toString()), ["24000000000", "25000000000", "26000000000"]);
+
+    // Three strikes make four outcomes
+    await expectProgramError(placeWager(lowerInclusiveKP, user1, 4, LAMPORTS_PER_SOL * 0.25), "InvalidBetRange");
+
+    const overWagerKP = await placeWager(lowerInclusiveKP, user1, 2, LAMPORTS_PER_SOL * 0.25);
+    const underWagerKP = await placeWager(lowerInclusiveKP, user2, 1, LAMPORTS_PER_SOL * 0.25);
+
+    await sleep(8 * 1000);
+
+    // Exactly on the $250 strike
+    await setMockPythPrice(mockOracle, feed, { price: 25000000000, conf: 0, expo: -8 });
+    await resolveBetState(lowerInclusiveKP, feed);
+    await resolveBetState(upperInclusiveKP, feed);
+
+    const lowerInclusiveAfterResolution = await program.account.betState.fetch(lowerInclusiveKP.publicKey);
+    assert.equal(strikeBucket(lowerInclusiveAfterResolution), 2);
+    assert.equal(strikeBucket(await program.account.betState.fetch(upperInclusiveKP.publicKey)), 1);
+
+    const claimWinnings = async (user, wagerKP) => program.rpc.claimWinnings({
+      accounts: {
+        betState: lowerInclusiveKP.publicKey,
+        wagerDetail: wagerKP.publicKey,
+        userAccount: user.userAccountKP.publicKey,
+        bettorPosition: await findBettorPosition(lowerInclusiveKP, user.userAccountKP),
+        bettor: user.userKP.publicKey
+      },
+      signers: [user.userKP]
+    });
+
+    const userAccountBeforeClaim = await program.account.userAccount.fetch(user1.userAccountKP.publicKey);
+    await claimWinnings(user1, overWagerKP);
+    const userAccountAfterClaim = await program.account.userAccount.fetch(user1.userAccountKP.publicKey);
+    assert.ok(userAccountAfterClaim.currentBalance.eq(userAccountBeforeClaim.currentBalance.add(lowerInclusiveAfterResolution.staticTotalPool)));
+    await expectProgramError(claimWinnings(user2, underWagerKP), "NonWinningParty");
+
+  });
+
+  it('Resolves relative-performance markets on the spread between their asset\'s move and their benchmark\'s', async() => {
+
+    const oracleGuards = {
+      maxStalenessSecs: new anchor.BN(60),
+      maxConfidenceBps: 0,
+      retryWindowSecs: new anchor.BN(60 * 60),
+    };
+
+    const user1 = await createFundedUserAccount(LAMPORTS_PER_SOL * 1);
+    const user2 = await createFundedUserAccount(LAMPORTS_PER_SOL * 1);
+
+    const feed = await createMockPythFeed(mockOracle, { price: 25000000000, conf: 0, expo: -8 });
+    const benchmarkFeed = await createMockPythFeed(mockOracle, { price: 18000000000, conf: 0, expo: -8 });
+    const symbol = symbolForFeed(feed);
+    const benchmarkSymbol = symbolForFeed(benchmarkFeed);
+    const benchmarkAsset = await ensureAsset(benchmarkSymbol, benchmarkFeed);
+
+    const relativePerformance = (benchmarkSymbol: string) => ({ relativePerformance: { benchmarkSymbol, bucketLayout: { bucketWidthBps: 100 } } });
+    const createRelativeBetState = (benchmarkSymbol: string, benchmarkAccounts: anchor.web3.PublicKey[]) => createOracleBetState({
+      feed,
+      durationMs: 6 * 1000,
+      oracleGuards,
+      marketType: relativePerformance(benchmarkSymbol),
+      benchmarkAccounts,
+    });
+
+    // The benchmark's asset entry and feed have to be passed, and it can't be the bet's own asset
+    await expectProgramError(createRelativeBetState(benchmarkSymbol, []), "InvalidOracleSources");
+    await expectProgramError(createRelativeBetState(symbol, [await findAssetPDA(symbol), feed]), "InvalidMarketType");
+    await expectProgramError(createRelativeBetState(benchmarkSymbol, [benchmarkAsset, feed]), "AssetOracleMismatch");
+
+    const betStateKP = await createRelativeBetState(benchmarkSymbol, [benchmarkAsset, benchmarkFeed]);
+
+    const betState = await program.account.betState.fetch(betStateKP.publicKey);
+    assert.equal(decodeSymbol(betState.benchmarkSymbol), benchmarkSymbol);
+    assert.ok(betState.benchmarkOracleFeed.equals(benchmarkFeed));
+    assert.ok(betState.benchmarkSnapshotMantissa.eq(new anchor.BN(18000000000)));
+
+    // Outperforming by 1% to 2%, and by up to 1%
+    const wager1KP = await placeWager(betStateKP, user1, 5, LAMPORTS_PER_SOL * 0.25);
+    const wager2KP = await placeWager(betStateKP, user2, 4, LAMPORTS_PER_SOL * 0.25);
+
+    await sleep(8 * 1000);
+
+    // +2.00% against +0.50%, a spread of 150 bps
+    await setMockPythPrice(mockOracle, feed, { price: 25500000000, conf: 0, expo: -8 });
+    await setMockPythPrice(mockOracle, benchmarkFeed, { price: 18090000000, conf: 0, expo: -8 });
+
+    await expectProgramError(resolveBetState(betStateKP, feed), "InvalidOracleSources");
+    await expectProgramError(resolveBetState(betStateKP, feed, [], [feed]), "InvalidOracleFeed");
+    await resolveBetState(betStateKP, feed, [], [benchmarkFeed]);
+
+    const betStateAfterResolution = await program.account.betState.fetch(betStateKP.publicKey);
+    assert.equal(BET_RANGES[betStateAfterResolution.winningBetRange], "posOneToPosTwo");
+    assert.ok(betStateAfterResolution.benchmarkClosingMantissa.eq(new anchor.BN(18090000000)));
+
+    const claimWinnings = async (user, wagerKP) => program.rpc.claimWinnings({
+      accounts: {
+        betState: betStateKP.publicKey,
+        wagerDetail: wagerKP.publicKey,
+        userAccount: user.userAccountKP.publicKey,
+        bettorPosition: await findBettorPosition(betStateKP, user.userAccountKP),
+        bettor: user.userKP.publicKey
+      },
+      signers: [user.userKP]
+    });
+
+    const userAccountBeforeClaim = await program.account.userAccount.fetch(user1.userAccountKP.publicKey);
+    await claimWinnings(user1, wager1KP);
+    const userAccountAfterClaim = await program.account.userAccount.fetch(user1.userAccountKP.publicKey);
+    assert.ok(userAccountAfterClaim.currentBalance.eq(userAccountBeforeClaim.currentBalance.add(betStateAfterResolution.staticTotalPool)));
+    await expectProgramError(claimWinnings(user2, wager2KP), "NonWinningParty");
+
+  });
+
+  it('Resolves a bet state from its oracle feed only once the price passes the staleness, status and confidence guards', async() => {
+
+    const feed = await createMockPythFeed(mockOracle, { price: 72545000000, conf: 1000000, expo: -8 });
+
+    const user1 = await createFundedUserAccount(LAMPORTS_PER_SOL * 1);
+    const user2 = await createFundedUserAccount(LAMPORTS_PER_SOL * 1);
+
+    const betStateKP = await createOracleBetState({
+      feed,
+      durationMs: 6 * 1000,
+      oracleGuards: {
+        maxStalenessSecs: new anchor.BN(60),
+        maxConfidenceBps: 100,
+        retryWindowSecs: new anchor.BN(60 * 60),
+      },
+    });
+
+    await placeWager(betStateKP, user1, 1, LAMPORTS_PER_SOL * 0.5);
+    await placeWager(betStateKP, user2, 4, LAMPORTS_PER_SOL * 0.5);
+
+    await expectProgramError(resolveBetState(betStateKP, feed), "BetNotYetEnded");
+
+    await sleep(8 * 1000);
+
+    // Published ten minutes ago
+    await setMockPythPrice(mockOracle, feed, { price: 70700000000, conf: 1000000, expo: -8, publishTime: nowInSeconds() - 600 });
+    await expectProgramError(resolveBetState(betStateKP, feed), "OraclePriceStale");
+
+    await setMockPythPrice(mockOracle, feed, { price: 70700000000, conf: 1000000, expo: -8, status: PYTH_STATUS_UNKNOWN });
+    await expectProgramError(resolveBetState(betStateKP, feed), "OraclePriceNotTrading");
+
+    await setMockPythPrice(mockOracle, feed, { price: 70700000000, conf: 1000000000, expo: -8 });
+    await expectProgramError(resolveBetState(betStateKP, feed), "OracleConfidenceTooWide");
+
+    // 704.00 +/- 1.00 covers the -3% boundary at 703.69
+    await setMockPythPrice(mockOracle, feed, { price: 70400000000, conf: 100000000, expo: -8 });
+    await expectProgramError(resolveBetState(betStateKP, feed), "OracleConfidenceStraddlesBoundary");
+
+    // 707.00 is a -2.54% move
+    await setMockPythPrice(mockOracle, feed, { price: 70700000000, conf: 1000000, expo: -8 });
+    await resolveBetState(betStateKP, feed);
+
+    const betStateAfterResolution = await program.account.betState.fetch(betStateKP.publicKey);
+    assert.equal(BET_STATE_STATUSES[betStateAfterResolution.status], "closed");
+    assert.equal(BET_RANGES[betStateAfterResolution.winningBetRange], "negThreeToNegTwo");
+
+  });
+
+  it('Rejects a closing price published too long after the end time', async() => {
+
+    const feed = await createMockPythFeed(mockOracle, { price: 72545000000, conf: 1000000, expo: -8 });
+
+    const betStateKP = await createOracleBetState({
+      feed,
+      durationMs: 2 * 1000,
+      oracleGuards: {
+        maxStalenessSecs: new anchor.BN(10),
+        maxConfidenceBps: 100,
+        retryWindowSecs: new anchor.BN(60 * 60),
+      },
+    });
+    const endSecs = Math.ceil((await program.account.betState.fetch(betStateKP.publicKey)).endTime.toNumber() / 1000);
+
+    await sleep(15 * 1000);
+
+    // Fresh, but published more than the staleness allowance after the bet ended
+    await setMockPythPrice(mockOracle, feed, { price: 70700000000, conf: 1000000, expo: -8, publishTime: endSecs + 11 });
+    await expectProgramError(resolveBetState(betStateKP, feed), "OraclePriceStale");
+
+    await setMockPythPrice(mockOracle, feed, { price: 70700000000, conf: 1000000, expo: -8, publishTime: endSecs + 8 });
+    await resolveBetState(betStateKP, feed);
+
+    const betStateAfterResolution = await program.account.betState.fetch(betStateKP.publicKey);
+    assert.equal(BET_STATE_STATUSES[betStateAfterResolution.status], "closed");
+    assert.ok(betStateAfterResolution.closingPublishTime.eqn(endSecs + 8));
+
+  });
+
+  it('Voids a bet state whose oracle price stays unusable past the retry window and refunds its wagers', async() => {
+
+    const feed = await createMockPythFeed(mockOracle, { price: 72545000000, conf: 1000000, expo: -8 });
+
+    const user1 = await createFundedUserAccount(LAMPORTS_PER_SOL * 1);
+
+    const oracleGuards = {
+      maxStalenessSecs: new anchor.BN(60),
+      maxConfidenceBps: 0,
+      retryWindowSecs: new anchor.BN(2),
+    };
+
+    // A bet has to give its oracle some time past the end before it can be voided
+    await expectProgramError(
+      createOracleBetState({ feed, durationMs: 4 * 1000, oracleGuards: { ...oracleGuards, retryWindowSecs: new anchor.BN(0) } }),
+      "InvalidOracleGuards"
+    );
+
+    const betStateKP = await createOracleBetState({ feed, durationMs: 4 * 1000, oracleGuards });
+
+    const wagerDetailKP = await placeWager(betStateKP, user1, 3, LAMPORTS_PER_SOL * 0.5);
+    const wagerDetail = await program.account.wagerDetail.fetch(wagerDetailKP.publicKey);
+    const userAccountAfterWager = await program.account.userAccount.fetch(user1.userAccountKP.publicKey);
+
+    await sleep(8 * 1000);
+
+    await setMockPythPrice(mockOracle, feed, { price: 70700000000, conf: 1000000, expo: -8, status: PYTH_STATUS_UNKNOWN });
+
+    // Past the retry window, but a feed the bet doesn't have is the caller's mistake and voids nothing
+    await expectProgramError(resolveBetState(betStateKP, feed, [], [feed]), "InvalidOracleSources");
+    assert.equal(BET_STATE_STATUSES[(await program.account.betState.fetch(betStateKP.publicKey)).status], "open");
+
+    await resolveBetState(betStateKP, feed);
+
+    const betStateAfterResolution = await program.account.betState.fetch(betStateKP.publicKey);
+    assert.equal(BET_STATE_STATUSES[betStateAfterResolution.status], "voided");
+    assert.equal(BET_RANGES[betStateAfterResolution.winningBetRange], "notAvailable");
+
+    await program.rpc.refundVoidedWager({
+      accounts: {
+        betState: betStateKP.publicKey,
+        wagerDetail: wagerDetailKP.publicKey,
+        userAccount: user1.userAccountKP.publicKey,
+        bettorPosition: await findBettorPosition(betStateKP, user1.userAccountKP),
+        bettor: user1.userKP.publicKey,
+      },
+      signers: [user1.userKP]
+    });
+
+    const userAccountAfterRefund = await program.account.userAccount.fetch(user1.userAccountKP.publicKey);
+    assert.equal(userAccountAfterRefund.activeWagers.length, 0);
+    assert.ok(userAccountAfterRefund.currentBalance.eq(userAccountAfterWager.currentBalance.add(wagerDetail.betValue)));
+
+  });
+
+  it('Resolves a bet state through the Switchboard adapter', async() => {
+
+    // 725.45 with 2 decimals
+    const feed = await createMockSwitchboardFeed(mockOracle, { mantissa: 72545, scale: 2 });
+
+    const betStateKP = await createOracleBetState({
+      feed,
+      durationMs: 2 * 1000,
+      oracleGuards: {
+        maxStalenessSecs: new anchor.BN(60),
+        maxConfidenceBps: 100,
+        retryWindowSecs: new anchor.BN(60 * 60),
+      },
+      oracleKind: { switchboard: {} },
+    });
+
+    const betStateAccount = await program.account.betState.fetch(betStateKP.publicKey);
+    assert.equal(ORACLE_KINDS[betStateAccount.oracleKind], "switchboard");
+    assert.ok(betStateAccount.oracleFeed.equals(feed));
+
+    await sleep(4 * 1000);
+
+    // Not enough oracles answered the round
+    await setMockSwitchboardRound(mockOracle, feed, { mantissa: 74200, scale: 2, numSuccess: 1 });
+    await expectProgramError(resolveBetState(betStateKP, feed), "OraclePriceNotTrading");
+
+    // 742.00 +/- 0.50 is a +2.21% to +2.35% move
+    await setMockSwitchboardRound(mockOracle, feed, { mantissa: 742000, scale: 3, stdDeviationMantissa: 500 });
+    await resolveBetState(betStateKP, feed);
+
+    const betStateAfterResolution = await program.account.betState.fetch(betStateKP.publicKey);
+    assert.equal(BET_RANGES[betStateAfterResolution.winningBetRange], "posTwoToPosThree");
+
+  });
+
+  it('Resolves a bet state from a price signed by a whitelisted publisher', async() => {
+
+    // Signed feeds have no account, the key only names the feed in the signed messages
+    const feed = anchor.web3.Keypair.generate().publicKey;
+
+    const oracleGuards = {
+      maxStalenessSecs: new anchor.BN(60),
+      maxConfidenceBps: 100,
+      retryWindowSecs: new anchor.BN(60 * 60),
+    };
+
+    // The opening price has to be signed as well, by the publisher's first message at or after the start time
+    const startSecs = nowInSeconds();
+    await expectProgramError(createOracleBetState({ feed, durationMs: 2 * 1000, oracleGuards, oracleKind: { signedFeed: {} } }), "MissingSignedPrice");
+    await expectProgramError(
+      createOracleBetState({
+        feed,
+        durationMs: 2 * 1000,
+        oracleGuards,
+        oracleKind: { signedFeed: {} },
+        start: startSecs * 1000,
+        preInstructions: [
+          signedPriceInstruction(pricePublisherKP, { feed, price: 72545, conf: 10, expo: -2, publishTime: startSecs - 1 })
+        ],
+      }),
+      "SignedPriceNotAtSettlement"
+    );
+
+    const betStateKP = await createOracleBetState({
+      feed,
+      durationMs: 2 * 1000,
+      oracleGuards,
+      oracleKind: { signedFeed: {} },
+      start: startSecs * 1000,
+      preInstructions: [
+        signedPriceInstruction(pricePublisherKP, { feed, price: 72545, conf: 10, expo: -2, publishTime: startSecs })
+      ],
+    });
+
+    const betStateAccount = await program.account.betState.fetch(betStateKP.publicKey);
+    assert.ok(betStateAccount.snapshotMantissa.eq(new anchor.BN(72545)));
+    assert.equal(betStateAccount.snapshotExpo, -2);
+
+    await sleep(4 * 1000);
+
+    await expectProgramError(resolveBetState(betStateKP, feed), "MissingSignedPrice");
+
+    const unknownPublisherKP = anchor.web3.Keypair.generate();
+    await expectProgramError(
+      resolveBetState(betStateKP, feed, [signedPriceInstruction(unknownPublisherKP, { feed, price: 71800, conf: 10, expo: -2 })]),
+      "UnknownPricePublisher"
+    );
+
+    const otherFeed = anchor.web3.Keypair.generate().publicKey;
+    await expectProgramError(
+      resolveBetState(betStateKP, feed, [signedPriceInstruction(pricePublisherKP, { feed: otherFeed, price: 71800, conf: 10, expo: -2 })]),
+      "MissingSignedPrice"
+    );
+
+    // 718.00 is a -1.03% move, signed as the publisher's first message at or after the end time
+    const endSecs = Math.ceil(betStateAccount.endTime.toNumber() / 1000);
+    await resolveBetState(betStateKP, feed, [
+      signedPriceInstruction(pricePublisherKP, { feed, price: 71800, conf: 10, expo: -2, publishTime: endSecs, prevPublishTime: endSecs - 1 })
+    ]);
+
+    const betStateAfterResolution = await program.account.betState.fetch(betStateKP.publicKey);
+    assert.equal(BET_RANGES[betStateAfterResolution.winningBetRange], "negTwoToNegOne");
+
+  });
+
+  it('Only settles a signed feed on the publisher\'s first message at or after the end time', async() => {
+
+    const feed = anchor.web3.Keypair.generate().publicKey;
+
+    const oracleGuards = {
+      maxStalenessSecs: new anchor.BN(60),
+      maxConfidenceBps: 100,
+      retryWindowSecs: new anchor.BN(60 * 60),
+    };
+
+    const startSecs = nowInSeconds();
+    const betStateKP = await createOracleBetState({
+      feed,
+      durationMs: 2 * 1000,
+      oracleGuards,
+      oracleKind: { signedFeed: {} },
+      start: startSecs * 1000,
+      preInstructions: [
+        signedPriceInstruction(pricePublisherKP, { feed, price: 72545, conf: 10, expo: -2, publishTime: startSecs })
+      ],
+    });
+
+    await sleep(4 * 1000);
+
+    const endSecs = Math.ceil((await program.account.betState.fetch(betStateKP.publicKey)).endTime.toNumber() / 1000);
+    const signedAt = (price: number, publishTime: number, prevPublishTime: number) =>
+      signedPriceInstruction(pricePublisherKP, { feed, price, conf: 10, expo: -2, publishTime, prevPublishTime });
+
+    // A message from before the end time is still fresh and validly signed, but it isn't the settlement price
+    await expectProgramError(resolveBetState(betStateKP, feed, [signedAt(74000, endSecs - 1, endSecs - 2)]), "SignedPriceNotAtSettlement");
+    // Nor is one that came after another message at or after the end time
+    await expectProgramError(resolveBetState(betStateKP, feed, [signedAt(74000, endSecs + 1, endSecs)]), "SignedPriceNotAtSettlement");
+
+    await resolveBetState(betStateKP, feed, [signedAt(71800, endSecs, endSecs - 1)]);
+
+    const betStateAfterResolution = await program.account.betState.fetch(betStateKP.publicKey);
+    assert.ok(betStateAfterResolution.closingMantissa.eq(new anchor.BN(71800)));
+    assert.equal(BET_RANGES[betStateAfterResolution.winningBetRange], "negTwoToNegOne");
+
+  });
+
+  it('Resolves a multi-oracle bet state from the median of the sources that pass the guards', async() => {
+
+    const guards = {
+      maxStalenessSecs: new anchor.BN(60),
+      maxConfidenceBps: 100,
+      retryWindowSecs: new anchor.BN(60 * 60),
+    };
+
+    const pythFeed = await createMockPythFeed(mockOracle, { price: 72545000000, conf: 1000000, expo: -8 });
+    const switchboardFeed = await createMockSwitchboardFeed(mockOracle, { mantissa: 72545, scale: 2 });
+    const secondPythFeed = await createMockPythFeed(mockOracle, { price: 72545000000, conf: 1000000, expo: -8 });
+    const signedFeed = anchor.web3.Keypair.generate().publicKey;
+
+    const secondaryOracles = [
+      { kind: { switchboard: {} }, feed: switchboardFeed },
+      { kind: { pyth: {} }, feed: secondPythFeed },
+      { kind: { signedFeed: {} }, feed: signedFeed },
+    ];
+    const secondaryFeeds = secondaryOracles.map(source => source.feed);
+
+    await expectProgramError(createOracleBetState({
+      feed: pythFeed,
+      durationMs: 2 * 1000,
+      oracleGuards: guards,
+      secondaryOracles,
+      oracleQuorum: 5,
+    }), "InvalidOracleSources");
+    await expectProgramError(
+      createOracleBetState({
+        feed: pythFeed,
+        durationMs: 2 * 1000,
+        oracleGuards: guards,
+        secondaryOracles: [...secondaryOracles, { kind: { pyth: {} }, feed: secondPythFeed }],
+        oracleQuorum: 3,
+      }),
+      "InvalidOracleSources"
+    );
+
+    const startSecs = nowInSeconds();
+    const openingPrice = signedPriceInstruction(pricePublisherKP, { feed: signedFeed, price: 72545, conf: 5, expo: -2, publishTime: startSecs });
+    const betStateKP = await createOracleBetState({
+      feed: pythFeed,
+      durationMs: 2 * 1000,
+      oracleGuards: guards,
+      secondaryOracles,
+      oracleQuorum: 3,
+      start: startSecs * 1000,
+      preInstructions: [openingPrice],
+    });
+    const quorumBetStateKP = await createOracleBetState({
+      feed: pythFeed,
+      durationMs: 2 * 1000,
+      oracleGuards: guards,
+      secondaryOracles,
+      oracleQuorum: 4,
+      start: startSecs * 1000,
+      preInstructions: [openingPrice],
+    });
+
+    await sleep(4 * 1000);
+
+    // 735.00, 736.00 and 737.00 all land in +1% to +2%, the stale second Pyth feed is left out
+    await setMockPythPrice(mockOracle, pythFeed, { price: 73500000000, conf: 1000000, expo: -8 });
+    await setMockSwitchboardRound(mockOracle, switchboardFeed, { mantissa: 73700, scale: 2 });
+    await setMockPythPrice(mockOracle, secondPythFeed, { price: 70000000000, conf: 1000000, expo: -8, publishTime: nowInSeconds() - 600 });
+    const endSecs = Math.ceil((await program.account.betState.fetch(betStateKP.publicKey)).endTime.toNumber() / 1000);
+    const signedPrice = signedPriceInstruction(pricePublisherKP, {
+      feed: signedFeed, price: 73600, conf: 5, expo: -2, publishTime: endSecs, prevPublishTime: endSecs - 1
+    });
+
+    await expectProgramError(resolveBetState(quorumBetStateKP, pythFeed, [signedPrice], secondaryFeeds), "OracleQuorumNotReached");
+    await expectProgramError(resolveBetState(betStateKP, pythFeed, [signedPrice], secondaryFeeds.slice(0, 2)), "InvalidOracleSources");
+
+    await resolveBetState(betStateKP, pythFeed, [signedPrice], secondaryFeeds);
+
+    const betStateAfterResolution = await program.account.betState.fetch(betStateKP.publicKey);
+    assert.equal(BET_RANGES[betStateAfterResolution.winningBetRange], "posOneToPosTwo");
+    assert.equal(betStateAfterResolution.closingExpo, -8);
+    assert.ok(betStateAfterResolution.closingMantissa.eq(new anchor.BN(73600000000)));
+    assert.deepEqual(
+      betStateAfterResolution.resolutionSources.slice(0, betStateAfterResolution.resolutionSourceCount).map(source => source.toBase58()),
+      [pythFeed, switchboardFeed, signedFeed].map(source => source.toBase58())
+    );
+
+  });
+
+  const findDisputePDA = async (betStateKP) => (await anchor.web3.PublicKey.findProgramAddress(
+    [Buffer.from("dispute"), betStateKP.publicKey.toBuffer()],
+    program.programId
+  ))[0];
+
+  const updateDisputeConfig = (arbitrationAuthority: anchor.web3.PublicKey, bond: number, windowSecs: number) => program.rpc.updateDisputeConfig(
+    arbitrationAuthority,
+    new anchor.BN(bond),
+    new anchor.BN(windowSecs),
+    {
+      accounts: {
+        config: configPDA,
+        admin: providerWallet.publicKey,
+      }
+    }
+  );
+
+  const claimWinningsFor = async (betStateKP, user, wagerKP) => program.rpc.claimWinnings({
+    accounts: {
+      betState: betStateKP.publicKey,
+      wagerDetail: wagerKP.publicKey,
+      userAccount: user.userAccountKP.publicKey,
+      bettorPosition: await findBettorPosition(betStateKP, user.userAccountKP),
+      bettor: user.userKP.publicKey
+    },
+    signers: [user.userKP]
+  });
+
+  const disputeOutcome = async (betStateKP, disputerKP, wagerKP, disputedBetRange: number) => program.rpc.disputeOutcome(disputedBetRange, {
+    accounts: {
+      betState: betStateKP.publicKey,
+      dispute: await findDisputePDA(betStateKP),
+      wagerDetail: wagerKP.publicKey,
+      config: configPDA,
+      disputer: disputerKP.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    },
+    signers: [disputerKP]
+  });
+
+  const ruleOnDispute = async (betStateKP, disputer: anchor.web3.PublicKey, authorityKP, correctedBetRange: number | null) => program.rpc.ruleOnDispute(correctedBetRange, {
+    accounts: {
+      betState: betStateKP.publicKey,
+      dispute: await findDisputePDA(betStateKP),
+      disputer,
+      creatorBond: await findCreatorBondPDA(betStateKP),
+      config: configPDA,
+      arbitrationAuthority: authorityKP.publicKey,
+    },
+    signers: [authorityKP]
+  });
+
+  it('Freezes claims while an outcome is disputed and settles the bond on the arbitration ruling', async() => {
+
+    const oracleGuards = {
+      maxStalenessSecs: new anchor.BN(60),
+      maxConfidenceBps: 100,
+      retryWindowSecs: new anchor.BN(60 * 60),
+    };
+    const arbitratorKP = anchor.web3.Keypair.generate();
+    const bond = LAMPORTS_PER_SOL * 0.1;
+
+    const user1 = await createFundedUserAccount(LAMPORTS_PER_SOL * 1);
+    const user2 = await createFundedUserAccount(LAMPORTS_PER_SOL * 1);
+    const outsider = await createFundedUserAccount(LAMPORTS_PER_SOL * 1);
+
+    // Bet states copy the window when they are created, so it is only switched on for these two
+    await updateDisputeConfig(arbitratorKP.publicKey, bond, 60);
+    const overturnedFeed = await createMockPythFeed(mockOracle, { price: 72545000000, conf: 1000000, expo: -8 });
+    const overturnedBetStateKP = await createOracleBetState({ feed: overturnedFeed, durationMs: 6 * 1000, oracleGuards });
+    const upheldFeed = await createMockPythFeed(mockOracle, { price: 72545000000, conf: 1000000, expo: -8 });
+    const upheldBetStateKP = await createOracleBetState({ feed: upheldFeed, durationMs: 6 * 1000, oracleGuards });
+    await updateDisputeConfig(providerWallet.publicKey, 0, 0);
+
+    const overturnedWager1KP = await placeWager(overturnedBetStateKP, user1, 2, LAMPORTS_PER_SOL * 0.25);
+    const overturnedWager2KP = await placeWager(overturnedBetStateKP, user2, 4, LAMPORTS_PER_SOL * 0.25);
+    const upheldWager1KP = await placeWager(upheldBetStateKP, user1, 2, LAMPORTS_PER_SOL * 0.25);
+    const upheldWager2KP = await placeWager(upheldBetStateKP, user2, 4, LAMPORTS_PER_SOL * 0.25);
+
+    await sleep(8 * 1000);
+
+    // 718.00 is a -1.03% move
+    for (const feed of [overturnedFeed, upheldFeed]) {
+      await setMockPythPrice(mockOracle, feed, { price: 71800000000, conf: 1000000, expo: -8 });
+    }
+    await resolveBetState(overturnedBetStateKP, overturnedFeed);
+    await resolveBetState(upheldBetStateKP, upheldFeed);
+
+    // Undisputed but still inside the window
+    await expectProgramError(claimWinningsFor(overturnedBetStateKP, user1, overturnedWager1KP), "ClaimsLocked");
+
+    await expectProgramError(disputeOutcome(overturnedBetStateKP, outsider.userKP, overturnedWager2KP, 4), "NotBettorOnBetState");
+    await expectProgramError(disputeOutcome(overturnedBetStateKP, user2.userKP, overturnedWager2KP, 2), "InvalidDisputeRuling");
+
+    const disputerBalanceBeforeDispute = await program.provider.connection.getBalance(user2.userKP.publicKey);
+    await disputeOutcome(overturnedBetStateKP, user2.userKP, overturnedWager2KP, 4);
+
+    const disputeAccount = await program.account.outcomeDispute.fetch(await findDisputePDA(overturnedBetStateKP));
+    assert.ok(disputeAccount.bond.eq(new anchor.BN(bond)));
+    assert.ok(disputeAccount.disputedBetRange.hasOwnProperty("zeroToPosOne"));
+
+    const betStateWhileDisputed = await program.account.betState.fetch(overturnedBetStateKP.publicKey);
+    assert.equal(DISPUTE_STATUSES[betStateWhileDisputed.disputeStatus], "pending");
+    await expectProgramError(claimWinningsFor(overturnedBetStateKP, user1, overturnedWager1KP), "ClaimsLocked");
+
+    await expectProgramError(ruleOnDispute(overturnedBetStateKP, user2.userKP.publicKey, user1.userKP, 4), "NotArbitrationAuthority");
+    // The role tests' fee payer isn't funded yet, so a bettor pays for the unsigned attempt
+    await expectUnsignedRejected(
+      program.instruction.ruleOnDispute(4, {
+        accounts: {
+          betState: overturnedBetStateKP.publicKey,
+          dispute: await findDisputePDA(overturnedBetStateKP),
+          disputer: user2.userKP.publicKey,
+          creatorBond: await findCreatorBondPDA(overturnedBetStateKP),
+          config: configPDA,
+          arbitrationAuthority: arbitratorKP.publicKey,
+        }
+      }),
+      arbitratorKP.publicKey,
+      user1.userKP
+    );
+
+    // Overturned, the bond comes back and the disputer's range wins
+    await ruleOnDispute(overturnedBetStateKP, user2.userKP.publicKey, arbitratorKP, 4);
+
+    const overturnedBetState = await program.account.betState.fetch(overturnedBetStateKP.publicKey);
+    assert.equal(DISPUTE_STATUSES[overturnedBetState.disputeStatus], "overturned");
+    assert.equal(BET_RANGES[overturnedBetState.winningBetRange], "zeroToPosOne");
+    assert.equal(await program.provider.connection.getBalance(user2.userKP.publicKey), disputerBalanceBeforeDispute);
+    assert.equal(await program.provider.connection.getAccountInfo(await findDisputePDA(overturnedBetStateKP)), null);
+
+    await expectProgramError(claimWinningsFor(overturnedBetStateKP, user1, overturnedWager1KP), "NonWinningParty");
+    await claimWinningsFor(overturnedBetStateKP, user2, overturnedWager2KP);
+
+    // Upheld, the bond is slashed to the arbitration authority
+    await disputeOutcome(upheldBetStateKP, user2.userKP, upheldWager2KP, 4);
+    await ruleOnDispute(upheldBetStateKP, user2.userKP.publicKey, arbitratorKP, null);
+
+    const upheldBetState = await program.account.betState.fetch(upheldBetStateKP.publicKey);
+    assert.equal(DISPUTE_STATUSES[upheldBetState.disputeStatus], "upheld");
+    assert.equal(BET_RANGES[upheldBetState.winningBetRange], "negTwoToNegOne");
+    assert.equal(await program.provider.connection.getBalance(arbitratorKP.publicKey), bond);
+
+    await claimWinningsFor(upheldBetStateKP, user1, upheldWager1KP);
+    await expectProgramError(claimWinningsFor(upheldBetStateKP, user2, upheldWager2KP), "NonWinningParty");
+
+  });
+
+  it('Unlocks claims once the dispute window passes without a dispute', async() => {
+
+    const oracleGuards = {
+      maxStalenessSecs: new anchor.BN(60),
+      maxConfidenceBps: 100,
+      retryWindowSecs: new anchor.BN(60 * 60),
+    };
+
+    const user1 = await createFundedUserAccount(LAMPORTS_PER_SOL * 1);
+    const user2 = await createFundedUserAccount(LAMPORTS_PER_SOL * 1);
+
+    await updateDisputeConfig(providerWallet.publicKey, LAMPORTS_PER_SOL * 0.1, 3);
+    const feed = await createMockPythFeed(mockOracle, { price: 72545000000, conf: 1000000, expo: -8 });
+    const betStateKP = await createOracleBetState({ feed, durationMs: 4 * 1000, oracleGuards });
+    await updateDisputeConfig(providerWallet.publicKey, 0, 0);
+
+    const wager1KP = await placeWager(betStateKP, user1, 2, LAMPORTS_PER_SOL * 0.25);
+    const wager2KP = await placeWager(betStateKP, user2, 4, LAMPORTS_PER_SOL * 0.25);
+
+    await sleep(6 * 1000);
+
+    await setMockPythPrice(mockOracle, feed, { price: 71800000000, conf: 1000000, expo: -8 });
+    await resolveBetState(betStateKP, feed);
+
+    await expectProgramError(claimWinningsFor(betStateKP, user1, wager1KP), "ClaimsLocked");
+
+    await sleep(5 * 1000);
+
+    await expectProgramError(disputeOutcome(betStateKP, user2.userKP, wager2KP, 4), "DisputeWindowClosed");
+    await claimWinningsFor(betStateKP, user1, wager1KP);
+
+  });
+
+  it('Decides a bet state once enough members of its resolver committee vote for the same outcome', async() => {
+
+    const oracleGuards = {
+      maxStalenessSecs: new anchor.BN(60),
+      maxConfidenceBps: 100,
+      retryWindowSecs: new anchor.BN(60 * 60),
+    };
+
+    const members = [anchor.web3.Keypair.generate(), anchor.web3.Keypair.generate(), anchor.web3.Keypair.generate()];
+    const outsiderKP = anchor.web3.Keypair.generate();
+    for (const memberKP of [...members, outsiderKP]) {
+      const airdropSig = await program.provider.connection.requestAirdrop(memberKP.publicKey, LAMPORTS_PER_SOL);
+      await program.provider.connection.confirmTransaction(airdropSig, "finalized");
+    }
+
+    const initializeCommittee = (committeeKP, memberKeys: anchor.web3.PublicKey[], threshold: number) => program.rpc.initializeResolverCommittee(
+      memberKeys,
+      threshold,
+      {
+        accounts: {
+          resolverCommittee: committeeKP.publicKey,
+          config: configPDA,
+          admin: providerWallet.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId
+        },
+        signers: [committeeKP]
+      }
+    );
+
+    const memberKeys = members.map(memberKP => memberKP.publicKey);
+    await expectProgramError(initializeCommittee(anchor.web3.Keypair.generate(), memberKeys, 4), "InvalidResolverCommittee");
+    await expectProgramError(initializeCommittee(anchor.web3.Keypair.generate(), [memberKeys[0], memberKeys[0]], 1), "InvalidResolverCommittee");
+
+    // 2 of 3
+    const committeeKP = anchor.web3.Keypair.generate();
+    await initializeCommittee(committeeKP, memberKeys, 2);
+
+    const feed = await createMockPythFeed(mockOracle, { price: 72545000000, conf: 1000000, expo: -8 });
+    const betStateKP = await createOracleBetState({ feed, durationMs: 3 * 1000, oracleGuards, resolverCommittee: committeeKP.publicKey });
+
+    const pendingResolutionPDA = (await anchor.web3.PublicKey.findProgramAddress(
+      [Buffer.from("resolution"), betStateKP.publicKey.toBuffer()],
+      program.programId
+    ))[0];
+
+    const voteOnOutcome = (voterKP, outcome: number) => program.rpc.voteOnOutcome(outcome, {
+      accounts: {
+        betState: betStateKP.publicKey,
+        resolverCommittee: committeeKP.publicKey,
+        pendingResolution: pendingResolutionPDA,
+        member: voterKP.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId
+      },
+      signers: [voterKP]
+    });
+
+    await expectProgramError(voteOnOutcome(members[0], 2), "BetNotYetEnded");
+
+    await sleep(5 * 1000);
+
+    await expectProgramError(voteOnOutcome(outsiderKP, 2), "NotCommitteeMember");
+
+    await voteOnOutcome(members[0], 2);
+    await expectProgramError(voteOnOutcome(members[0], 2), "AlreadyVoted");
+
+    // Two votes, but not for the same outcome
+    await voteOnOutcome(members[1], 3);
+
+    const betStateAfterSplitVote = await program.account.betState.fetch(betStateKP.publicKey);
+    assert.equal(BET_RANGES[betStateAfterSplitVote.winningBetRange], "notAvailable");
+
+    const pendingResolution = await program.account.pendingResolution.fetch(pendingResolutionPDA);
+    assert.equal(pendingResolution.votes.length, 2);
+
+    await voteOnOutcome(members[2], 2);
+
+    const betStateAfterDecision = await program.account.betState.fetch(betStateKP.publicKey);
+    assert.equal(BET_STATE_STATUSES[betStateAfterDecision.status], "closed");
+    assert.equal(BET_RANGES[betStateAfterDecision.winningBetRange], "negTwoToNegOne");
+
+    await expectProgramError(voteOnOutcome(members[1], 2), "BetAlreadyDecided");
+
+  });
+
+
+  // Role Tests //
+
+  // Pays for transactions in the role tests so the provider wallet, which holds the admin and creator roles, doesn't sign them
+  const feePayerKP = anchor.web3.Keypair.generate();
+
+  // Sends an instruction with `key` left unsigned, which the client would otherwise refuse to build
+  const expectUnsignedRejected = async (instruction: anchor.web3.TransactionInstruction, key: anchor.web3.PublicKey, payerKP = feePayerKP) => {
+    instruction.keys.forEach(meta => {
+      if (meta.pubkey.equals(key)) {
+        meta.isSigner = false;
+      }
+    });
+
+    try {
+      await anchor.web3.sendAndConfirmTransaction(program.provider.connection, new anchor.web3.Transaction().add(instruction), [payerKP]);
+    } catch (error) {
+      // 0xbc2 is AccountNotSigner
+      assert.ok(`${error}`.includes("custom program error: 0xbc2"), `${error}`);
+      return;
+    }
+    assert.fail(`Expected the instruction to fail without a signature from ${key.toBase58()}`);
+  };
+
+  // Checks a privileged instruction fails both for a key that doesn't hold the role and for the right key without its signature
+  const expectRoleEnforced = async (name: string, args: any[], accounts, role: string, code: string) => {
+    const imposterKP = anchor.web3.Keypair.generate();
+    await expectProgramError(
+      program.rpc[name](...args, { accounts: { ...accounts, [role]: imposterKP.publicKey }, signers: [imposterKP] }),
+      code
+    );
+    await expectUnsignedRejected(program.instruction[name](...args, { accounts }), accounts[role]);
+  };
+
+  it('Rejects every admin instruction from any key but the signing admin', async() => {
+
+    const airdropSig = await program.provider.connection.requestAirdrop(feePayerKP.publicKey, LAMPORTS_PER_SOL);
+    await program.provider.connection.confirmTransaction(airdropSig, "finalized");
+
+    const adminAccounts = { config: configPDA, admin: providerWallet.publicKey };
+    const limits = {
+      minWager: new anchor.BN(0),
+      maxWager: new anchor.BN(0),
+      maxUserStake: new anchor.BN(0),
+      maxTotalPool: new anchor.BN(0),
+      maxBucketShareBps: 0,
+    };
+    const oracleGuards = {
+      maxStalenessSecs: new anchor.BN(60),
+      maxConfidenceBps: 0,
+      retryWindowSecs: new anchor.BN(60 * 60),
+    };
+
+    await expectRoleEnforced("updateDefaultLimits", [limits], adminAccounts, "admin", "NotConfigAdmin");
+    await expectRoleEnforced("updateOracleConfig", [mockOracle.programId, mockOracle.programId, oracleGuards], adminAccounts, "admin", "NotConfigAdmin");
+    await expectRoleEnforced("setSignedFeedPublishers", [[pricePublisherKP.publicKey]], adminAccounts, "admin", "NotConfigAdmin");
+    await expectRoleEnforced("updateDisputeConfig", [providerWallet.publicKey, new anchor.BN(0), new anchor.BN(0)], adminAccounts, "admin", "NotConfigAdmin");
+    await expectRoleEnforced("updateRoles", [providerWallet.publicKey, providerWallet.publicKey], adminAccounts, "admin", "NotConfigAdmin");
+    await expectRoleEnforced("updateCreatorBond", [new anchor.BN(0)], adminAccounts, "admin", "NotConfigAdmin");
+    await expectRoleEnforced("updateFeeConfig", [providerWallet.publicKey, 0, 0], adminAccounts, "admin", "NotConfigAdmin");
+    await expectRoleEnforced("updateReferralShare", [0], adminAccounts, "admin", "NotConfigAdmin");
+    await expectRoleEnforced("updateFeeTiers", [[]], adminAccounts, "admin", "NotConfigAdmin");
+    await expectRoleEnforced("startFeeSeason", [], adminAccounts, "admin", "NotConfigAdmin");
+
+    const committeeKP = anchor.web3.Keypair.generate();
+    await expectProgramError(
+      program.rpc.initializeResolverCommittee([feePayerKP.publicKey], 1, {
+        accounts: {
+          resolverCommittee: committeeKP.publicKey,
+          config: configPDA,
+          admin: feePayerKP.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId
+        },
+        signers: [committeeKP, feePayerKP]
+      }),
+      "NotConfigAdmin"
+    );
+
+    // Registering inits the entry before the config is checked, so the imposter has to be able to pay for it
+    const unregisteredSymbol = anchor.web3.Keypair.generate().publicKey.toBase58().slice(0, 8);
+    const registerAccounts = {
+      config: configPDA,
+      asset: await findAssetPDA(unregisteredSymbol),
+      oracleFeed: spyPriceFeed,
+      admin: providerWallet.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    };
+    const registerArgs = [unregisteredSymbol, { equity: {} }, { pyth: {} }, { bucketWidthBps: 100 }, null, ALWAYS_TRADING];
+    await expectProgramError(
+      program.rpc.registerAsset(...registerArgs, { accounts: { ...registerAccounts, admin: feePayerKP.publicKey }, signers: [feePayerKP] }),
+      "NotConfigAdmin"
+    );
+    await expectUnsignedRejected(program.instruction.registerAsset(...registerArgs, { accounts: registerAccounts }), providerWallet.publicKey);
+
+    const assetAdminAccounts = { config: configPDA, asset: spyAssetPDA, admin: providerWallet.publicKey };
+    await expectRoleEnforced("updateAsset", [{ equity: {} }, { bucketWidthBps: 100 }, limits, ALWAYS_TRADING], assetAdminAccounts, "admin", "NotConfigAdmin");
+    await expectRoleEnforced("setAssetOracle", [{ pyth: {} }], { ...assetAdminAccounts, oracleFeed: spyPriceFeed }, "admin", "NotConfigAdmin");
+    await expectRoleEnforced("setAssetEnabled", [false], assetAdminAccounts, "admin", "NotConfigAdmin");
+    await expectRoleEnforced("setAssetHolidays", [[]], assetAdminAccounts, "admin", "NotConfigAdmin");
+    await expectRoleEnforced("deregisterAsset", [], assetAdminAccounts, "admin", "NotConfigAdmin");
+
+    // Creating a series inits it and its jackpot before the config is checked, so it needs an asset without one
+    const seriesSymbol = anchor.web3.Keypair.generate().publicKey.toBase58().slice(0, 8);
+    const seriesPDA = await findSeriesPDA(seriesSymbol);
+    const seriesAccounts = {
+      config: configPDA,
+      asset: await registerAsset(seriesSymbol, spyPriceFeed),
+      series: seriesPDA,
+      jackpot: await findJackpotPDA(seriesPDA),
+      admin: providerWallet.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    };
+    const seriesArgs = [seriesSymbol, new anchor.BN(5 * 1000), new anchor.BN(5 * 1000), new anchor.BN(0), { bucketWidthBps: 100 }, new anchor.BN(Date.now()), 0, { nextRound: {} }];
+    await expectProgramError(
+      program.rpc.createMarketSeries(...seriesArgs, { accounts: { ...seriesAccounts, admin: feePayerKP.publicKey }, signers: [feePayerKP] }),
+      "NotConfigAdmin"
+    );
+    await expectUnsignedRejected(program.instruction.createMarketSeries(...seriesArgs, { accounts: seriesAccounts }), providerWallet.publicKey);
+
+  });
+
+  it('Rejects pausing from any key but the signing pauser and blocks new bets and wagers while paused', async() => {
+
+    const pauserKP = anchor.web3.Keypair.generate();
+    const resolverKP = anchor.web3.Keypair.generate();
+    await program.rpc.updateRoles(resolverKP.publicKey, pauserKP.publicKey, {
+      accounts: { config: configPDA, admin: providerWallet.publicKey }
+    });
+
+    const user1 = await createFundedUserAccount(LAMPORTS_PER_SOL * 1);
+    const betStateKP = await createOracleBetState({ feed: spyPriceFeed, durationMs: 60 * 1000 });
+
+    const pauserAccounts = { config: configPDA, pauser: pauserKP.publicKey };
+    await expectRoleEnforced("setPaused", [true], pauserAccounts, "pauser", "NotPauser");
+
+    await program.rpc.setPaused(true, { accounts: pauserAccounts, signers: [pauserKP] });
+    await expectProgramError(placeWager(betStateKP, user1, 1, LAMPORTS_PER_SOL * 0.1), "BettingPaused");
+    await expectProgramError(createOracleBetState({ feed: spyPriceFeed, durationMs: 60 * 1000 }), "BettingPaused");
+
+    await program.rpc.setPaused(false, { accounts: pauserAccounts, signers: [pauserKP] });
+    await placeWager(betStateKP, user1, 1, LAMPORTS_PER_SOL * 0.1);
+
+    await program.rpc.updateRoles(providerWallet.publicKey, providerWallet.publicKey, {
+      accounts: { config: configPDA, admin: providerWallet.publicKey }
+    });
+
+  });
+
+  it('Rejects closing, deciding, settling and cancelling a bet state from any key but the signing creator or resolver', async() => {
+
+    const resolverKP = anchor.web3.Keypair.generate();
+    await program.rpc.updateRoles(resolverKP.publicKey, providerWallet.publicKey, {
+      accounts: { config: configPDA, admin: providerWallet.publicKey }
+    });
+
+    const betStateKP = await createOracleBetState({ feed: spyPriceFeed, durationMs: 60 * 1000 });
+    const cancelledBetStateKP = await createOracleBetState({ feed: spyPriceFeed, durationMs: 60 * 1000 });
+
+    const resolverAccounts = { betState: betStateKP.publicKey, config: configPDA, resolver: resolverKP.publicKey };
+    await expectRoleEnforced("closeBetState", [], resolverAccounts, "resolver", "NotResolver");
+
+    // The creator can't close their own bet state early
+    await expectProgramError(
+      program.rpc.closeBetState({ accounts: { ...resolverAccounts, resolver: providerWallet.publicKey } }),
+      "NotResolver"
+    );
+    await program.rpc.closeBetState({ accounts: resolverAccounts, signers: [resolverKP] });
+
+    const creatorAccounts = { betState: betStateKP.publicKey, betCreator: providerWallet.publicKey };
+    await expectRoleEnforced("decideBetStateOutcome", [4], resolverAccounts, "resolver", "NotResolver");
+
+    // The creator doesn't hold the resolver role
+    await expectProgramError(
+      program.rpc.decideBetStateOutcome(4, { accounts: { ...resolverAccounts, resolver: providerWallet.publicKey } }),
+      "NotResolver"
+    );
+    await program.rpc.decideBetStateOutcome(4, { accounts: resolverAccounts, signers: [resolverKP] });
+
+    const settleAccounts = { ...creatorAccounts, creatorBond: await findCreatorBondPDA(betStateKP) };
+    await expectRoleEnforced("settleBetState", [], settleAccounts, "betCreator", "NotBetCreator");
+    await program.rpc.settleBetState({ accounts: settleAccounts });
+
+    const cancelAccounts = {
+      betState: cancelledBetStateKP.publicKey,
+      creatorBond: await findCreatorBondPDA(cancelledBetStateKP),
+      bettor: providerWallet.publicKey
+    };
+    await expectRoleEnforced("cancelBetState", [], cancelAccounts, "bettor", "NotBetCreator");
+
+    // A zero-value wager leaves the pool empty, but it still has to be cancelled before the bet state can be
+    const zeroBettor = await createFundedUserAccount(LAMPORTS_PER_SOL * 0.1);
+    const zeroWagerKP = await placeWager(cancelledBetStateKP, zeroBettor, 1, 0);
+    await expectProgramError(program.rpc.cancelBetState({ accounts: cancelAccounts }), "FundsStillInPlay");
+    await program.rpc.cancelWager({
+      accounts: {
+        betState: cancelledBetStateKP.publicKey,
+        wagerDetail: zeroWagerKP.publicKey,
+        userAccount: zeroBettor.userAccountKP.publicKey,
+        bettorPosition: await findBettorPosition(cancelledBetStateKP, zeroBettor.userAccountKP),
+        bettor: zeroBettor.userKP.publicKey,
+      },
+      signers: [zeroBettor.userKP]
+    });
+
+    await program.rpc.cancelBetState({ accounts: cancelAccounts });
+
+    await program.rpc.updateRoles(providerWallet.publicKey, providerWallet.publicKey, {
+      accounts: { config: configPDA, admin: providerWallet.publicKey }
+    });
+
+  });
+
+  it('Holds the creator bond in escrow, returns it on settlement and slashes it to bettors on a creator-fault void', async() => {
+
+    const bond = LAMPORTS_PER_SOL * 0.2;
+    const updateCreatorBond = (creatorBond: number) => program.rpc.updateCreatorBond(new anchor.BN(creatorBond), {
+      accounts: { config: configPDA, admin: providerWallet.publicKey }
+    });
+
+    const user1 = await createFundedUserAccount(LAMPORTS_PER_SOL * 1);
+    const user2 = await createFundedUserAccount(LAMPORTS_PER_SOL * 1);
+
+    // Bet states copy the bond when they are created, so it is only charged for these two
+    await updateCreatorBond(bond);
+    const cleanBetStateKP = await createOracleBetState({ feed: spyPriceFeed, durationMs: 60 * 1000 });
+    const faultedBetStateKP = await createOracleBetState({ feed: spyPriceFeed, durationMs: 60 * 1000 });
+    await updateCreatorBond(0);
+
+    const escrow = await program.account.creatorBond.fetch(await findCreatorBondPDA(faultedBetStateKP));
+    assert.ok(escrow.amount.eq(new anchor.BN(bond)));
+    assert.ok(escrow.creator.equals(providerWallet.publicKey));
+    assert.ok((await program.account.betState.fetch(faultedBetStateKP.publicKey)).creatorBond.eq(new anchor.BN(bond)));
+
+    // Settled cleanly, the bond goes back to the creator with the escrow's rent
+    const cleanEscrowPDA = await findCreatorBondPDA(cleanBetStateKP);
+    const cleanEscrowLamports = await program.provider.connection.getBalance(cleanEscrowPDA);
+    assert.ok(cleanEscrowLamports > bond);
+
+    await program.rpc.closeBetState({
+      accounts: { betState: cleanBetStateKP.publicKey, config: configPDA, resolver: providerWallet.publicKey }
+    });
+    await program.rpc.decideBetStateOutcome(4, {
+      accounts: { betState: cleanBetStateKP.publicKey, config: configPDA, resolver: providerWallet.publicKey }
+    });
+    await program.rpc.settleBetState({
+      accounts: {
+        betState: cleanBetStateKP.publicKey,
+        creatorBond: cleanEscrowPDA,
+        betCreator: providerWallet.publicKey,
+      }
+    });
+    assert.equal(await program.provider.connection.getAccountInfo(cleanEscrowPDA), null);
+
+    // Voided for creator fault, the bond is paid out to the bettors pro-rata to their stake
+    const wager1KP = await placeWager(faultedBetStateKP, user1, 2, LAMPORTS_PER_SOL * 0.25);
+    const wager2KP = await placeWager(faultedBetStateKP, user2, 4, LAMPORTS_PER_SOL * 0.75);
+
+    const voidAccounts = {
+      betState: faultedBetStateKP.publicKey,
+      creatorBond: await findCreatorBondPDA(faultedBetStateKP),
+      config: configPDA,
+      arbitrationAuthority: providerWallet.publicKey,
+    };
+    await expectRoleEnforced("voidForCreatorFault", [], voidAccounts, "arbitrationAuthority", "NotArbitrationAuthority");
+    await program.rpc.voidForCreatorFault({ accounts: voidAccounts });
+    await expectProgramError(program.rpc.voidForCreatorFault({ accounts: voidAccounts }), "BetIsClosedOrSettled");
+
+    const faultedBetState = await program.account.betState.fetch(faultedBetStateKP.publicKey);
+    assert.equal(BET_STATE_STATUSES[faultedBetState.status], "voided");
+    assert.equal(faultedBetState.creatorAtFault, 1);
+    assert.ok(faultedBetState.slashedBond.eq(new anchor.BN(bond)));
+    assert.ok((await program.account.creatorBond.fetch(voidAccounts.creatorBond)).amount.eq(new anchor.BN(0)));
+
+    for (const [user, wagerKP] of [[user1, wager1KP], [user2, wager2KP]] as const) {
+      const betValue = (await program.account.wagerDetail.fetch(wagerKP.publicKey)).betValue;
+      const balanceBeforeRefund = (await program.account.userAccount.fetch(user.userAccountKP.publicKey)).currentBalance;
+
+      await program.rpc.refundVoidedWager({
+        accounts: {
+          betState: faultedBetStateKP.publicKey,
+          wagerDetail: wagerKP.publicKey,
+          userAccount: user.userAccountKP.publicKey,
+          bettorPosition: await findBettorPosition(faultedBetStateKP, user.userAccountKP),
+          bettor: user.userKP.publicKey,
+        },
+        signers: [user.userKP]
+      });
+
+      const bondShare = betValue.mul(new anchor.BN(bond)).div(faultedBetState.staticTotalPool);
+      const balanceAfterRefund = (await program.account.userAccount.fetch(user.userAccountKP.publicKey)).currentBalance;
+      assert.ok(balanceAfterRefund.sub(balanceBeforeRefund).eq(betValue.add(bondShare)));
+    }
+
+    await program.rpc.settleBetState({
+      accounts: {
+        betState: faultedBetStateKP.publicKey,
+        creatorBond: voidAccounts.creatorBond,
+        betCreator: providerWallet.publicKey,
+      }
+    });
+    assert.equal(await program.provider.connection.getAccountInfo(voidAccounts.creatorBond), null);
+
+  });
+
+  it('Splits the fee on each wager between the treasury and the creator, who claims their share once the bet is paid out', async() => {
+
+    const treasuryKP = anchor.web3.Keypair.generate();
+    const airdropSig = await program.provider.connection.requestAirdrop(treasuryKP.publicKey, LAMPORTS_PER_SOL);
+    await program.provider.connection.confirmTransaction(airdropSig, "finalized");
+
+    const updateFeeConfig = (treasury: anchor.web3.PublicKey, protocolFeeBps: number, maxCreatorFeeBps: number) => program.rpc.updateFeeConfig(
+      treasury,
+      protocolFeeBps,
+      maxCreatorFeeBps,
+      { accounts: { config: configPDA, admin: providerWallet.publicKey } }
+    );
+
+    await expectProgramError(updateFeeConfig(treasuryKP.publicKey, 600, 500), "InvalidFeeConfig");
+    await updateFeeConfig(treasuryKP.publicKey, 150, 100);
+
+    await expectProgramError(
+      createOracleBetState({ feed: spyPriceFeed, durationMs: 60 * 1000, creatorFeeBps: 200 }),
+      "InvalidCreatorFee"
+    );
+    const betStateKP = await createOracleBetState({ feed: spyPriceFeed, durationMs: 60 * 1000, creatorFeeBps: 100 });
+
+    const betState = await program.account.betState.fetch(betStateKP.publicKey);
+    assert.equal(betState.protocolFeeBps, 150);
+    assert.equal(betState.creatorFeeBps, 100);
+
+    // 0.5125 SOL in is a 0.5 SOL wager and a 0.0125 SOL fee, split 150 to 100 between the treasury and the creator
+    const user = await createFundedUserAccount(LAMPORTS_PER_SOL * 1);
+    const wagerDetailKP = anchor.web3.Keypair.generate();
+    const placeAccounts = {
+      betState: betStateKP.publicKey,
+      wagerDetail: wagerDetailKP.publicKey,
+      userAccount: user.userAccountKP.publicKey,
+      bettorPosition: await findBettorPosition(betStateKP, user.userAccountKP),
+      config: configPDA,
+      treasury: treasuryKP.publicKey,
+      bettorAccount: user.userKP.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    };
+    await expectProgramError(
+      program.rpc.placeWager(4, new anchor.BN(512500000), {
+        accounts: { ...placeAccounts, treasury: providerWallet.publicKey },
+        signers: [user.userKP, wagerDetailKP]
+      }),
+      "InvalidTreasury"
+    );
+    await program.rpc.placeWager(4, new anchor.BN(512500000), { accounts: placeAccounts, signers: [user.userKP, wagerDetailKP] });
+
+    assert.equal(await program.provider.connection.getBalance(treasuryKP.publicKey), LAMPORTS_PER_SOL + 7500000);
+    assert.ok((await program.account.wagerDetail.fetch(wagerDetailKP.publicKey)).betValue.eq(new anchor.BN(500000000)));
+
+    const betStateAfterWager = await program.account.betState.fetch(betStateKP.publicKey);
+    assert.ok(betStateAfterWager.staticTotalPool.eq(new anchor.BN(500000000)));
+    assert.ok(betStateAfterWager.protocolFees.eq(new anchor.BN(7500000)));
+    assert.ok(betStateAfterWager.creatorFees.eq(new anchor.BN(5000000)));
+
+    const claimCreatorFees = () => program.rpc.claimCreatorFees({
+      accounts: { betState: betStateKP.publicKey, betCreator: providerWallet.publicKey }
+    });
+    await expectProgramError(claimCreatorFees(), "BetStillOpen");
+
+    await program.rpc.closeBetState({
+      accounts: { betState: betStateKP.publicKey, config: configPDA, resolver: providerWallet.publicKey }
+    });
+    await program.rpc.decideBetStateOutcome(4, {
+      accounts: { betState: betStateKP.publicKey, config: configPDA, resolver: providerWallet.publicKey }
+    });
+
+    // The winner is still owed their payout
+    await expectProgramError(claimCreatorFees(), "FundsStillInPlay");
+    await claimWinningsFor(betStateKP, user, wagerDetailKP);
+
+    const betStateLamportsBeforeClaim = await program.provider.connection.getBalance(betStateKP.publicKey);
+    await claimCreatorFees();
+    assert.equal(await program.provider.connection.getBalance(betStateKP.publicKey), betStateLamportsBeforeClaim - 5000000);
+    assert.ok((await program.account.betState.fetch(betStateKP.publicKey)).creatorFees.eq(new anchor.BN(0)));
+
+    await program.rpc.settleBetState({
+      accounts: {
+        betState: betStateKP.publicKey,
+        creatorBond: await findCreatorBondPDA(betStateKP),
+        betCreator: providerWallet.publicKey,
+      }
+    });
+
+    await updateFeeConfig(providerWallet.publicKey, 200, 0);
+
+  });
+
+  it('Pays a share of the protocol fee on a referred bettor\'s wagers to their referrer, set once and never in a loop', async() => {
+
+    const referrer = await createFundedUserAccount(LAMPORTS_PER_SOL * 1);
+    const referred = await createFundedUserAccount(LAMPORTS_PER_SOL * 1);
+    const latecomer = await createFundedUserAccount(LAMPORTS_PER_SOL * 1);
+
+    const setReferrer = (user, referrerAccount: anchor.web3.PublicKey) => program.rpc.setReferrer({
+      accounts: {
+        userAccount: user.userAccountKP.publicKey,
+        referrerAccount,
+        accountOwner: user.userKP.publicKey,
+      },
+      signers: [user.userKP]
+    });
+
+    await expectProgramError(setReferrer(referred, referred.userAccountKP.publicKey), "SelfReferral");
+    await setReferrer(referred, referrer.userAccountKP.publicKey);
+    await expectProgramError(setReferrer(referred, latecomer.userAccountKP.publicKey), "ReferrerAlreadySet");
+
+    // The referrer has referred someone, so taking the referred user or anyone below them would close a loop
+    await expectProgramError(setReferrer(referrer, referred.userAccountKP.publicKey), "ReferralLoop");
+    await setReferrer(latecomer, referred.userAccountKP.publicKey);
+    await expectProgramError(setReferrer(referrer, latecomer.userAccountKP.publicKey), "ReferralLoop");
+
+    assert.ok((await program.account.userAccount.fetch(referred.userAccountKP.publicKey)).referrer.equals(referrer.userAccountKP.publicKey));
+    assert.equal((await program.account.userAccount.fetch(referrer.userAccountKP.publicKey)).referralCount, 1);
+
+    await program.rpc.updateReferralShare(5000, { accounts: { config: configPDA, admin: providerWallet.publicKey } });
+
+    const betStateKP = await createOracleBetState({ feed: spyPriceFeed, durationMs: 60 * 1000 });
+    const wagerDetailKP = anchor.web3.Keypair.generate();
+    const placeReferredWager = async (remainingAccounts) => program.rpc.placeWager(4, new anchor.BN(510000000), {
+      accounts: {
+        betState: betStateKP.publicKey,
+        wagerDetail: wagerDetailKP.publicKey,
+        userAccount: referred.userAccountKP.publicKey,
+        bettorPosition: await findBettorPosition(betStateKP, referred.userAccountKP),
+        config: configPDA,
+        treasury: providerWallet.publicKey,
+        bettorAccount: referred.userKP.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId
+      },
+      remainingAccounts,
+      signers: [referred.userKP, wagerDetailKP]
+    });
+
+    await expectProgramError(placeReferredWager([]), "ReferrerAccountMismatch");
+    await expectProgramError(
+      placeReferredWager([{ pubkey: latecomer.userAccountKP.publicKey, isWritable: true, isSigner: false }]),
+      "ReferrerAccountMismatch"
+    );
+
+    const referrerBeforeWager = await program.account.userAccount.fetch(referrer.userAccountKP.publicKey);
+    const referrerLamportsBeforeWager = await program.provider.connection.getBalance(referrer.userAccountKP.publicKey);
+    await placeReferredWager([{ pubkey: referrer.userAccountKP.publicKey, isWritable: true, isSigner: false }]);
+
+    // 0.51 SOL in is a 0.5 SOL wager and a 0.01 SOL protocol fee, half of which goes to the referrer
+    const referrerAfterWager = await program.account.userAccount.fetch(referrer.userAccountKP.publicKey);
+    assert.ok(referrerAfterWager.currentBalance.sub(referrerBeforeWager.currentBalance).eq(new anchor.BN(5000000)));
+    assert.ok(referrerAfterWager.referralEarnings.eq(new anchor.BN(5000000)));
+    assert.ok(referrerAfterWager.referralVolume.eq(new anchor.BN(500000000)));
+    assert.equal(await program.provider.connection.getBalance(referrer.userAccountKP.publicKey), referrerLamportsBeforeWager + 5000000);
+    assert.ok((await program.account.betState.fetch(betStateKP.publicKey)).protocolFees.eq(new anchor.BN(5000000)));
+
+    await program.rpc.updateReferralShare(0, { accounts: { config: configPDA, admin: providerWallet.publicKey } });
+
+  });
+
+  it('Charges the protocol fee of the tier a bettor\'s volume this season reaches and quotes it before the wager', async() => {
+
+    const adminAccounts = { accounts: { config: configPDA, admin: providerWallet.publicKey } };
+    const updateFeeTiers = (tiers) => program.rpc.updateFeeTiers(
+      tiers.map(([minVolume, protocolFeeBps]) => ({ minVolume: new anchor.BN(minVolume), protocolFeeBps })),
+      adminAccounts
+    );
+
+    await expectProgramError(updateFeeTiers([[LAMPORTS_PER_SOL, 100], [0, 200]]), "InvalidFeeTiers");
+    await expectProgramError(updateFeeTiers([[0, 2000]]), "InvalidFeeTiers");
+    await updateFeeTiers([[0, 200], [LAMPORTS_PER_SOL, 100]]);
+    await program.rpc.startFeeSeason(adminAccounts);
+
+    const user = await createFundedUserAccount(LAMPORTS_PER_SOL * 2);
+    const betStateKP = await createOracleBetState({ feed: spyPriceFeed, durationMs: 60 * 1000 });
+
+    const quoteWager = async (lamports: number) => (await program.simulate.quoteWager(new anchor.BN(lamports), {
+      accounts: {
+        betState: betStateKP.publicKey,
+        userAccount: user.userAccountKP.publicKey,
+        config: configPDA,
+      }
+    })).events[0].data;
+
+    const firstQuote = await quoteWager(1020000000);
+    assert.equal(firstQuote.protocolFeeBps, 200);
+    assert.ok(firstQuote.wager.eq(new anchor.BN(LAMPORTS_PER_SOL)));
+    assert.ok(firstQuote.seasonVolume.eq(new anchor.BN(0)));
+
+    await placeWager(betStateKP, user, 4, LAMPORTS_PER_SOL);
+    assert.ok((await program.account.userAccount.fetch(user.userAccountKP.publicKey)).seasonVolume.eq(new anchor.BN(LAMPORTS_PER_SOL)));
+
+    // A full SOL wagered this season reaches the 1% tier
+    const secondQuote = await quoteWager(505000000);
+    assert.equal(secondQuote.protocolFeeBps, 100);
+    assert.ok(secondQuote.wager.eq(new anchor.BN(500000000)));
+    assert.ok(secondQuote.protocolFee.eq(new anchor.BN(5000000)));
+    assert.ok(secondQuote.seasonVolume.eq(new anchor.BN(LAMPORTS_PER_SOL)));
+
+    const wagerDetailKP = anchor.web3.Keypair.generate();
+    await program.rpc.placeWager(4, new anchor.BN(505000000), {
+      accounts: {
+        betState: betStateKP.publicKey,
+        wagerDetail: wagerDetailKP.publicKey,
+        userAccount: user.userAccountKP.publicKey,
+        bettorPosition: await findBettorPosition(betStateKP, user.userAccountKP),
+        config: configPDA,
+        treasury: providerWallet.publicKey,
+        bettorAccount: user.userKP.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId
+      },
+      signers: [user.userKP, wagerDetailKP]
+    });
+
+    assert.ok((await program.account.wagerDetail.fetch(wagerDetailKP.publicKey)).betValue.eq(new anchor.BN(500000000)));
+    assert.ok((await program.account.betState.fetch(betStateKP.publicKey)).protocolFees.eq(new anchor.BN(25000000)));
+
+    // A new season starts everyone back at the base tier
+    await program.rpc.startFeeSeason(adminAccounts);
+    const newSeasonQuote = await quoteWager(505000000);
+    assert.equal(newSeasonQuote.protocolFeeBps, 200);
+    assert.ok(newSeasonQuote.seasonVolume.eq(new anchor.BN(0)));
+
+    await updateFeeTiers([]);
+
+  });
+
+  // Compute units the program consumed in a transaction, read from its logs
+  const computeUnitsUsed = async (signature: string) => {
+    await program.provider.connection.confirmTransaction(signature, "confirmed");
+    const transaction = await program.provider.connection.getTransaction(signature, { commitment: "confirmed" });
+    const consumedLog = new RegExp(`^Program ${program.programId.toBase58()} consumed (\\d+) of`);
+    const consumed = transaction.meta.logMessages.map(log => log.match(consumedLog)).find(match => match !== null);
+    return Number(consumed[1]);
+  };
+
+  // Benchmarks the compute units of the instructions every bettor goes through against the numbers they used on the
+  // Borsh bet state layout, kept in tests/fixtures/compute-units-baseline.json. This test can't be run on that layout
+  // as is, its setup relies on the asset registry and market types, so the baseline is measured on the last commit
+  // before zero-copy bet states. Anything that got more expensive than its baseline fails the test. Set CU_REPORT to a
+  // file to write this run's numbers.
+  it('Benchmarks the compute units used by placing wagers and claiming winnings', async() => {
+
+    const oracleGuards = {
+      maxStalenessSecs: new anchor.BN(60),
+      maxConfidenceBps: 100,
+      retryWindowSecs: new anchor.BN(60 * 60),
+    };
+
+    const user1 = await createFundedUserAccount(LAMPORTS_PER_SOL * 1);
+    const user2 = await createFundedUserAccount(LAMPORTS_PER_SOL * 1);
+
+    const feed = await createMockPythFeed(mockOracle, { price: 72545000000, conf: 1000000, expo: -8 });
+    const betStateKP = await createOracleBetState({ feed, durationMs: 8 * 1000, oracleGuards });
+
+    const placeWagerSignature = async (user, betRange: number, lamports: number) => {
+      const wagerDetailKP = anchor.web3.Keypair.generate();
+      const signature = await program.rpc.placeWager(betRange, new anchor.BN(Math.floor(lamports * JUICED_BETS_TAKE_RATE)), {
+        accounts: {
+          betState: betStateKP.publicKey,
+          wagerDetail: wagerDetailKP.publicKey,
+          userAccount: user.userAccountKP.publicKey,
+          bettorPosition: await findBettorPosition(betStateKP, user.userAccountKP),
+          config: configPDA,
+          treasury: providerWallet.publicKey,
+          bettorAccount: user.userKP.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId
+        },
+        signers: [user.userKP, wagerDetailKP]
+      });
+      return { wagerDetailKP, signature };
+    };
+
+    // A bettor's first wager on a bet state also creates their position
+    const firstWager = await placeWagerSignature(user1, 4, LAMPORTS_PER_SOL * 0.2);
+    const secondWager = await placeWagerSignature(user1, 4, LAMPORTS_PER_SOL * 0.2);
+    await placeWagerSignature(user2, 2, LAMPORTS_PER_SOL * 0.2);
+
+    await sleep(10 * 1000);
+
+    await setMockPythPrice(mockOracle, feed, { price: 72900000000, conf: 1000000, expo: -8 });
+    await resolveBetState(betStateKP, feed);
+
+    const report = {
+      placeWager: await computeUnitsUsed(firstWager.signature),
+      placeWagerExistingPosition: await computeUnitsUsed(secondWager.signature),
+      claimWinnings: await computeUnitsUsed(await claimWinningsFor(betStateKP, user1, firstWager.wagerDetailKP)),
+    };
+
+    if (process.env.CU_REPORT) {
+      fs.writeFileSync(process.env.CU_REPORT, JSON.stringify(report, null, 2));
+    }
+
+    const baselinePath = "tests/fixtures/compute-units-baseline.json";
+    assert.ok(fs.existsSync(baselinePath), `${baselinePath} has to hold the compute units measured on the Borsh layout`);
+    const baseline = JSON.parse(fs.readFileSync(baselinePath, "utf8"));
+    for (const [instruction, computeUnits] of Object.entries(report)) {
+      assert.ok(baseline[instruction] !== undefined, `${instruction} has no baseline`);
+      console.log(`${instruction}: ${computeUnits} compute units, ${baseline[instruction]} before (${computeUnits - baseline[instruction]})`);
+      assert.ok(computeUnits <= baseline[instruction], `${instruction} uses more compute units than its baseline`);
+    }
+
+  });
+
+  it('Migrates version 0 bet states, wager details and user accounts loaded from fixtures to the current layout', async() => {
+
+    // Loaded into the test validator from tests/fixtures, written with the layouts from before accounts were versioned
+    const legacyBetState = new anchor.web3.PublicKey("G9AbszgKXSLPquu2HPtxV5FR6gSDGjW3UG6B9k961HFS");
+    const legacyWagerDetail = new anchor.web3.PublicKey("5WiJBsrBweoZ1WjYi9keohmaB2Y7KS5Cx6h8Y4G67dEV");
+    const legacyUserAccount = new anchor.web3.PublicKey("12KsNCKhMyUELNSpoLyjxmLMiVUZk8ayHkGGKV36eYSH");
+    const legacyAccountOwner = new anchor.web3.PublicKey("CYzK7NenUEuCuSeVkXhmCAjgtHmBERxnTZZqoi2xmqcy");
+
+    const migrationAccounts = (account: anchor.web3.PublicKey) => ({
+      account,
+      payer: providerWallet.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    });
+    const accountSize = async (account: anchor.web3.PublicKey) => (await program.provider.connection.getAccountInfo(account)).data.length;
+
+    assert.equal(await accountSize(legacyBetState), 257 + 8);
+    assert.equal(await accountSize(legacyWagerDetail), 105 + 8);
+    assert.equal(await accountSize(legacyUserAccount), 220 + 8);
+
+    await expectProgramError(program.rpc.migrateUserAccount({ accounts: migrationAccounts(legacyBetState) }), "InvalidMigrationAccount");
+    await program.rpc.migrateUserAccount({ accounts: migrationAccounts(legacyUserAccount) });
+
+    const userAccount = await program.account.userAccount.fetch(legacyUserAccount);
+    assert.equal(userAccount.version, 1);
+    assert.ok(userAccount.accountOwner.equals(legacyAccountOwner));
+    assert.ok(userAccount.wins.eq(new anchor.BN(3)));
+    assert.ok(userAccount.losses.eq(new anchor.BN(2)));
+    assert.ok(userAccount.activeWagers[0].equals(legacyWagerDetail));
+    assert.ok(userAccount.currentBalance.eq(new anchor.BN(250000000)));
+    assert.ok(userAccount.referrer.equals(anchor.web3.PublicKey.default));
+
+    // A wager detail comes across with its bet state and user account, the bet state has to be migrated first
+    const bettorPosition = (await anchor.web3.PublicKey.findProgramAddress(
+      [Buffer.from("position"), legacyBetState.toBuffer(), legacyUserAccount.toBuffer()],
+      program.programId
+    ))[0];
+    const wagerMigrationAccounts = { betState: legacyBetState, userAccount: legacyUserAccount, bettorPosition, ...migrationAccounts(legacyWagerDetail) };
+    await expectProgramError(program.rpc.migrateWagerDetail({ accounts: wagerMigrationAccounts }), "BetStateNotMigrated");
+
+    const creatorBond = await findCreatorBondPDA({ publicKey: legacyBetState });
+    const betStateMigrationAccounts = { ...migrationAccounts(legacyBetState), creatorBond };
+    await program.rpc.migrateBetState({ accounts: betStateMigrationAccounts });
+    await expectProgramError(program.rpc.migrateBetState({ accounts: betStateMigrationAccounts }), "AccountAlreadyMigrated");
+    assert.equal(await accountSize(legacyBetState), 896 + 8);
+
+    const betState = await program.account.betState.fetch(legacyBetState);
+    assert.equal(betState.version, 1);
+    assert.equal(decodeSymbol(betState.symbol), "SPY");
+    assert.equal(BET_STATE_STATUSES[betState.status], "closed");
+    assert.equal(BET_RANGES[betState.winningBetRange], "notAvailable");
+    assert.ok(betState.staticTotalPool.eq(new anchor.BN(500000000)));
+    assert.ok(betState.pools[4].eq(new anchor.BN(500000000)));
+    assert.ok(betState.snapshotMantissa.eq(new anchor.BN(725450)));
+    assert.equal(betState.snapshotExpo, -3);
+    assert.equal(betState.openWagers, 0);
+    assert.ok(betState.unmigratedStake.eq(new anchor.BN(500000000)));
+
+    // Version 0 bet states predate creator bonds and get an empty escrow so they can be settled, cancelled and ruled on
+    const escrow = await program.account.creatorBond.fetch(creatorBond);
+    assert.ok(escrow.betState.equals(legacyBetState));
+    assert.ok(escrow.creator.equals(betState.creator));
+    assert.ok(escrow.amount.eqn(0));
+
+    await program.rpc.migrateWagerDetail({ accounts: wagerMigrationAccounts });
+
+    const wagerDetail = await program.account.wagerDetail.fetch(legacyWagerDetail);
+    assert.equal(wagerDetail.version, 1);
+    assert.ok(wagerDetail.betState.equals(legacyBetState));
+    assert.ok(wagerDetail.bettor.equals(legacyAccountOwner));
+    assert.ok(wagerDetail.rangeStatus.hasOwnProperty("zeroToPosOne"));
+    assert.ok(wagerDetail.betValue.eq(new anchor.BN(500000000)));
+    const betStateAfterWager = await program.account.betState.fetch(legacyBetState);
+    assert.equal(betStateAfterWager.openWagers, 1);
+    assert.ok(betStateAfterWager.unmigratedStake.eqn(0));
+    const position = await program.account.bettorPosition.fetch(bettorPosition);
+    assert.ok(position.totalStake.eq(new anchor.BN(500000000)));
+    assert.ok(position.bucketStakes[4].eq(new anchor.BN(500000000)));
+
+    // Migrated bet states have no oracle and are decided by the resolver
+    await program.rpc.decideBetStateOutcome(4, {
+      accounts: { betState: legacyBetState, config: configPDA, resolver: providerWallet.publicKey }
+    });
+    assert.equal(BET_RANGES[(await program.account.betState.fetch(legacyBetState)).winningBetRange], "zeroToPosOne");
+
+  });
+
+  it('Keeps a migrated version 0 bet state from being settled until its wagers are migrated', async() => {
+
+    // Already decided when it was written, its creator's key is kept in tests/fixtures so the test can sign for them
+    const legacyBetState = new anchor.web3.PublicKey("BYiA8uVgmzi3yLic1yf76rpBdBCYeFYYiWskQmzjTQsB");
+    const legacyCreatorKP = anchor.web3.Keypair.fromSecretKey(
+      Uint8Array.from(JSON.parse(fs.readFileSync("tests/fixtures/legacy-creator.json", "utf8")))
+    );
+
+    const creatorBond = await findCreatorBondPDA({ publicKey: legacyBetState });
+    await program.rpc.migrateBetState({
+      accounts: {
+        account: legacyBetState,
+        creatorBond,
+        payer: providerWallet.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId
+      }
+    });
+
+    const betState = await program.account.betState.fetch(legacyBetState);
+    assert.ok(betState.creator.equals(legacyCreatorKP.publicKey));
+    assert.equal(BET_RANGES[betState.winningBetRange], "zeroToPosOne");
+    assert.equal(betState.openWagers, 0);
+    assert.ok(betState.unmigratedStake.eq(new anchor.BN(500000000)));
+
+    // None of its wagers are open on the current layout yet, but their stake still is
+    await expectProgramError(
+      program.rpc.settleBetState({
+        accounts: { betState: legacyBetState, creatorBond, betCreator: legacyCreatorKP.publicKey },
+        signers: [legacyCreatorKP]
+      }),
+      "FundsStillInPlay"
+    );
+    assert.ok((await program.provider.connection.getAccountInfo(legacyBetState)) !== null);
+
+  });
+
+  it('Rejects migrating bet states, wager details and user accounts that were created on the current layout', async() => {
+
+    const betStateKP = await createOracleBetState({ feed: spyPriceFeed, durationMs: 60 * 1000 });
+    const user = await createFundedUserAccount(LAMPORTS_PER_SOL * 1);
+    const wagerKP = await placeWager(betStateKP, user, 4, LAMPORTS_PER_SOL * 0.1);
+
+    const migrationAccounts = (account: anchor.web3.PublicKey) => ({
+      account,
+      payer: providerWallet.publicKey,
+      systemProgram: anchor.web3.SystemProgram.programId
+    });
+
+    await expectProgramError(
+      program.rpc.migrateBetState({
+        accounts: { ...migrationAccounts(betStateKP.publicKey), creatorBond: await findCreatorBondPDA(betStateKP) }
+      }),
+      "AccountAlreadyMigrated"
+    );
+    await expectProgramError(
+      program.rpc.migrateWagerDetail({
+        accounts: {
+          betState: betStateKP.publicKey,
+          userAccount: user.userAccountKP.publicKey,
+          bettorPosition: await findBettorPosition(betStateKP, user.userAccountKP),
+          ...migrationAccounts(wagerKP.publicKey)
+        }
+      }),
+      "AccountAlreadyMigrated"
+    );
+    await expectProgramError(program.rpc.migrateUserAccount({ accounts: migrationAccounts(user.userAccountKP.publicKey) }), "AccountAlreadyMigrated");
+
+    // Nothing was touched
+    const betState = await program.account.betState.fetch(betStateKP.publicKey);
+    assert.equal(betState.version, 1);
+    assert.equal(betState.openWagers, 1);
+    assert.ok((await program.account.bettorPosition.fetch(await findBettorPosition(betStateKP, user.userAccountKP))).totalStake.eq(betState.staticTotalPool));
+
+  });
+
 });
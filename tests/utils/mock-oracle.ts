@@ -0,0 +1,170 @@
+import * as anchor from '@project-serum/anchor';
+import { Program } from '@project-serum/anchor';
+import { SystemProgram } from "@solana/web3.js";
+import { MockOracle } from '../../target/types/mock_oracle';
+
+// Helpers to build oracle accounts owned by the mock oracle program, laid out the way the real oracle programs lay them out
+
+export const PYTH_PRICE_ACCOUNT_SIZE = 3312;
+
+export const PYTH_STATUS_UNKNOWN = 0;
+export const PYTH_STATUS_TRADING = 1;
+
+export type MockPythPrice = {
+  price: number,
+  conf: number,
+  expo: number,
+  status?: number,
+  publishTime?: number,
+};
+
+export const nowInSeconds = () => Math.floor(Date.now() / 1000);
+
+const i64 = (value: number) => new anchor.BN(value).toTwos(64).toArrayLike(Buffer, 'le', 8);
+const u64 = (value: number) => new anchor.BN(value).toArrayLike(Buffer, 'le', 8);
+
+export const encodePythPrice = ({ price, conf, expo, status = PYTH_STATUS_TRADING, publishTime = nowInSeconds() }: MockPythPrice) => {
+  const data = Buffer.alloc(240);
+
+  data.writeUInt32LE(0xa1b2c3d4, 0);            // magic
+  data.writeUInt32LE(2, 4);                     // version
+  data.writeUInt32LE(3, 8);                     // account type: price
+  data.writeUInt32LE(PYTH_PRICE_ACCOUNT_SIZE, 12);
+  data.writeUInt32LE(1, 16);                    // price type: price
+  data.writeInt32LE(expo, 20);
+  i64(publishTime).copy(data, 96);
+  i64(price).copy(data, 208);
+  u64(conf).copy(data, 216);
+  data.writeUInt32LE(status, 224);
+
+  return data;
+};
+
+export const createMockPythFeed = async (mockOracle: Program<MockOracle>, price: MockPythPrice) => {
+  const feedKP = anchor.web3.Keypair.generate();
+  const provider = mockOracle.provider;
+
+  await mockOracle.rpc.write(new anchor.BN(0), encodePythPrice(price), {
+    accounts: {
+      oracleAccount: feedKP.publicKey,
+    },
+    preInstructions: [
+      SystemProgram.createAccount({
+        fromPubkey: provider.wallet.publicKey,
+        newAccountPubkey: feedKP.publicKey,
+        space: PYTH_PRICE_ACCOUNT_SIZE,
+        lamports: await provider.connection.getMinimumBalanceForRentExemption(PYTH_PRICE_ACCOUNT_SIZE),
+        programId: mockOracle.programId,
+      })
+    ],
+    signers: [feedKP]
+  });
+
+  return feedKP.publicKey;
+};
+
+export const setMockPythPrice = async (mockOracle: Program<MockOracle>, feed: anchor.web3.PublicKey, price: MockPythPrice) => {
+  await mockOracle.rpc.write(new anchor.BN(0), encodePythPrice(price), {
+    accounts: {
+      oracleAccount: feed,
+    }
+  });
+};
+
+export const SWITCHBOARD_AGGREGATOR_ACCOUNT_SIZE = 3851;
+
+export type MockSwitchboardRound = {
+  mantissa: number,
+  scale: number,
+  stdDeviationMantissa?: number,
+  numSuccess?: number,
+  minOracleResults?: number,
+  roundOpenTimestamp?: number,
+};
+
+const i128 = (value: number) => new anchor.BN(value).toTwos(128).toArrayLike(Buffer, 'le', 16);
+
+export const encodeSwitchboardAggregator = ({
+  mantissa,
+  scale,
+  stdDeviationMantissa = 0,
+  numSuccess = 3,
+  minOracleResults = 3,
+  roundOpenTimestamp = nowInSeconds(),
+}: MockSwitchboardRound) => {
+  // 8 byte discriminator + AggregatorAccountData up to the end of latestConfirmedRound.stdDeviation
+  const data = Buffer.alloc(406);
+
+  data.writeUInt32LE(minOracleResults, 236);
+  data.writeUInt32LE(numSuccess, 341);           // latestConfirmedRound.numSuccess
+  i64(roundOpenTimestamp).copy(data, 358);       // latestConfirmedRound.roundOpenTimestamp
+  i128(mantissa).copy(data, 366);                // latestConfirmedRound.result
+  data.writeUInt32LE(scale, 382);
+  i128(stdDeviationMantissa).copy(data, 386);    // latestConfirmedRound.stdDeviation
+  data.writeUInt32LE(scale, 402);
+
+  return data;
+};
+
+export const createMockSwitchboardFeed = async (mockOracle: Program<MockOracle>, round: MockSwitchboardRound) => {
+  const feedKP = anchor.web3.Keypair.generate();
+  const provider = mockOracle.provider;
+
+  await mockOracle.rpc.write(new anchor.BN(0), encodeSwitchboardAggregator(round), {
+    accounts: {
+      oracleAccount: feedKP.publicKey,
+    },
+    preInstructions: [
+      SystemProgram.createAccount({
+        fromPubkey: provider.wallet.publicKey,
+        newAccountPubkey: feedKP.publicKey,
+        space: SWITCHBOARD_AGGREGATOR_ACCOUNT_SIZE,
+        lamports: await provider.connection.getMinimumBalanceForRentExemption(SWITCHBOARD_AGGREGATOR_ACCOUNT_SIZE),
+        programId: mockOracle.programId,
+      })
+    ],
+    signers: [feedKP]
+  });
+
+  return feedKP.publicKey;
+};
+
+export const setMockSwitchboardRound = async (mockOracle: Program<MockOracle>, feed: anchor.web3.PublicKey, round: MockSwitchboardRound) => {
+  await mockOracle.rpc.write(new anchor.BN(0), encodeSwitchboardAggregator(round), {
+    accounts: {
+      oracleAccount: feed,
+    }
+  });
+};
+
+export type SignedPrice = {
+  feed: anchor.web3.PublicKey,
+  price: number,
+  conf: number,
+  expo: number,
+  publishTime?: number,
+  trading?: boolean,
+  // Publish time of the publisher's previous message on the feed
+  prevPublishTime?: number,
+};
+
+// Borsh layout of the program's SignedPriceMessage
+export const encodeSignedPrice = ({ feed, price, conf, expo, publishTime = nowInSeconds(), trading = true, prevPublishTime = publishTime - 1 }: SignedPrice) => {
+  const data = Buffer.alloc(69);
+
+  feed.toBuffer().copy(data, 0);
+  i64(price).copy(data, 32);
+  u64(conf).copy(data, 40);
+  data.writeInt32LE(expo, 48);
+  i64(publishTime).copy(data, 52);
+  data.writeUInt8(trading ? 1 : 0, 60);
+  i64(prevPublishTime).copy(data, 61);
+
+  return data;
+};
+
+export const signedPriceInstruction = (publisherKP: anchor.web3.Keypair, price: SignedPrice) =>
+  anchor.web3.Ed25519Program.createInstructionWithPrivateKey({
+    privateKey: publisherKP.secretKey,
+    message: encodeSignedPrice(price),
+  });